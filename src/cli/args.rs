@@ -0,0 +1,188 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use crate::cli::cli_errors::CliError;
+use crate::matrix::vector3d::Vector3d;
+
+pub const USAGE: &str = "\
+Usage: qrender [OPTIONS] <MODEL>
+
+Render a Wavefront OBJ model to an image without opening a window.
+
+Options:
+  -o, --output <PATH>    Output image, png, jpg, bmp, tga, ppm or pfm by extension [default: output.png]
+  -s, --size <WxH>       Resolution in pixels [default: 512x512]
+      --eye <X,Y,Z>      Camera position [default: in front of the model, far enough to see all of it]
+      --target <X,Y,Z>   Point the camera looks at [default: center of the model]
+      --fov <DEGREES>    Vertical field of view [default: 45]
+      --rotate <X,Y,Z>   Model rotation in degrees around X, then Y, then Z [default: 0,0,0]
+  -m, --mode <MODE>      wireframe, flat or shaded [default: shaded]
+  -h, --help             Print this help
+
+Exit codes: 0 on success, 1 when the model cannot be loaded, rendered or saved, 2 for an invalid command line.";
+
+/// How the CLI draws the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderMode {
+    /// The visible edges of the triangles, hidden edges are removed with a depth pre-pass
+    Wireframe,
+    /// Blinn-Phong lighting with one normal per face
+    Flat,
+    /// Blinn-Phong lighting with interpolated vertex normals, computed from the faces when the file has none
+    Shaded,
+}
+
+impl FromStr for RenderMode {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<RenderMode, CliError> {
+        match s {
+            "wireframe" => Ok(RenderMode::Wireframe),
+            "flat" => Ok(RenderMode::Flat),
+            "shaded" => Ok(RenderMode::Shaded),
+            _ => Err(invalid_value("--mode", s)),
+        }
+    }
+}
+
+/// Everything needed to render one image
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub model:    PathBuf,
+    pub output:   PathBuf,
+    pub width:    usize,
+    pub height:   usize,
+    /// None places the camera on the +Z side of the target at a distance that fits the whole model
+    pub eye:      Option<Vector3d<f32>>,
+    /// None looks at the center of the bounding box of the rotated model
+    pub target:   Option<Vector3d<f32>>,
+    /// Vertical field of view in degrees
+    pub fov:      f32,
+    /// Euler angles in degrees, applied around X, then Y, then Z
+    pub rotation: Vector3d<f32>,
+    pub mode:     RenderMode,
+}
+
+impl Options {
+    /// The defaults of every option for a model file
+    pub fn new<P: Into<PathBuf>>(model: P) -> Options {
+        Options {
+            model: model.into(),
+            output: PathBuf::from("output.png"),
+            width: 512,
+            height: 512,
+            eye: None,
+            target: None,
+            fov: 45.0_f32,
+            rotation: Vector3d::fill(0.0_f32),
+            mode: RenderMode::Shaded,
+        }
+    }
+}
+
+/// What the command line asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Help,
+    Render(Options),
+}
+
+impl Command {
+    /** Parse the command line arguments without the program name. Options take their value as the next argument or after =,
+      -- ends the options. Example
+    ```rust
+    use QRender::cli::args::{Command, RenderMode};
+
+    let args = ["teapot.obj", "--size=320x240", "-m", "wireframe"].iter().map(|s| s.to_string());
+    if let Command::Render(options) = Command::parse(args).unwrap() {
+        let size = (options.width, options.height, options.mode);
+    }
+    ```
+
+    output size:
+    ```text
+    (320, 240, Wireframe)
+    ```
+     */
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, CliError> {
+        let mut args = args.into_iter();
+        let mut options = Options::new("");
+        let mut model: Option<String> = None;
+        let mut options_done = false;
+
+        while let Some(arg) = args.next() {
+            if options_done || !arg.starts_with('-') || arg == "-" {
+                if model.is_some() {
+                    return Err(CliError { err_code: 6005, message: arg });
+                }
+                model = Some(arg);
+                continue;
+            }
+            if arg == "--" {
+                options_done = true;
+                continue;
+            }
+
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            if name == "-h" || name == "--help" {
+                return Ok(Command::Help);
+            }
+            let value = match inline_value.or_else(|| args.next()) {
+                Some(value) => value,
+                None => return Err(CliError { err_code: 6002, message: name }),
+            };
+            match name.as_str() {
+                "-o" | "--output" => options.output = PathBuf::from(value),
+                "-s" | "--size" => {
+                    let (width, height) = parse_size(&value)?;
+                    options.width = width;
+                    options.height = height;
+                }
+                "--eye" => options.eye = Some(parse_vector(&name, &value)?),
+                "--target" => options.target = Some(parse_vector(&name, &value)?),
+                "--fov" => {
+                    options.fov = match value.parse::<f32>() {
+                        Ok(fov) if fov > 0.0_f32 && fov < 180.0_f32 => fov,
+                        _ => return Err(invalid_value(&name, &value)),
+                    }
+                }
+                "--rotate" => options.rotation = parse_vector(&name, &value)?,
+                "-m" | "--mode" => options.mode = value.parse()?,
+                _ => return Err(CliError { err_code: 6001, message: name }),
+            }
+        }
+
+        match model {
+            None => Err(CliError { err_code: 6004, message: "run qrender --help for the usage".to_string() }),
+            Some(model) => {
+                options.model = PathBuf::from(model);
+                Ok(Command::Render(options))
+            }
+        }
+    }
+}
+
+fn invalid_value(name: &str, value: &str) -> CliError {
+    CliError { err_code: 6003, message: format!("{} {}", name, value) }
+}
+
+/// WxH with both sides at least 1
+fn parse_size(value: &str) -> Result<(usize, usize), CliError> {
+    let size = value.split_once(['x', 'X'])
+        .and_then(|(w, h)| Some((w.trim().parse::<usize>().ok()?, h.trim().parse::<usize>().ok()?)));
+    match size {
+        Some((width, height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(invalid_value("--size", value)),
+    }
+}
+
+/// Three finite numbers separated by commas
+fn parse_vector(name: &str, value: &str) -> Result<Vector3d<f32>, CliError> {
+    let items: Vec<f32> = value.split(',').filter_map(|s| s.trim().parse::<f32>().ok()).filter(|v| v.is_finite()).collect();
+    if items.len() != 3 || value.split(',').count() != 3 {
+        return Err(invalid_value(name, value));
+    }
+    Ok(Vector3d::new(items[0], items[1], items[2]))
+}
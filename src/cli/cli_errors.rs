@@ -0,0 +1,38 @@
+use std::{error, fmt};
+
+/// Error raised by the qrender command line, message holds the argument or file at fault
+#[derive(Debug)]
+pub struct CliError {
+    pub err_code: usize,
+    pub message: String,
+}
+
+impl CliError {
+    /// Process exit code of the error, 2 for a malformed command line and 1 when loading, rendering or saving failed
+    pub fn exit_code(&self) -> i32 {
+        match self.err_code {
+            6001..=6005 => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let err_msg = match self.err_code {
+            6001 => "Unknown option",
+            6002 => "Missing option value",
+            6003 => "Invalid option value",
+            6004 => "Missing model file",
+            6005 => "Unexpected argument",
+            6006 => "Failed to load the model",
+            6007 => "Failed to render the model",
+            6008 => "Failed to save the image",
+            _ => "Sorry, something is wrong! Please Try Again!",
+        };
+
+        write!(f, "{}: {}", err_msg, self.message)
+    }
+}
+
+impl error::Error for CliError {}
@@ -0,0 +1,3 @@
+pub mod args;
+pub mod render;
+pub mod cli_errors;
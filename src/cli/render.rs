@@ -0,0 +1,104 @@
+use crate::cli::args::{Options, RenderMode};
+use crate::cli::cli_errors::CliError;
+use crate::geometry::mesh::{Aabb, Mesh};
+use crate::geometry::obj::{ObjMesh, ObjModel};
+use crate::matrix::matrix4d::Matrix4d;
+use crate::matrix::transform;
+use crate::matrix::vector3d::Vector3d;
+use crate::rasterizer::camera::Camera;
+use crate::rasterizer::light::{BlinnPhongFragmentShader, Light, Material};
+use crate::rasterizer::rasterizer::{IndexBufferId, PositionBufferId, Rasterizer};
+use crate::rasterizer::rasterizer_errors::RasterizerError;
+
+/// Render the model of options and save it to options.output
+pub fn run(options: &Options) -> Result<(), CliError> {
+    let raster = render(options)?;
+    raster.save(&options.output).map_err(|e| CliError { err_code: 6008, message: e.to_string() })
+}
+
+/** Load the model of options and render it into a new rasterizer of options.width x options.height on a black background
+  The camera, the light and the near and far planes follow the bounding sphere of the rotated model,
+  the light shines from behind the camera, slightly from the upper left.
+ */
+pub fn render(options: &Options) -> Result<Rasterizer, CliError> {
+    let model = ObjModel::load(&options.model).map_err(|e| CliError { err_code: 6006, message: e.to_string() })?;
+    let rotation = transform::rotate_z(options.rotation.z) * transform::rotate_y(options.rotation.y) * transform::rotate_x(options.rotation.x);
+    let (center, radius) = bounding_sphere(&model, &rotation)
+        .ok_or_else(|| CliError { err_code: 6006, message: format!("{} has no faces", options.model.display()) })?;
+
+    let aspect_ratio = options.width as f32 / options.height as f32;
+    let target = options.target.unwrap_or(center);
+    let eye = options.eye.unwrap_or_else(|| {
+        // Fit the sphere into the narrower of the two fields of view
+        let half_fov = (options.fov.to_radians() / 2.0_f32).tan();
+        let half_fov = half_fov.min(half_fov * aspect_ratio).atan();
+        target.add(Vector3d::new(0.0_f32, 0.0_f32, radius / half_fov.sin() * 1.05_f32))
+    });
+    if target.sub(eye).norm() < 1e-6_f32 {
+        return Err(CliError { err_code: 6003, message: "--eye and --target are the same point".to_string() });
+    }
+
+    let distance = eye.sub(center).norm();
+    let far = distance + radius * 1.5_f32;
+    let near = (distance - radius * 1.5_f32).max(far * 1e-3_f32);
+    let mut camera = Camera::perspective(options.fov, aspect_ratio, near, far);
+    camera.position = eye;
+    camera.look_at(target, Vector3d::new(0.0_f32, 1.0_f32, 0.0_f32));
+    let (forward, right, camera_up) = (camera.forward(), camera.right(), camera.up());
+
+    let mut raster = Rasterizer::new(options.width, options.height);
+    raster.set_model(rotation);
+    raster.set_camera(&camera);
+
+    let light_direction = forward.sub(Vector3d::mul_item(camera_up, 0.5_f32)).add(Vector3d::mul_item(right, 0.3_f32));
+    let lights = vec![Light::directional(light_direction, Vector3d::fill(1.0_f32))];
+    let error = |e: RasterizerError| CliError { err_code: 6007, message: e.to_string() };
+
+    if options.mode == RenderMode::Wireframe {
+        let meshes: Vec<(PositionBufferId, IndexBufferId)> = model.meshes.iter().map(|mesh| raster.load_obj_mesh(mesh)).collect();
+        raster.set_depth_only(true);
+        for (pos_id, ind_id) in meshes.iter() {
+            raster.draw_triangle(*pos_id, *ind_id, Vector3d::fill(0.0_f32)).map_err(error)?;
+        }
+        raster.set_depth_only(false);
+        for (pos_id, ind_id) in meshes.iter() {
+            raster.draw_wireframe(*pos_id, *ind_id, Vector3d::fill(255.0_f32)).map_err(error)?;
+        }
+        return Ok(raster);
+    }
+
+    for mesh in model.meshes.iter() {
+        let material = mesh.material.as_ref()
+            .and_then(|name| model.materials.get(name))
+            .map_or_else(Material::default, Material::from);
+        raster.set_fragment_shader(BlinnPhongFragmentShader::new(material, lights.clone(), eye));
+        let (pos_id, ind_id) = load_mesh(&mut raster, mesh, options.mode)?;
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0_f32)).map_err(error)?;
+    }
+    Ok(raster)
+}
+
+/// Center and radius of a sphere around the faces of every mesh after the rotation, None without faces
+fn bounding_sphere(model: &ObjModel, rotation: &Matrix4d<f32>) -> Option<(Vector3d<f32>, f32)> {
+    let points: Vec<Vector3d<f32>> = model.meshes.iter()
+        .filter(|mesh| !mesh.indices.is_empty())
+        .flat_map(|mesh| mesh.positions.iter())
+        .map(|p| rotation.product_with_vector4d(p.to_vector4d(1.0_f32)).head3())
+        .collect();
+    let center = Aabb::from_points(points.iter())?.center();
+    let radius = points.iter().map(|p| p.sub(center).norm()).fold(0.0_f32, f32::max);
+    Some((center, radius.max(1e-3_f32)))
+}
+
+/// Flat shading gives every face its own vertices with the face normal, smooth shading keeps the normals of the file
+/// or averages the normals of the adjacent faces when the file has none
+fn load_mesh(raster: &mut Rasterizer, mesh: &ObjMesh, mode: RenderMode) -> Result<(PositionBufferId, IndexBufferId), CliError> {
+    let mut mesh = Mesh::from(mesh);
+    let result = match mode {
+        RenderMode::Flat => mesh.compute_flat_normals(),
+        _ if mesh.normals.is_empty() => mesh.compute_smooth_normals(),
+        _ => Ok(()),
+    };
+    result.map_err(|e| CliError { err_code: 6006, message: e.to_string() })?;
+    Ok(raster.load_mesh(&mesh))
+}
@@ -1,4 +1,4 @@
-use std::{fmt};
+use std::{error, fmt};
 
 #[derive(Debug)]
 pub struct TriangleError {
@@ -19,4 +19,51 @@ impl fmt::Display for TriangleError {
 
         write!(f, "{}", err_msg)
     }
-}
\ No newline at end of file
+}
+
+/// Error raised while reading a Wavefront OBJ or MTL file, message holds the file name or line number at fault
+#[derive(Debug)]
+pub struct ObjError {
+    pub err_code: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let err_msg = match self.err_code {
+            4001 => "Failed to read the file",
+            4002 => "Invalid number",
+            4003 => "Vertex index out of range",
+            4004 => "A face needs at least 3 vertices",
+            4005 => "Missing statement argument",
+            _ => "Sorry, something is wrong! Please Try Again!",
+        };
+
+        write!(f, "{}: {}", err_msg, self.message)
+    }
+}
+
+impl error::Error for ObjError {}
+
+/// Error raised by the operations of a Mesh, message holds the index or attribute at fault
+#[derive(Debug)]
+pub struct MeshError {
+    pub err_code: usize,
+    pub message: String,
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let err_msg = match self.err_code {
+            7001 => "The number of indices is not a multiple of 3",
+            7002 => "Index out of range",
+            7003 => "Attribute length differs from the number of positions",
+            7004 => "Missing vertex attribute",
+            _ => "Sorry, something is wrong! Please Try Again!",
+        };
+
+        write!(f, "{}: {}", err_msg, self.message)
+    }
+}
+
+impl error::Error for MeshError {}
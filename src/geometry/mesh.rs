@@ -0,0 +1,295 @@
+use crate::geometry::geometry_errors::MeshError;
+use crate::geometry::obj::ObjMesh;
+use crate::geometry::triangle::Triangle;
+use crate::matrix::vector2d::Vector2d;
+use crate::matrix::vector3d::Vector3d;
+use crate::matrix::vector4d::Vector4d;
+
+/// Axis aligned bounding box, min is smaller than or equal to max on every axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vector3d<f32>,
+    pub max: Vector3d<f32>,
+}
+
+impl Aabb {
+    pub fn new(min: Vector3d<f32>, max: Vector3d<f32>) -> Aabb {
+        Aabb { min, max }
+    }
+
+    /// The smallest box containing every point, None without points
+    pub fn from_points<'a, I: IntoIterator<Item = &'a Vector3d<f32>>>(points: I) -> Option<Aabb> {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        Some(points.fold(Aabb::new(first, first), |aabb, p| aabb.include(*p)))
+    }
+
+    /// The smallest box containing this one and point
+    pub fn include(&self, point: Vector3d<f32>) -> Aabb {
+        Aabb::new(
+            Vector3d::new(self.min.x.min(point.x), self.min.y.min(point.y), self.min.z.min(point.z)),
+            Vector3d::new(self.max.x.max(point.x), self.max.y.max(point.y), self.max.z.max(point.z)),
+        )
+    }
+
+    /// The smallest box containing both boxes
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        self.include(other.min).include(other.max)
+    }
+
+    pub fn center(&self) -> Vector3d<f32> {
+        Vector3d::mul_item(self.min.add(self.max), 0.5_f32)
+    }
+
+    /// Length of the box along every axis
+    pub fn size(&self) -> Vector3d<f32> {
+        self.max.sub(self.min)
+    }
+
+    /// Whether point lies inside or on the border of the box
+    pub fn contains(&self, point: Vector3d<f32>) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x
+            && point.y >= self.min.y && point.y <= self.max.y
+            && point.z >= self.min.z && point.z <= self.max.z
+    }
+}
+
+/** An indexed triangle mesh, the vertex arrays are shared by the faces that use the same vertex
+  Attribute arrays are either empty or as long as positions, the i-th entry belongs to the i-th position.
+  Colors are RGB in [0, 1], tangents hold the tangent in xyz and the handedness of the bitangent, 1 or -1, in w.
+  indices is a triangle list of counter-clockwise faces. Example
+```rust
+use QRender::geometry::mesh::Mesh;
+use QRender::matrix::vector3d::Vector3d;
+
+// A unit square in the XY plane made of two triangles sharing the diagonal
+let mut mesh = Mesh::new(
+    vec![Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(1.0, 0.0, 0.0), Vector3d::new(0.0, 1.0, 0.0), Vector3d::new(1.0, 1.0, 0.0)],
+    vec![0, 1, 2, 2, 1, 3],
+);
+mesh.compute_smooth_normals().unwrap();
+let normal = mesh.normals[3];
+```
+
+output normal:
+```text
+[0.0 0.0 1.0]
+```
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Mesh {
+    pub positions:  Vec<Vector3d<f32>>,
+    pub normals:    Vec<Vector3d<f32>>,
+    pub tex_coords: Vec<Vector2d<f32>>,
+    pub colors:     Vec<Vector3d<f32>>,
+    pub tangents:   Vec<Vector4d<f32>>,
+    pub indices:    Vec<u32>,
+}
+
+impl Mesh {
+    /// A mesh without attributes
+    pub fn new(positions: Vec<Vector3d<f32>>, indices: Vec<u32>) -> Mesh {
+        Mesh { positions, indices, ..Mesh::default() }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn face_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Check the index count, the index range and the length of every attribute array
+    pub fn validate(&self) -> Result<(), MeshError> {
+        if self.indices.len() % 3 != 0 {
+            return Err(MeshError { err_code: 7001, message: format!("{} indices", self.indices.len()) });
+        }
+        if let Some(index) = self.indices.iter().find(|i| **i as usize >= self.positions.len()) {
+            return Err(MeshError { err_code: 7002, message: format!("index: {}, length: {}", index, self.positions.len()) });
+        }
+        let lengths = [
+            ("normals", self.normals.len()),
+            ("tex_coords", self.tex_coords.len()),
+            ("colors", self.colors.len()),
+            ("tangents", self.tangents.len()),
+        ];
+        for (name, len) in lengths.iter() {
+            if *len != 0 && *len != self.positions.len() {
+                return Err(MeshError { err_code: 7003, message: format!("{}: {}, positions: {}", name, len, self.positions.len()) });
+            }
+        }
+        Ok(())
+    }
+
+    /// The three vertex indices of every face
+    fn face_indices(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        self.indices.chunks_exact(3).map(|f| [f[0] as usize, f[1] as usize, f[2] as usize])
+    }
+
+    /// Normal of a face scaled by twice its area, zero for degenerate faces
+    fn face_normal(&self, face: [usize; 3]) -> Vector3d<f32> {
+        let a = self.positions[face[0]];
+        self.positions[face[1]].sub(a).cross(self.positions[face[2]].sub(a))
+    }
+
+    /// Set the normal of every vertex to the average of the normals of its faces weighted by their area,
+    /// vertices that belong to no face get a zero normal
+    pub fn compute_smooth_normals(&mut self) -> Result<(), MeshError> {
+        self.validate()?;
+        let mut normals = vec![Vector3d::fill(0.0_f32); self.positions.len()];
+        for face in self.face_indices() {
+            let normal = self.face_normal(face);
+            for i in face.iter() {
+                normals[*i] = normals[*i].add(normal);
+            }
+        }
+        self.normals = normals.into_iter().map(normalize_or_zero).collect();
+        Ok(())
+    }
+
+    /** Give every face its own three vertices with the face normal, the other attributes are copied to the new vertices
+      Vertices that belong to no face are dropped and indices becomes 0, 1, 2, ..., so the vertex count is three times the face count.
+     */
+    pub fn compute_flat_normals(&mut self) -> Result<(), MeshError> {
+        self.validate()?;
+        let corners: Vec<usize> = self.indices.iter().map(|i| *i as usize).collect();
+        let normals = self.face_indices()
+            .flat_map(|face| [normalize_or_zero(self.face_normal(face)); 3])
+            .collect();
+        self.positions = corners.iter().map(|i| self.positions[*i]).collect();
+        self.tex_coords = unweld(&self.tex_coords, &corners);
+        self.colors = unweld(&self.colors, &corners);
+        self.tangents = unweld(&self.tangents, &corners);
+        self.normals = normals;
+        self.indices = (0..corners.len() as u32).collect();
+        Ok(())
+    }
+
+    /** Compute a tangent frame per vertex from the normals and texture coordinates, in the way of MikkTSpace:
+      the tangent and bitangent of every face follow the directions of growing u and v, they are accumulated on the vertices
+      weighted by the angle of the face at the vertex, then the tangent is made orthogonal to the normal
+      and w stores whether the bitangent is cross(normal, tangent) (1) or its opposite (-1, mirrored texture coordinates).
+      Vertices without usable texture coordinates get some tangent orthogonal to their normal. Example
+    ```rust
+    use QRender::geometry::mesh::Mesh;
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+
+    let mut mesh = Mesh::new(vec![Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(0.0, 1.0, 0.0), Vector3d::new(-1.0, 0.0, 0.0)], vec![0, 1, 2]);
+    mesh.compute_smooth_normals().unwrap();
+    // u grows along Y and v along -X
+    mesh.tex_coords = vec![Vector2d::new(0.0, 0.0), Vector2d::new(1.0, 0.0), Vector2d::new(0.0, 1.0)];
+    mesh.compute_tangents().unwrap();
+    let tangent = mesh.tangents[0];
+    ```
+
+    output tangent:
+    ```text
+    [0.0 1.0 0.0 1.0]
+    ```
+     */
+    pub fn compute_tangents(&mut self) -> Result<(), MeshError> {
+        self.validate()?;
+        if self.normals.is_empty() || self.tex_coords.is_empty() {
+            let missing = if self.normals.is_empty() { "normals" } else { "tex_coords" };
+            return Err(MeshError { err_code: 7004, message: missing.to_string() });
+        }
+        let mut tangents = vec![Vector3d::fill(0.0_f32); self.positions.len()];
+        let mut bitangents = vec![Vector3d::fill(0.0_f32); self.positions.len()];
+        for face in self.face_indices() {
+            let p = [self.positions[face[0]], self.positions[face[1]], self.positions[face[2]]];
+            let uv = [self.tex_coords[face[0]], self.tex_coords[face[1]], self.tex_coords[face[2]]];
+            let (e1, e2) = (p[1].sub(p[0]), p[2].sub(p[0]));
+            let (d1, d2) = (uv[1].sub(uv[0]), uv[2].sub(uv[0]));
+            let det = d1.x * d2.y - d2.x * d1.y;
+            if det.abs() < 1e-12_f32 {
+                continue;
+            }
+            let tangent = Vector3d::div_item(Vector3d::mul_item(e1, d2.y).sub(Vector3d::mul_item(e2, d1.y)), det);
+            let bitangent = Vector3d::div_item(Vector3d::mul_item(e2, d1.x).sub(Vector3d::mul_item(e1, d2.x)), det);
+            for k in 0..3 {
+                let weight = corner_angle(p[k], p[(k + 1) % 3], p[(k + 2) % 3]);
+                tangents[face[k]] = tangents[face[k]].add(Vector3d::mul_item(tangent, weight));
+                bitangents[face[k]] = bitangents[face[k]].add(Vector3d::mul_item(bitangent, weight));
+            }
+        }
+
+        self.tangents = (0..self.positions.len()).map(|i| {
+            let n = normalize_or_zero(self.normals[i]);
+            // Gram-Schmidt, fall back to any direction orthogonal to the normal
+            let mut t = tangents[i].sub(Vector3d::mul_item(n, n.dot(tangents[i])));
+            if t.norm() < 1e-12_f32 {
+                let axis = if n.x.abs() < 0.9_f32 { Vector3d::new(1.0_f32, 0.0_f32, 0.0_f32) } else { Vector3d::new(0.0_f32, 1.0_f32, 0.0_f32) };
+                t = axis.sub(Vector3d::mul_item(n, n.dot(axis)));
+            }
+            let t = t.normalize();
+            let w = if n.cross(t).dot(bitangents[i]) < 0.0_f32 { -1.0_f32 } else { 1.0_f32 };
+            Vector4d::new(t.x, t.y, t.z, w)
+        }).collect();
+        Ok(())
+    }
+
+    /// Bounding box of the positions, None for a mesh without vertices
+    pub fn aabb(&self) -> Option<Aabb> {
+        Aabb::from_points(self.positions.iter())
+    }
+
+    /// The faces as triangles with their attributes, attributes the mesh does not have are zero
+    pub fn faces(&self) -> Result<impl Iterator<Item = Triangle> + '_, MeshError> {
+        self.validate()?;
+        Ok(self.face_indices().map(move |face| {
+            let mut triangle = Triangle::new();
+            for (k, i) in face.iter().enumerate() {
+                triangle.vertex[k] = self.positions[*i];
+                if let Some(normal) = self.normals.get(*i) {
+                    triangle.normal[k] = *normal;
+                }
+                if let Some(tex_coords) = self.tex_coords.get(*i) {
+                    triangle.tex_coords[k] = *tex_coords;
+                }
+                if let Some(color) = self.colors.get(*i) {
+                    triangle.color[k] = *color;
+                }
+            }
+            triangle
+        }))
+    }
+}
+
+impl From<&ObjMesh> for Mesh {
+    /// The arrays of the OBJ mesh, normals and texture coordinates are left empty unless the file provides them for every vertex
+    fn from(mesh: &ObjMesh) -> Mesh {
+        let normals = if mesh.has_normals { mesh.normals.clone() } else { Vec::new() };
+        let tex_coords = if mesh.has_tex_coords { mesh.tex_coords.clone() } else { Vec::new() };
+        Mesh {
+            positions: mesh.positions.clone(),
+            normals,
+            tex_coords,
+            indices: mesh.indices.iter().flat_map(|f| [f.x as u32, f.y as u32, f.z as u32]).collect(),
+            ..Mesh::default()
+        }
+    }
+}
+
+fn normalize_or_zero(v: Vector3d<f32>) -> Vector3d<f32> {
+    if v.norm() > 0.0_f32 { v.normalize() } else { v }
+}
+
+/// Angle in radians at corner between the edges towards a and b, zero when an edge is degenerate
+fn corner_angle(corner: Vector3d<f32>, a: Vector3d<f32>, b: Vector3d<f32>) -> f32 {
+    let (u, v) = (a.sub(corner), b.sub(corner));
+    let len = u.norm() * v.norm();
+    if len == 0.0_f32 {
+        return 0.0_f32;
+    }
+    (u.dot(v) / len).clamp(-1.0_f32, 1.0_f32).acos()
+}
+
+/// The attribute of every corner, an empty attribute stays empty
+fn unweld<T: Copy>(attribute: &[T], corners: &[usize]) -> Vec<T> {
+    if attribute.is_empty() {
+        return Vec::new();
+    }
+    corners.iter().map(|i| attribute[*i]).collect()
+}
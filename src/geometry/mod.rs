@@ -1,2 +1,4 @@
 pub mod triangle;
-pub mod geometry_errors;
\ No newline at end of file
+pub mod obj;
+pub mod geometry_errors;
+pub mod mesh;
\ No newline at end of file
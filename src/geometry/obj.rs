@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use crate::matrix::vector2d::Vector2d;
+use crate::matrix::vector3d::Vector3d;
+use crate::geometry::geometry_errors::ObjError;
+
+/// A material read from a MTL file, colors are RGB in [0, 1]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMaterial {
+    pub name:        String,
+    /// Ka
+    pub ambient:     Vector3d<f32>,
+    /// Kd
+    pub diffuse:     Vector3d<f32>,
+    /// Ks
+    pub specular:    Vector3d<f32>,
+    /// Ns, the specular exponent
+    pub shininess:   f32,
+    /// d, 1 is fully opaque
+    pub dissolve:    f32,
+    /// map_Kd, relative to the MTL file
+    pub diffuse_map: Option<String>,
+}
+
+/** A group of faces sharing one material, ready for Rasterizer::load_obj_mesh
+  Positions, normals and texture coordinates are unified per vertex so that one index addresses all three,
+  normals and texture coordinates that the file does not provide are zero, has_normals and has_tex_coords tell whether it provides them.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMesh {
+    /// Name of the enclosing g or o statement, "default" before the first one
+    pub name:       String,
+    /// Name of the material selected by usemtl
+    pub material:   Option<String>,
+    pub positions:  Vec<Vector3d<f32>>,
+    pub normals:    Vec<Vector3d<f32>>,
+    pub tex_coords: Vec<Vector2d<f32>>,
+    /// Counter-clockwise triangles, polygons are triangulated as fans
+    pub indices:    Vec<Vector3d<i32>>,
+    /// Whether every vertex takes its normal from a vn statement
+    pub has_normals:    bool,
+    /// Whether every vertex takes its texture coordinates from a vt statement
+    pub has_tex_coords: bool,
+}
+
+/// The content of a Wavefront OBJ file and the materials of its MTL libraries
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjModel {
+    pub meshes:        Vec<ObjMesh>,
+    pub materials:     HashMap<String, ObjMaterial>,
+    /// File names of the mtllib statements
+    pub material_libs: Vec<String>,
+}
+
+impl ObjMaterial {
+    pub fn new(name: &str) -> ObjMaterial {
+        ObjMaterial {
+            name: name.to_string(),
+            ambient: Vector3d::fill(0.0_f32),
+            diffuse: Vector3d::fill(1.0_f32),
+            specular: Vector3d::fill(0.0_f32),
+            shininess: 0.0_f32,
+            dissolve: 1.0_f32,
+            diffuse_map: None,
+        }
+    }
+
+    /** Parse the content of a MTL file into materials keyed by name, Example
+    ```rust
+    use QRender::geometry::obj::ObjMaterial;
+
+    let materials = ObjMaterial::parse("newmtl red\nKd 1 0 0\nNs 32").unwrap();
+    let shininess = materials["red"].shininess;
+    ```
+
+    output shininess:
+    ```text
+    32.0
+    ```
+     */
+    pub fn parse(source: &str) -> Result<HashMap<String, ObjMaterial>, ObjError> {
+        let mut materials = HashMap::new();
+        let mut current: Option<ObjMaterial> = None;
+
+        for (i, raw) in source.lines().enumerate() {
+            let line = i + 1;
+            let args: Vec<&str> = strip_comment(raw).split_whitespace().collect();
+            if args.is_empty() {
+                continue;
+            }
+
+            if args[0] == "newmtl" {
+                if let Some(m) = current.take() {
+                    materials.insert(m.name.clone(), m);
+                }
+                current = Some(ObjMaterial::new(&rest(&args, line)?));
+                continue;
+            }
+            let m = match current.as_mut() {
+                Some(m) => m,
+                // Statements before the first newmtl have nothing to apply to
+                None => continue,
+            };
+            match args[0] {
+                "Ka" => m.ambient = parse_vector3d(&args, line)?,
+                "Kd" => m.diffuse = parse_vector3d(&args, line)?,
+                "Ks" => m.specular = parse_vector3d(&args, line)?,
+                "Ns" => m.shininess = parse_f32(&args, 1, line)?,
+                "d" => m.dissolve = parse_f32(&args, 1, line)?,
+                "Tr" => m.dissolve = 1.0_f32 - parse_f32(&args, 1, line)?,
+                "map_Kd" => m.diffuse_map = Some(rest(&args, line)?),
+                _ => {}
+            }
+        }
+        if let Some(m) = current.take() {
+            materials.insert(m.name.clone(), m);
+        }
+        Ok(materials)
+    }
+}
+
+impl ObjModel {
+    /// Read an OBJ file and the MTL libraries it references, which are looked up next to it
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<ObjModel, ObjError> {
+        let path = path.as_ref();
+        let mut model = ObjModel::parse(&read_file(path)?)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for lib in model.material_libs.iter() {
+            let materials = ObjMaterial::parse(&read_file(&dir.join(lib))?)?;
+            model.materials.extend(materials);
+        }
+        Ok(model)
+    }
+
+    /** Parse the content of an OBJ file, mtllib statements are recorded in material_libs but not read, Example
+    ```rust
+    use QRender::geometry::obj::ObjModel;
+
+    let model = ObjModel::parse("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4").unwrap();
+    let (first, second) = (model.meshes[0].indices[0], model.meshes[0].indices[1]);
+    ```
+
+    output first and second, the quad is split into two triangles:
+    ```text
+    [0 1 2]
+    [0 2 3]
+    ```
+     */
+    pub fn parse(source: &str) -> Result<ObjModel, ObjError> {
+        let mut positions: Vec<Vector3d<f32>> = Vec::new();
+        let mut normals: Vec<Vector3d<f32>> = Vec::new();
+        let mut tex_coords: Vec<Vector2d<f32>> = Vec::new();
+        let mut meshes = Vec::new();
+        let mut material_libs = Vec::new();
+        let mut builder = MeshBuilder::new("default".to_string(), None);
+
+        for (i, raw) in source.lines().enumerate() {
+            let line = i + 1;
+            let args: Vec<&str> = strip_comment(raw).split_whitespace().collect();
+            if args.is_empty() {
+                continue;
+            }
+
+            match args[0] {
+                "v" => positions.push(parse_vector3d(&args, line)?),
+                "vn" => normals.push(parse_vector3d(&args, line)?),
+                "vt" => {
+                    let v = if args.len() > 2 { parse_f32(&args, 2, line)? } else { 0.0_f32 };
+                    tex_coords.push(Vector2d::new(parse_f32(&args, 1, line)?, v));
+                }
+                "f" => {
+                    if args.len() < 4 {
+                        return Err(ObjError { err_code: 4004, message: format!("line {}", line) });
+                    }
+                    let mut corners = Vec::with_capacity(args.len() - 1);
+                    for corner in args[1..].iter() {
+                        corners.push(builder.vertex(corner, &positions, &normals, &tex_coords, line)?);
+                    }
+                    for k in 1..corners.len() - 1 {
+                        builder.mesh.indices.push(Vector3d::new(corners[0], corners[k], corners[k + 1]));
+                    }
+                }
+                "g" | "o" => {
+                    let name = if args.len() > 1 { args[1..].join(" ") } else { "default".to_string() };
+                    let material = builder.mesh.material.clone();
+                    builder.finish(&mut meshes);
+                    builder = MeshBuilder::new(name, material);
+                }
+                "usemtl" => {
+                    let material = rest(&args, line)?;
+                    let name = builder.mesh.name.clone();
+                    builder.finish(&mut meshes);
+                    builder = MeshBuilder::new(name, Some(material));
+                }
+                "mtllib" => material_libs.extend(args[1..].iter().map(|s| s.to_string())),
+                _ => {}
+            }
+        }
+        builder.finish(&mut meshes);
+
+        Ok(ObjModel {
+            meshes,
+            materials: HashMap::new(),
+            material_libs,
+        })
+    }
+}
+
+/// Accumulates the faces of one ObjMesh and deduplicates its position/tex_coords/normal triples
+struct MeshBuilder {
+    mesh:   ObjMesh,
+    lookup: HashMap<(usize, Option<usize>, Option<usize>), i32>,
+}
+
+impl MeshBuilder {
+    fn new(name: String, material: Option<String>) -> MeshBuilder {
+        MeshBuilder {
+            mesh: ObjMesh {
+                name,
+                material,
+                positions: Vec::new(),
+                normals: Vec::new(),
+                tex_coords: Vec::new(),
+                indices: Vec::new(),
+                has_normals: true,
+                has_tex_coords: true,
+            },
+            lookup: HashMap::new(),
+        }
+    }
+
+    /// Index of the mesh vertex for a face corner written as v, v/vt, v//vn or v/vt/vn
+    fn vertex(&mut self, corner: &str, positions: &[Vector3d<f32>], normals: &[Vector3d<f32>],
+              tex_coords: &[Vector2d<f32>], line: usize) -> Result<i32, ObjError> {
+        let mut parts = corner.split('/');
+        let v = resolve_index(parts.next().unwrap_or(""), positions.len(), line)?;
+        let vt = match parts.next() {
+            Some(s) if !s.is_empty() => Some(resolve_index(s, tex_coords.len(), line)?),
+            _ => None,
+        };
+        let vn = match parts.next() {
+            Some(s) if !s.is_empty() => Some(resolve_index(s, normals.len(), line)?),
+            _ => None,
+        };
+
+        if let Some(&index) = self.lookup.get(&(v, vt, vn)) {
+            return Ok(index);
+        }
+        let index = self.mesh.positions.len() as i32;
+        self.mesh.positions.push(positions[v]);
+        self.mesh.tex_coords.push(vt.map_or(Vector2d::fill(0.0_f32), |i| tex_coords[i]));
+        self.mesh.normals.push(vn.map_or(Vector3d::fill(0.0_f32), |i| normals[i]));
+        self.mesh.has_tex_coords &= vt.is_some();
+        self.mesh.has_normals &= vn.is_some();
+        self.lookup.insert((v, vt, vn), index);
+        Ok(index)
+    }
+
+    fn finish(self, meshes: &mut Vec<ObjMesh>) {
+        if !self.mesh.indices.is_empty() {
+            meshes.push(self.mesh);
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, ObjError> {
+    fs::read_to_string(path).map_err(|e| ObjError { err_code: 4001, message: format!("{}, {}", path.display(), e) })
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(i) => &line[..i],
+        None => line,
+    }
+}
+
+/// The arguments of a statement joined back together, for names that may contain spaces
+fn rest(args: &[&str], line: usize) -> Result<String, ObjError> {
+    if args.len() < 2 {
+        return Err(ObjError { err_code: 4005, message: format!("line {}", line) });
+    }
+    Ok(args[1..].join(" "))
+}
+
+fn parse_f32(args: &[&str], i: usize, line: usize) -> Result<f32, ObjError> {
+    match args.get(i) {
+        Some(s) => s.parse::<f32>().map_err(|_| ObjError { err_code: 4002, message: format!("line {}, {}", line, s) }),
+        None => Err(ObjError { err_code: 4005, message: format!("line {}", line) }),
+    }
+}
+
+fn parse_vector3d(args: &[&str], line: usize) -> Result<Vector3d<f32>, ObjError> {
+    Ok(Vector3d::new(parse_f32(args, 1, line)?, parse_f32(args, 2, line)?, parse_f32(args, 3, line)?))
+}
+
+/// Turn a 1-based or negative relative OBJ index into a 0-based index below len
+fn resolve_index(s: &str, len: usize, line: usize) -> Result<usize, ObjError> {
+    let i = s.parse::<i64>().map_err(|_| ObjError { err_code: 4002, message: format!("line {}, {}", line, s) })?;
+    let resolved = if i > 0 { i - 1 } else { len as i64 + i };
+    if i == 0 || resolved < 0 || resolved >= len as i64 {
+        return Err(ObjError { err_code: 4003, message: format!("line {}, {}", line, s) });
+    }
+    Ok(resolved as usize)
+}
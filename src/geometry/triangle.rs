@@ -59,10 +59,10 @@ impl Triangle {
     }
 
     pub fn set_tex_coords(&mut self, index: usize, tex: Vector2d<f32>) -> Result<(), TriangleError> {
-        if index >= 2 {
+        if index >= 3 {
             return Err(
                 TriangleError {
-                    err_code: 1002,
+                    err_code: 1003,
                     message: "".to_string()
                 }
             )
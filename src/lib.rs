@@ -1,3 +1,4 @@
 pub mod matrix;
 pub mod rasterizer;
-pub mod geometry;
\ No newline at end of file
+pub mod geometry;
+pub mod cli;
\ No newline at end of file
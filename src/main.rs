@@ -1,11 +1,21 @@
-use crate::rasterizer::rasterizer::Rasterizer;
-use crate::rasterizer::example1;
-
-mod matrix;
-mod rasterizer;
-mod geometry;
+use std::process;
+use QRender::cli::args::{Command, USAGE};
+use QRender::cli::render;
 
 fn main() {
-    let args = vec!["", "", "200.0", "output.png"];
-    example1::draw_green_triangle(args);
+    let options = match Command::parse(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Ok(Command::Render(options)) => options,
+        Err(e) => {
+            eprintln!("qrender: {}", e);
+            process::exit(e.exit_code());
+        }
+    };
+    if let Err(e) = render::run(&options) {
+        eprintln!("qrender: {}", e);
+        process::exit(e.exit_code());
+    }
 }
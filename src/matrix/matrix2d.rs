@@ -1,5 +1,7 @@
 use std::{fmt, ops};
 use crate::matrix::vector2d::Vector2d;
+use crate::matrix::matrix_errors::{MatrixError, MatrixErrorType};
+use crate::matrix::scalar::Float;
 
 /** 2D matrix
  Supports regular matrix calculations
@@ -233,6 +235,65 @@ impl<T> Matrix2d<T>
             cols: 2,
         }
     }
+
+    /** Determinant of a 2x2 dimensional matrix, Example
+    ```rust
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::matrix2d::Matrix2d;
+
+    let m = Matrix2d::new(Vector2d::new(3, 8), Vector2d::new(4, 6));
+    let d = m.determinant();
+    ```
+
+    output d:
+    ```text
+    -14
+    ```
+     */
+    #[inline]
+    pub fn determinant(&self) -> T {
+        self.items[0].x * self.items[1].y - self.items[0].y * self.items[1].x
+    }
+}
+
+impl<T> Matrix2d<T>
+    where T: Float
+{
+    /** Inverse of a 2x2 dimensional matrix, a singular matrix returns an error
+      The matrix counts as singular when its determinant is within the machine epsilon of the product of the row lengths,
+      the largest determinant rows of those lengths can have, so nearly singular matrices are rejected at any scale, Example
+    ```rust
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::matrix2d::Matrix2d;
+
+    let m = Matrix2d::new(Vector2d::new(4.0, 7.0), Vector2d::new(2.0, 6.0));
+    let inv = m.inverse().unwrap();
+    ```
+
+    output inv:
+    ```text
+    [0.6, -0.7]
+    [-0.2, 0.4]
+    ```
+     */
+    pub fn inverse(&self) -> Result<Matrix2d<T>, MatrixError> {
+        let det = self.determinant();
+        let length = |v: Vector2d<T>| v.dot(v).sqrt();
+        if det.abs() <= T::epsilon() * length(self.items[0]) * length(self.items[1]) {
+            return Err(
+                MatrixError {
+                    err_msg: String::from("The matrix is singular and cannot be inverted"),
+                    err_type: MatrixErrorType::SingularMatrixError,
+                }
+            )
+        }
+        Ok(
+            Matrix2d::new(
+                Vector2d::new(self.items[1].y / det, -self.items[0].y / det),
+                Vector2d::new(-self.items[1].x / det, self.items[0].x / det),
+            )
+        )
+    }
 }
 
 impl<T> fmt::Display for Matrix2d<T>
@@ -1,5 +1,7 @@
 use std::{fmt, ops};
 use crate::matrix::vector3d::Vector3d;
+use crate::matrix::matrix_errors::{MatrixError, MatrixErrorType};
+use crate::matrix::scalar::Float;
 
 /** 3D matrix
   Supports regular matrix calculations
@@ -301,6 +303,75 @@ impl<T> Matrix3d<T>
             self.items[2].cross(v)
         )
     }
+
+    /** Determinant of a 3x3 dimensional matrix, Example
+    ```rust
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::matrix::matrix3d::Matrix3d;
+
+    let m = Matrix3d::new(
+        Vector3d::new(2, 0, 1),
+        Vector3d::new(1, 3, 2),
+        Vector3d::new(1, 1, 2)
+    );
+    let d = m.determinant();
+    ```
+
+    output d:
+    ```text
+    6
+    ```
+     */
+    #[inline]
+    pub fn determinant(&self) -> T {
+        self.items[0].dot(self.items[1].cross(self.items[2]))
+    }
+}
+
+impl<T> Matrix3d<T>
+    where T: Float
+{
+    /** Inverse of a 3x3 dimensional matrix, a singular matrix returns an error
+      Like Matrix2d::inverse, the determinant is compared to the machine epsilon times the product of the row lengths, Example
+    ```rust
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::matrix::matrix3d::Matrix3d;
+
+    let m = Matrix3d::new(
+        Vector3d::new(2.0, 0.0, 1.0),
+        Vector3d::new(1.0, 3.0, 2.0),
+        Vector3d::new(1.0, 1.0, 2.0)
+    );
+    let inv = m.inverse().unwrap();
+    ```
+
+    output inv:
+    ```text
+    [ 0.6667,  0.1667, -0.5]
+    [ 0,       0.5,    -0.5]
+    [-0.3333, -0.3333,  1  ]
+    ```
+     */
+    pub fn inverse(&self) -> Result<Matrix3d<T>, MatrixError> {
+        let det = self.determinant();
+        let length = |v: Vector3d<T>| v.dot(v).sqrt();
+        if det.abs() <= T::epsilon() * length(self.items[0]) * length(self.items[1]) * length(self.items[2]) {
+            return Err(
+                MatrixError {
+                    err_msg: String::from("The matrix is singular and cannot be inverted"),
+                    err_type: MatrixErrorType::SingularMatrixError,
+                }
+            )
+        }
+        // The columns of the adjugate are the cross products of the rows
+        let mut adj = Matrix3d::new(
+            self.items[1].cross(self.items[2]),
+            self.items[2].cross(self.items[0]),
+            self.items[0].cross(self.items[1])
+        );
+        adj.transpose();
+        Ok(adj.div_item(det))
+    }
 }
 
 impl<T> fmt::Display for Matrix3d<T>
@@ -1,5 +1,7 @@
 use std::{fmt, ops};
 use crate::matrix::vector4d::Vector4d;
+use crate::matrix::matrix_errors::{MatrixError, MatrixErrorType};
+use crate::matrix::scalar::Float;
 
 /** 4D matrix
  Supports regular matrix calculations
@@ -327,6 +329,124 @@ impl<T> Matrix4d<T>
             cols: 4,
         }
     }
+
+    /** Determinant of a 4x4 dimensional matrix, Example
+    ```rust
+    use QRender::matrix::vector4d::Vector4d;
+    use QRender::matrix::matrix4d::Matrix4d;
+
+    let m = Matrix4d::new(
+        Vector4d::new(3, 0, 4, 9),
+        Vector4d::new(7, 1, 3, 8),
+        Vector4d::new(9, 2, 5, 4),
+        Vector4d::new(4, 7, 6, 0)
+    );
+    let d = m.determinant();
+    ```
+
+    output d:
+    ```text
+    938
+    ```
+     */
+    #[inline]
+    pub fn determinant(&self) -> T {
+        let (s, c) = self.sub_determinants();
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    /// The 2x2 minors of the two upper rows (s) and of the two lower rows (c), shared by determinant and inverse
+    #[inline]
+    fn sub_determinants(&self) -> ([T; 6], [T; 6]) {
+        let m = &self.items;
+        let s = [
+            m[0].x * m[1].y - m[1].x * m[0].y,
+            m[0].x * m[1].z - m[1].x * m[0].z,
+            m[0].x * m[1].w - m[1].x * m[0].w,
+            m[0].y * m[1].z - m[1].y * m[0].z,
+            m[0].y * m[1].w - m[1].y * m[0].w,
+            m[0].z * m[1].w - m[1].z * m[0].w,
+        ];
+        let c = [
+            m[2].x * m[3].y - m[3].x * m[2].y,
+            m[2].x * m[3].z - m[3].x * m[2].z,
+            m[2].x * m[3].w - m[3].x * m[2].w,
+            m[2].y * m[3].z - m[3].y * m[2].z,
+            m[2].y * m[3].w - m[3].y * m[2].w,
+            m[2].z * m[3].w - m[3].z * m[2].w,
+        ];
+        (s, c)
+    }
+}
+
+impl<T> Matrix4d<T>
+    where T: Float
+{
+    /** Inverse of a 4x4 dimensional matrix, a singular matrix returns an error
+      Like Matrix2d::inverse, the determinant is compared to the machine epsilon times the product of the row lengths, Example
+    ```rust
+    use QRender::matrix::vector4d::Vector4d;
+    use QRender::matrix::matrix4d::Matrix4d;
+
+    let m = Matrix4d::new(
+        Vector4d::new(1.0, 0.0, 0.0, 2.0),
+        Vector4d::new(0.0, 2.0, 0.0, 0.0),
+        Vector4d::new(0.0, 0.0, 4.0, 0.0),
+        Vector4d::new(0.0, 0.0, 0.0, 1.0)
+    );
+    let inv = m.inverse().unwrap();
+    ```
+
+    output inv:
+    ```text
+    [1, 0,   0,    -2]
+    [0, 0.5, 0,     0]
+    [0, 0,   0.25,  0]
+    [0, 0,   0,     1]
+    ```
+     */
+    pub fn inverse(&self) -> Result<Matrix4d<T>, MatrixError> {
+        let (s, c) = self.sub_determinants();
+        let det = s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
+        let length = |v: Vector4d<T>| v.dot(v).sqrt();
+        if det.abs() <= T::epsilon() * length(self.items[0]) * length(self.items[1]) * length(self.items[2]) * length(self.items[3]) {
+            return Err(
+                MatrixError {
+                    err_msg: String::from("The matrix is singular and cannot be inverted"),
+                    err_type: MatrixErrorType::SingularMatrixError,
+                }
+            )
+        }
+
+        let m = &self.items;
+        let adj = Matrix4d::new(
+            Vector4d::new(
+                m[1].y * c[5] - m[1].z * c[4] + m[1].w * c[3],
+                m[0].z * c[4] - m[0].y * c[5] - m[0].w * c[3],
+                m[3].y * s[5] - m[3].z * s[4] + m[3].w * s[3],
+                m[2].z * s[4] - m[2].y * s[5] - m[2].w * s[3]
+            ),
+            Vector4d::new(
+                m[1].z * c[2] - m[1].x * c[5] - m[1].w * c[1],
+                m[0].x * c[5] - m[0].z * c[2] + m[0].w * c[1],
+                m[3].z * s[2] - m[3].x * s[5] - m[3].w * s[1],
+                m[2].x * s[5] - m[2].z * s[2] + m[2].w * s[1]
+            ),
+            Vector4d::new(
+                m[1].x * c[4] - m[1].y * c[2] + m[1].w * c[0],
+                m[0].y * c[2] - m[0].x * c[4] - m[0].w * c[0],
+                m[3].x * s[4] - m[3].y * s[2] + m[3].w * s[0],
+                m[2].y * s[2] - m[2].x * s[4] - m[2].w * s[0]
+            ),
+            Vector4d::new(
+                m[1].y * c[1] - m[1].x * c[3] - m[1].z * c[0],
+                m[0].x * c[3] - m[0].y * c[1] + m[0].z * c[0],
+                m[3].y * s[1] - m[3].x * s[3] - m[3].z * s[0],
+                m[2].x * s[3] - m[2].y * s[1] + m[2].z * s[0]
+            )
+        );
+        Ok(adj.div_item(det))
+    }
 }
 
 impl<T> fmt::Display for Matrix4d<T>
@@ -3,7 +3,8 @@ use std::{error, fmt};
 #[derive(Debug, Clone)]
 pub enum MatrixErrorType {
     InvalidInitSizeError,
-    InvalidCalcError
+    InvalidCalcError,
+    SingularMatrixError
 }
 
 #[derive(Debug, Clone)]
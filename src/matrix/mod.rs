@@ -4,6 +4,9 @@ pub mod vector4d;
 pub mod matrix2d;
 pub mod matrix3d;
 pub mod matrix4d;
+pub mod quaternion;
+pub mod scalar;
+pub mod transform;
 pub mod utils;
 mod vector_errors;
-mod matrix_errors;
\ No newline at end of file
+pub mod matrix_errors;
\ No newline at end of file
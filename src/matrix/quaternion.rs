@@ -0,0 +1,377 @@
+use std::{fmt, ops};
+use crate::matrix::vector3d::Vector3d;
+use crate::matrix::vector4d::Vector4d;
+use crate::matrix::matrix3d::Matrix3d;
+use crate::matrix::matrix4d::Matrix4d;
+use crate::matrix::scalar::{Float, Scalar};
+
+/** Quaternion x*i + y*j + z*k + w
+  Supports the Hamilton product, conjugation and the regular component-wise calculations
+  Unit quaternions represent rotations, they can be built from an axis and an angle or from Euler angles,
+  converted to and from rotation matrices and interpolated with nlerp or slerp (Float items only)
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion<T>
+    where T:
+    fmt::Debug +
+    Copy +
+    PartialOrd +
+    PartialEq +
+    ops::Add<Output=T> +
+    ops::Mul<Output=T> +
+    ops::Div<Output=T> +
+    ops::Sub<Output=T>
+{
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    pub w: T,
+}
+
+impl<T> Quaternion<T>
+    where T: Scalar
+{
+    /// Create the quaternion x*i + y*j + z*k + w, note that the real part comes last
+    #[inline]
+    pub fn new(x: T, y: T, z: T, w: T) -> Quaternion<T> {
+        Quaternion { x, y, z, w }
+    }
+
+    /// Create the identity quaternion, item_type is the value of one
+    #[inline]
+    pub fn identity(item_type: T) -> Quaternion<T> {
+        Quaternion { x: T::zero(), y: T::zero(), z: T::zero(), w: item_type }
+    }
+
+    /// Create a quaternion from its vector part and its real part
+    #[inline]
+    pub fn from_parts(v: Vector3d<T>, w: T) -> Quaternion<T> {
+        Quaternion { x: v.x, y: v.y, z: v.z, w }
+    }
+
+    /// The imaginary part (x, y, z) as a 3-dimensional vector
+    #[inline]
+    pub fn vector(&self) -> Vector3d<T> {
+        Vector3d::new(self.x, self.y, self.z)
+    }
+
+    /// The components as the 4-dimensional vector (x, y, z, w)
+    #[inline]
+    pub fn to_vector4d(&self) -> Vector4d<T> {
+        Vector4d::new(self.x, self.y, self.z, self.w)
+    }
+
+    /// The conjugate x*(-i) + y*(-j) + z*(-k) + w, which equals the inverse for unit quaternions
+    #[inline]
+    pub fn conjugate(&self) -> Quaternion<T>
+        where T: ops::Neg<Output=T>
+    {
+        Quaternion { x: -self.x, y: -self.y, z: -self.z, w: self.w }
+    }
+
+    /// Dot two quaternions as 4-dimensional vectors
+    #[inline]
+    pub fn dot(&self, q: Quaternion<T>) -> T {
+        self.x * q.x + self.y * q.y + self.z * q.z + self.w * q.w
+    }
+
+    /// Squared norm of the quaternion
+    #[inline]
+    pub fn norm_squared(&self) -> T {
+        self.dot(*self)
+    }
+
+    /** Hamilton product of two quaternions, the rotation of q is applied first, Example
+    ```rust
+    use QRender::matrix::quaternion::Quaternion;
+
+    let i = Quaternion::new(1, 0, 0, 0);
+    let j = Quaternion::new(0, 1, 0, 0);
+    let k = i.product(j);
+    ```
+
+    output k:
+    ```text
+    0i + 0j + 1k + 0
+    ```
+     */
+    #[inline]
+    pub fn product(&self, q: Quaternion<T>) -> Quaternion<T> {
+        Quaternion {
+            x: self.w * q.x + self.x * q.w + self.y * q.z - self.z * q.y,
+            y: self.w * q.y - self.x * q.z + self.y * q.w + self.z * q.x,
+            z: self.w * q.z + self.x * q.y - self.y * q.x + self.z * q.w,
+            w: self.w * q.w - self.x * q.x - self.y * q.y - self.z * q.z,
+        }
+    }
+
+    /// Add two quaternions component-wise
+    #[inline]
+    pub fn add(&self, q: Quaternion<T>) -> Quaternion<T> {
+        Quaternion { x: self.x + q.x, y: self.y + q.y, z: self.z + q.z, w: self.w + q.w }
+    }
+
+    /// Subtract two quaternions component-wise
+    #[inline]
+    pub fn sub(&self, q: Quaternion<T>) -> Quaternion<T> {
+        Quaternion { x: self.x - q.x, y: self.y - q.y, z: self.z - q.z, w: self.w - q.w }
+    }
+
+    /// Multiply a variable of the same type to each component of the quaternion
+    #[inline]
+    pub fn mul_item(q: Quaternion<T>, item: T) -> Quaternion<T> {
+        Quaternion { x: q.x * item, y: q.y * item, z: q.z * item, w: q.w * item }
+    }
+
+    /// Divide a variable of the same type to each component of the quaternion
+    #[inline]
+    pub fn div_item(q: Quaternion<T>, item: T) -> Quaternion<T> {
+        Quaternion { x: q.x / item, y: q.y / item, z: q.z / item, w: q.w / item }
+    }
+}
+
+impl<T> Quaternion<T>
+    where T: Float
+{
+    /** Create the rotation of angle degrees around axis, the axis does not need to be normalized, Example
+    ```rust
+    use QRender::matrix::quaternion::Quaternion;
+    use QRender::matrix::vector3d::Vector3d;
+
+    let q = Quaternion::from_axis_angle(Vector3d::new(0.0_f32, 0.0, 1.0), 90.0);
+    let v = q.rotate_vector(Vector3d::new(1.0, 0.0, 0.0));
+    ```
+
+    output v:
+    ```text
+    [0.0 1.0 0.0]
+    ```
+     */
+    pub fn from_axis_angle(axis: Vector3d<T>, angle: T) -> Quaternion<T> {
+        let len = axis.dot(axis).sqrt();
+        if len == T::zero() {
+            return Quaternion::identity(T::one());
+        }
+        let half = angle.to_radians() / T::from_f64(2.0);
+        let v = Vector3d::mul_item(axis, half.sin() / len);
+        Quaternion { x: v.x, y: v.y, z: v.z, w: half.cos() }
+    }
+
+    /// Create the rotation made of x degrees around the X axis, then y degrees around the Y axis, then z degrees around the Z axis
+    pub fn from_euler(x: T, y: T, z: T) -> Quaternion<T> {
+        let qx = Self::from_axis_angle(Vector3d::new(T::one(), T::zero(), T::zero()), x);
+        let qy = Self::from_axis_angle(Vector3d::new(T::zero(), T::one(), T::zero()), y);
+        let qz = Self::from_axis_angle(Vector3d::new(T::zero(), T::zero(), T::one()), z);
+        qz.product(qy).product(qx)
+    }
+
+    /// The Euler angles (x, y, z) in degrees of a unit quaternion, the inverse of from_euler
+    pub fn to_euler(&self) -> Vector3d<T> {
+        let two = T::from_f64(2.0);
+        let (x, y, z, w) = (self.x, self.y, self.z, self.w);
+        let roll = (two * (w * x + y * z)).atan2(T::one() - two * (x * x + y * y));
+        let pitch = (two * (w * y - z * x)).clamp(-T::one(), T::one()).asin();
+        let yaw = (two * (w * z + x * y)).atan2(T::one() - two * (y * y + z * z));
+        Vector3d::new(roll.to_degrees(), pitch.to_degrees(), yaw.to_degrees())
+    }
+
+    /// The normalized rotation axis and the angle in degrees of a unit quaternion
+    pub fn to_axis_angle(&self) -> (Vector3d<T>, T) {
+        let w = self.w.clamp(-T::one(), T::one());
+        let s = (T::one() - w * w).sqrt();
+        if s == T::zero() {
+            return (Vector3d::new(T::one(), T::zero(), T::zero()), T::zero());
+        }
+        (Vector3d::div_item(self.vector(), s), (T::from_f64(2.0) * w.acos()).to_degrees())
+    }
+
+    /// Norm length of the quaternion
+    #[inline]
+    pub fn norm(&self) -> T {
+        self.norm_squared().sqrt()
+    }
+
+    /// Return the unit quaternion with the same direction, a zero quaternion stays zero
+    #[inline]
+    pub fn normalized(&self) -> Quaternion<T> {
+        let n = self.norm();
+        if n == T::zero() {
+            return *self;
+        }
+        Quaternion::div_item(*self, n)
+    }
+
+    /// Multiplicative inverse, None for the zero quaternion
+    #[inline]
+    pub fn inverse(&self) -> Option<Quaternion<T>> {
+        let n = self.norm_squared();
+        if n == T::zero() {
+            return None;
+        }
+        Some(Quaternion::div_item(self.conjugate(), n))
+    }
+
+    /// Rotate a 3-dimensional vector by a unit quaternion
+    #[inline]
+    pub fn rotate_vector(&self, v: Vector3d<T>) -> Vector3d<T> {
+        let u = self.vector();
+        let t = Vector3d::mul_item(u.cross(v), T::from_f64(2.0));
+        v.add(Vector3d::mul_item(t, self.w)).add(u.cross(t))
+    }
+
+    /// Normalized linear interpolation along the shortest arc, t in [0, 1]
+    pub fn nlerp(&self, q: Quaternion<T>, t: T) -> Quaternion<T> {
+        let q = if self.dot(q) < T::zero() { Quaternion::mul_item(q, -T::one()) } else { q };
+        Quaternion::mul_item(*self, T::one() - t).add(Quaternion::mul_item(q, t)).normalized()
+    }
+
+    /** Spherical linear interpolation of two unit quaternions along the shortest arc with constant angular velocity, t in [0, 1], Example
+    ```rust
+    use QRender::matrix::quaternion::Quaternion;
+    use QRender::matrix::vector3d::Vector3d;
+
+    let a = Quaternion::identity(1.0_f32);
+    let b = Quaternion::from_axis_angle(Vector3d::new(0.0, 1.0, 0.0), 90.0);
+    let q = a.slerp(b, 0.5);
+    ```
+
+    output q.to_axis_angle():
+    ```text
+    ([0.0 1.0 0.0], 45.0)
+    ```
+     */
+    pub fn slerp(&self, q: Quaternion<T>, t: T) -> Quaternion<T> {
+        let mut cos_theta = self.dot(q);
+        let mut q = q;
+        if cos_theta < T::zero() {
+            q = Quaternion::mul_item(q, -T::one());
+            cos_theta = -cos_theta;
+        }
+        // sin(theta) vanishes for nearly parallel quaternions, where nlerp is indistinguishable
+        if cos_theta > T::from_f64(0.9995) {
+            return self.nlerp(q, t);
+        }
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let a = ((T::one() - t) * theta).sin() / sin_theta;
+        let b = (t * theta).sin() / sin_theta;
+        Quaternion::mul_item(*self, a).add(Quaternion::mul_item(q, b))
+    }
+
+    /// Rotation matrix of a unit quaternion
+    pub fn to_matrix3d(&self) -> Matrix3d<T> {
+        let two = T::from_f64(2.0);
+        let (x, y, z, w) = (self.x, self.y, self.z, self.w);
+        Matrix3d::new(
+            Vector3d::new(T::one() - two * (y * y + z * z), two * (x * y - w * z), two * (x * z + w * y)),
+            Vector3d::new(two * (x * y + w * z), T::one() - two * (x * x + z * z), two * (y * z - w * x)),
+            Vector3d::new(two * (x * z - w * y), two * (y * z + w * x), T::one() - two * (x * x + y * y)),
+        )
+    }
+
+    /// Homogeneous rotation matrix of a unit quaternion
+    pub fn to_matrix4d(&self) -> Matrix4d<T> {
+        let m = self.to_matrix3d();
+        Matrix4d::new(
+            m.items[0].to_vector4d(T::zero()),
+            m.items[1].to_vector4d(T::zero()),
+            m.items[2].to_vector4d(T::zero()),
+            Vector4d::new(T::zero(), T::zero(), T::zero(), T::one()),
+        )
+    }
+
+    /// Unit quaternion of a rotation matrix, the matrix must be orthonormal
+    pub fn from_matrix3d(m: Matrix3d<T>) -> Quaternion<T> {
+        let two = T::from_f64(2.0);
+        let r = &m.items;
+        let trace = r[0].x + r[1].y + r[2].z;
+        // Pick the largest of w, x, y and z as the pivot to stay away from a division by a small number
+        let q = if trace > T::zero() {
+            let s = (trace + T::one()).sqrt() * two;
+            Quaternion::new((r[2].y - r[1].z) / s, (r[0].z - r[2].x) / s, (r[1].x - r[0].y) / s, T::from_f64(0.25) * s)
+        } else if r[0].x > r[1].y && r[0].x > r[2].z {
+            let s = (T::one() + r[0].x - r[1].y - r[2].z).sqrt() * two;
+            Quaternion::new(T::from_f64(0.25) * s, (r[0].y + r[1].x) / s, (r[0].z + r[2].x) / s, (r[2].y - r[1].z) / s)
+        } else if r[1].y > r[2].z {
+            let s = (T::one() + r[1].y - r[0].x - r[2].z).sqrt() * two;
+            Quaternion::new((r[0].y + r[1].x) / s, T::from_f64(0.25) * s, (r[1].z + r[2].y) / s, (r[0].z - r[2].x) / s)
+        } else {
+            let s = (T::one() + r[2].z - r[0].x - r[1].y).sqrt() * two;
+            Quaternion::new((r[0].z + r[2].x) / s, (r[1].z + r[2].y) / s, T::from_f64(0.25) * s, (r[1].x - r[0].y) / s)
+        };
+        q.normalized()
+    }
+
+    /// Unit quaternion of the upper left 3x3 rotation part of a homogeneous matrix
+    pub fn from_matrix4d(m: Matrix4d<T>) -> Quaternion<T> {
+        Self::from_matrix3d(Matrix3d::new(m.items[0].head3(), m.items[1].head3(), m.items[2].head3()))
+    }
+}
+
+impl<T> fmt::Display for Quaternion<T>
+    where T:
+    fmt::Debug +
+    Copy +
+    PartialOrd +
+    PartialEq +
+    ops::Add<Output=T> +
+    ops::Mul<Output=T> +
+    ops::Div<Output=T> +
+    ops::Sub<Output=T>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}i + {:?}j + {:?}k + {:?}", self.x, self.y, self.z, self.w)
+    }
+}
+
+impl<T> ops::Add for Quaternion<T>
+    where T:
+    fmt::Debug +
+    Copy +
+    PartialOrd +
+    PartialEq +
+    ops::Add<Output=T> +
+    ops::Mul<Output=T> +
+    ops::Div<Output=T> +
+    ops::Sub<Output=T>
+{
+    type Output = Quaternion<T>;
+
+    /// Add two quaternions component-wise
+    #[inline]
+    fn add(self, q: Quaternion<T>) -> Quaternion<T> {
+        Quaternion { x: self.x + q.x, y: self.y + q.y, z: self.z + q.z, w: self.w + q.w }
+    }
+}
+
+impl<T> ops::Sub for Quaternion<T>
+    where T:
+    fmt::Debug +
+    Copy +
+    PartialOrd +
+    PartialEq +
+    ops::Add<Output=T> +
+    ops::Mul<Output=T> +
+    ops::Div<Output=T> +
+    ops::Sub<Output=T>
+{
+    type Output = Quaternion<T>;
+
+    /// Subtract two quaternions component-wise
+    #[inline]
+    fn sub(self, q: Quaternion<T>) -> Quaternion<T> {
+        Quaternion { x: self.x - q.x, y: self.y - q.y, z: self.z - q.z, w: self.w - q.w }
+    }
+}
+
+impl<T> ops::Mul for Quaternion<T>
+    where T: Scalar
+{
+    type Output = Quaternion<T>;
+
+    /// Hamilton product of two quaternions
+    #[inline]
+    fn mul(self, q: Quaternion<T>) -> Quaternion<T> {
+        self.product(q)
+    }
+}
@@ -0,0 +1,127 @@
+use std::{fmt, ops};
+
+/** Item type of the vectors, matrices and quaternions
+  Every primitive number type is a Scalar, it supports the Copy trait, comparison and alternate addition, subtraction, multiplication and division.
+ */
+pub trait Scalar:
+    fmt::Debug +
+    Copy +
+    PartialOrd +
+    PartialEq +
+    ops::Add<Output=Self> +
+    ops::Mul<Output=Self> +
+    ops::Div<Output=Self> +
+    ops::Sub<Output=Self>
+{
+    /// The additive identity
+    fn zero() -> Self;
+
+    /// The multiplicative identity
+    fn one() -> Self;
+}
+
+/** Floating point Scalar
+  Supports square root, absolute value, min and max, trigonometry and the machine epsilon, implemented for f32 and f64.
+  Angles are in radians, use to_radians and to_degrees to convert.
+ */
+pub trait Float: Scalar + ops::Neg<Output=Self> {
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn clamp(self, min: Self, max: Self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn asin(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn to_radians(self) -> Self;
+    fn to_degrees(self) -> Self;
+
+    /// The difference between 1.0 and the next larger representable number
+    fn epsilon() -> Self;
+
+    /// Convert a constant, possibly losing precision
+    fn from_f64(v: f64) -> Self;
+}
+
+macro_rules! impl_scalar {
+    ($($t: ty),* ; $zero: expr, $one: expr) => {
+        $(
+            impl Scalar for $t {
+                #[inline]
+                fn zero() -> Self { $zero }
+
+                #[inline]
+                fn one() -> Self { $one }
+            }
+        )*
+    };
+}
+
+impl_scalar!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize; 0, 1);
+impl_scalar!(f32, f64; 0.0, 1.0);
+
+macro_rules! impl_float {
+    ($($t: ident),*) => {
+        $(
+            impl Float for $t {
+                #[inline]
+                fn sqrt(self) -> Self { $t::sqrt(self) }
+
+                #[inline]
+                fn abs(self) -> Self { $t::abs(self) }
+
+                #[inline]
+                fn min(self, other: Self) -> Self { $t::min(self, other) }
+
+                #[inline]
+                fn max(self, other: Self) -> Self { $t::max(self, other) }
+
+                #[inline]
+                fn clamp(self, min: Self, max: Self) -> Self { $t::clamp(self, min, max) }
+
+                #[inline]
+                fn floor(self) -> Self { $t::floor(self) }
+
+                #[inline]
+                fn ceil(self) -> Self { $t::ceil(self) }
+
+                #[inline]
+                fn sin(self) -> Self { $t::sin(self) }
+
+                #[inline]
+                fn cos(self) -> Self { $t::cos(self) }
+
+                #[inline]
+                fn tan(self) -> Self { $t::tan(self) }
+
+                #[inline]
+                fn asin(self) -> Self { $t::asin(self) }
+
+                #[inline]
+                fn acos(self) -> Self { $t::acos(self) }
+
+                #[inline]
+                fn atan2(self, other: Self) -> Self { $t::atan2(self, other) }
+
+                #[inline]
+                fn to_radians(self) -> Self { $t::to_radians(self) }
+
+                #[inline]
+                fn to_degrees(self) -> Self { $t::to_degrees(self) }
+
+                #[inline]
+                fn epsilon() -> Self { $t::EPSILON }
+
+                #[inline]
+                fn from_f64(v: f64) -> Self { v as $t }
+            }
+        )*
+    };
+}
+
+impl_float!(f32, f64);
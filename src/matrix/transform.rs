@@ -0,0 +1,164 @@
+//! Builders of the 4x4 homogeneous transformation matrices used by the rasterizer.
+//! The world is right-handed, cameras look down the negative Z axis and every angle is in degrees.
+
+use crate::matrix::matrix4d::Matrix4d;
+use crate::matrix::vector3d::Vector3d;
+use crate::matrix::vector4d::Vector4d;
+
+/// Range of the normalized device depth produced by the projection matrices
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DepthRange {
+    /// OpenGL convention, the near plane maps to -1 and the far plane to 1
+    NegativeOneToOne,
+    /// Direct3D, Vulkan and Metal convention, the near plane maps to 0 and the far plane to 1
+    ZeroToOne,
+}
+
+/// Translate by v
+pub fn translate(v: Vector3d<f32>) -> Matrix4d<f32> {
+    Matrix4d::new(
+        Vector4d::new(1.0_f32, 0.0_f32, 0.0_f32, v.x),
+        Vector4d::new(0.0_f32, 1.0_f32, 0.0_f32, v.y),
+        Vector4d::new(0.0_f32, 0.0_f32, 1.0_f32, v.z),
+        Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32),
+    )
+}
+
+/// Scale each axis by the matching component of v
+pub fn scale(v: Vector3d<f32>) -> Matrix4d<f32> {
+    Matrix4d::new(
+        Vector4d::new(v.x, 0.0_f32, 0.0_f32, 0.0_f32),
+        Vector4d::new(0.0_f32, v.y, 0.0_f32, 0.0_f32),
+        Vector4d::new(0.0_f32, 0.0_f32, v.z, 0.0_f32),
+        Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32),
+    )
+}
+
+/// Rotate angle degrees counter-clockwise around the X axis
+pub fn rotate_x(angle: f32) -> Matrix4d<f32> {
+    let (s, c) = angle.to_radians().sin_cos();
+    Matrix4d::new(
+        Vector4d::new(1.0_f32, 0.0_f32, 0.0_f32, 0.0_f32),
+        Vector4d::new(0.0_f32, c, -s, 0.0_f32),
+        Vector4d::new(0.0_f32, s, c, 0.0_f32),
+        Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32),
+    )
+}
+
+/// Rotate angle degrees counter-clockwise around the Y axis
+pub fn rotate_y(angle: f32) -> Matrix4d<f32> {
+    let (s, c) = angle.to_radians().sin_cos();
+    Matrix4d::new(
+        Vector4d::new(c, 0.0_f32, s, 0.0_f32),
+        Vector4d::new(0.0_f32, 1.0_f32, 0.0_f32, 0.0_f32),
+        Vector4d::new(-s, 0.0_f32, c, 0.0_f32),
+        Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32),
+    )
+}
+
+/// Rotate angle degrees counter-clockwise around the Z axis
+pub fn rotate_z(angle: f32) -> Matrix4d<f32> {
+    let (s, c) = angle.to_radians().sin_cos();
+    Matrix4d::new(
+        Vector4d::new(c, -s, 0.0_f32, 0.0_f32),
+        Vector4d::new(s, c, 0.0_f32, 0.0_f32),
+        Vector4d::new(0.0_f32, 0.0_f32, 1.0_f32, 0.0_f32),
+        Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32),
+    )
+}
+
+/** Rotate angle degrees counter-clockwise around an arbitrary axis with Rodrigues' formula, the axis does not need to be normalized, Example
+```rust
+use QRender::matrix::transform;
+use QRender::matrix::vector3d::Vector3d;
+
+let m = transform::rotate(Vector3d::new(0.0, 0.0, 1.0), 90.0);
+let v = m.product_with_vector4d(Vector3d::new(1.0, 0.0, 0.0).to_vector4d(1.0));
+```
+
+output v:
+```text
+[0.0 1.0 0.0 1.0]
+```
+ */
+pub fn rotate(axis: Vector3d<f32>, angle: f32) -> Matrix4d<f32> {
+    let n = axis.normalize();
+    let (s, c) = angle.to_radians().sin_cos();
+    let t = 1.0_f32 - c;
+    Matrix4d::new(
+        Vector4d::new(c + t * n.x * n.x, t * n.x * n.y - s * n.z, t * n.x * n.z + s * n.y, 0.0_f32),
+        Vector4d::new(t * n.x * n.y + s * n.z, c + t * n.y * n.y, t * n.y * n.z - s * n.x, 0.0_f32),
+        Vector4d::new(t * n.x * n.z - s * n.y, t * n.y * n.z + s * n.x, c + t * n.z * n.z, 0.0_f32),
+        Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32),
+    )
+}
+
+/** View matrix of a camera at eye looking at target, up only needs to be roughly upwards, Example
+```rust
+use QRender::matrix::transform;
+use QRender::matrix::vector3d::Vector3d;
+
+let view = transform::look_at(Vector3d::new(0.0, 0.0, 5.0), Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(0.0, 1.0, 0.0));
+let v = view.product_with_vector4d(Vector3d::new(0.0, 0.0, 0.0).to_vector4d(1.0));
+```
+
+output v, the target lies 5 units in front of the camera:
+```text
+[0.0 0.0 -5.0 1.0]
+```
+ */
+pub fn look_at(eye: Vector3d<f32>, target: Vector3d<f32>, up: Vector3d<f32>) -> Matrix4d<f32> {
+    let f = target.sub(eye).normalize();
+    let s = f.cross(up).normalize();
+    let u = s.cross(f);
+    Matrix4d::new(
+        Vector4d::new(s.x, s.y, s.z, -s.dot(eye)),
+        Vector4d::new(u.x, u.y, u.z, -u.dot(eye)),
+        Vector4d::new(-f.x, -f.y, -f.z, f.dot(eye)),
+        Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32),
+    )
+}
+
+/// Orthographic projection of the box [left, right] x [bottom, top] x [-near, -far] in view space,
+/// near and far are distances in front of the camera
+pub fn ortho(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32, depth: DepthRange) -> Matrix4d<f32> {
+    let (z_scale, z_offset) = match depth {
+        DepthRange::NegativeOneToOne => (-2.0_f32 / (far - near), -(far + near) / (far - near)),
+        DepthRange::ZeroToOne => (-1.0_f32 / (far - near), -near / (far - near)),
+    };
+    Matrix4d::new(
+        Vector4d::new(2.0_f32 / (right - left), 0.0_f32, 0.0_f32, -(right + left) / (right - left)),
+        Vector4d::new(0.0_f32, 2.0_f32 / (top - bottom), 0.0_f32, -(top + bottom) / (top - bottom)),
+        Vector4d::new(0.0_f32, 0.0_f32, z_scale, z_offset),
+        Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32),
+    )
+}
+
+/** Perspective projection with a vertical field of view of fov_y degrees, aspect_ratio is width / height,
+near and far are positive distances in front of the camera. The clip space w equals the view space distance to the camera, Example
+```rust
+use QRender::matrix::transform::{self, DepthRange};
+use QRender::matrix::vector3d::Vector3d;
+
+let proj = transform::perspective(90.0, 1.0, 1.0, 10.0, DepthRange::ZeroToOne);
+let v = proj.product_with_vector4d(Vector3d::new(0.0, 0.0, -1.0).to_vector4d(1.0));
+```
+
+output v, a point on the near plane has depth 0:
+```text
+[0.0 0.0 0.0 1.0]
+```
+ */
+pub fn perspective(fov_y: f32, aspect_ratio: f32, near: f32, far: f32, depth: DepthRange) -> Matrix4d<f32> {
+    let f = 1.0_f32 / (fov_y.to_radians() / 2.0_f32).tan();
+    let (z_scale, z_offset) = match depth {
+        DepthRange::NegativeOneToOne => ((far + near) / (near - far), 2.0_f32 * far * near / (near - far)),
+        DepthRange::ZeroToOne => (far / (near - far), far * near / (near - far)),
+    };
+    Matrix4d::new(
+        Vector4d::new(f / aspect_ratio, 0.0_f32, 0.0_f32, 0.0_f32),
+        Vector4d::new(0.0_f32, f, 0.0_f32, 0.0_f32),
+        Vector4d::new(0.0_f32, 0.0_f32, z_scale, z_offset),
+        Vector4d::new(0.0_f32, 0.0_f32, -1.0_f32, 0.0_f32),
+    )
+}
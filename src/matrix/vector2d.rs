@@ -4,6 +4,7 @@ use crate::matrix::utils::Major;
 use crate::matrix::vector3d::Vector3d;
 use crate::matrix::vector4d::Vector4d;
 use crate::matrix::vector_errors::{VectorError, VectorErrorType};
+use crate::matrix::scalar::Float;
 
 /** 2D vector
     Supports regular vector calculations
@@ -151,9 +152,9 @@ impl<T> Vector2d<T>
         self.x * w.x + self.y * w.y
     }
 
-    /// Find the norm length of a 2-dimensional vector
+    /// Squared Euclidean length of a 2-dimensional vector, also available for integer vectors
     #[inline]
-    pub fn norm(&self) -> T {
+    pub fn length_squared(&self) -> T {
         self.x * self.x + self.y * self.y
     }
 
@@ -197,24 +198,6 @@ impl<T> Vector2d<T>
         }
     }
 
-    /** Normalize a 2D vector, Example
-    ```rust
-    let mut v = Vector2d::new(3.0_f32, 5.0_f32);
-    v.normalized();
-    ```
-
-    result v:
-    ```
-    [0.08823529411764706, 0.14705882352941177]
-    ```
-    */
-    #[inline]
-    pub fn normalized(&mut self) {
-        let n = self.norm();
-        self.x = self.x / n;
-        self.y = self.y / n;
-    }
-
     /** Multiply a 2-dimensional vector left by a 2x2-dimensional matrix to return a 2-dimensional vector, Example
     ```rust
     let v1 = Vector2d::new(3, 5);
@@ -248,6 +231,53 @@ impl<T> Vector2d<T>
     }
 }
 
+impl<T> Vector2d<T>
+    where T: Float
+{
+    /// Euclidean length of a 2-dimensional vector
+    #[inline]
+    pub fn norm(&self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    /** Normalize a 2-dimensional vector in place, a zero vector is left unchanged, Example
+    ```rust
+    use QRender::matrix::vector2d::Vector2d;
+
+    let mut v = Vector2d::new(3.0_f32, 4.0_f32);
+    v.normalized();
+    ```
+
+    result v:
+    ```text
+    [0.6 0.8]
+    ```
+     */
+    #[inline]
+    pub fn normalized(&mut self) {
+        let n = self.norm();
+        if n == T::zero() {
+            return;
+        }
+        self.x = self.x / n;
+        self.y = self.y / n;
+    }
+
+    /// Return the unit vector with the same direction, a zero vector stays zero
+    #[inline]
+    pub fn normalize(&self) -> Vector2d<T> {
+        let n = self.norm();
+        if n == T::zero() {
+            return *self;
+        }
+        Vector2d {
+            x: self.x / n,
+            y: self.y / n,
+            major: self.major
+        }
+    }
+}
+
 impl<T> fmt::Display for Vector2d<T>
     where T:
     fmt::Debug +
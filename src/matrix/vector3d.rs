@@ -4,6 +4,7 @@ use crate::matrix::matrix3d::Matrix3d;
 use crate::matrix::utils::Major;
 use crate::matrix::vector2d::Vector2d;
 use crate::matrix::vector_errors::{VectorError, VectorErrorType};
+use crate::matrix::scalar::Float;
 
 /** 3D vector
    Supports regular vector calculations
@@ -224,31 +225,12 @@ impl<T> Vector3d<T>
         }
     }
 
-    /// Find the norm length of a 3-dimensional vector
+    /// Squared Euclidean length of a 3-dimensional vector, also available for integer vectors
     #[inline]
-    pub fn norm(&self) -> T {
+    pub fn length_squared(&self) -> T {
         self.x * self.x + self.y * self.y + self.z * self.z
     }
 
-    /** Normalize a 2D vector, Example
-    ```rust
-    let mut v = Vector3d::new(3.0_f32, 5.0_f32, 2.0_f32);
-    v.normalized();
-    ```
-
-    result v:
-    ```
-    [0.07894736842105263, 0.13157894736842105, 0.05263157894736842]
-    ```
-     */
-    #[inline]
-    pub fn normalized(&mut self) {
-        let n = self.norm();
-        self.x = self.x / n;
-        self.y = self.y / n;
-        self.z = self.z / n;
-    }
-
     /** Multiply a 3-dimensional vector left by a 3x3-dimensional matrix to return a 3-dimensional vector, Example
     ```rust
     let v1 = Vector3d::new(3, 5, 6);
@@ -283,6 +265,55 @@ impl<T> Vector3d<T>
     }
 }
 
+impl<T> Vector3d<T>
+    where T: Float
+{
+    /// Euclidean length of a 3-dimensional vector
+    #[inline]
+    pub fn norm(&self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    /** Normalize a 3-dimensional vector in place, a zero vector is left unchanged, Example
+    ```rust
+    use QRender::matrix::vector3d::Vector3d;
+
+    let mut v = Vector3d::new(2.0_f32, 3.0_f32, 6.0_f32);
+    v.normalized();
+    ```
+
+    result v:
+    ```text
+    [0.2857143 0.42857143 0.85714287]
+    ```
+     */
+    #[inline]
+    pub fn normalized(&mut self) {
+        let n = self.norm();
+        if n == T::zero() {
+            return;
+        }
+        self.x = self.x / n;
+        self.y = self.y / n;
+        self.z = self.z / n;
+    }
+
+    /// Return the unit vector with the same direction, a zero vector stays zero
+    #[inline]
+    pub fn normalize(&self) -> Vector3d<T> {
+        let n = self.norm();
+        if n == T::zero() {
+            return *self;
+        }
+        Vector3d {
+            x: self.x / n,
+            y: self.y / n,
+            z: self.z / n,
+            major: self.major
+        }
+    }
+}
+
 impl<T> fmt::Display for Vector3d<T>
     where T:
     fmt::Debug +
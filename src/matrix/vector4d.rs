@@ -4,6 +4,7 @@ use crate::matrix::utils::Major;
 use crate::matrix::vector2d::Vector2d;
 use crate::matrix::vector3d::Vector3d;
 use crate::matrix::vector_errors::{VectorError, VectorErrorType};
+use crate::matrix::scalar::Float;
 
 /** 4D vector
   Supports regular vector calculations
@@ -211,32 +212,12 @@ impl<T> Vector4d<T>
         }
     }
 
-    /// Find the norm length of a 4-dimensional vector
+    /// Squared Euclidean length of a 4-dimensional vector, also available for integer vectors
     #[inline]
-    pub fn norm(&self) -> T {
+    pub fn length_squared(&self) -> T {
         self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w
     }
 
-    /** Normalize a 2D vector, Example
-    ```rust
-    let mut v = Vector4d::new(3.0_f32, 5.0_f32, 2.0_f32, 6.0_f32);
-    v.normalized();
-    ```
-
-    result v:
-    ```
-    [0.04054054054054054, 0.06756756756756757, 0.02702702702702703, 0.08108108108108109]
-    ```
-     */
-    #[inline]
-    pub fn normalized(&mut self) {
-        let n = self.norm();
-        self.x = self.x / n;
-        self.y = self.y / n;
-        self.z = self.z / n;
-        self.w = self.w / n;
-    }
-
     /** Multiply a 4-dimensional vector left by a 4x4-dimensional matrix to return a 4-dimensional vector, Example
     ```rust
     let v1 = Vector4d::new(3, 5, 6, 1);
@@ -277,6 +258,57 @@ impl<T> Vector4d<T>
     }
 }
 
+impl<T> Vector4d<T>
+    where T: Float
+{
+    /// Euclidean length of a 4-dimensional vector
+    #[inline]
+    pub fn norm(&self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    /** Normalize a 4-dimensional vector in place, a zero vector is left unchanged, Example
+    ```rust
+    use QRender::matrix::vector4d::Vector4d;
+
+    let mut v = Vector4d::new(1.0_f32, 1.0_f32, 1.0_f32, 1.0_f32);
+    v.normalized();
+    ```
+
+    result v:
+    ```text
+    [0.5 0.5 0.5 0.5]
+    ```
+     */
+    #[inline]
+    pub fn normalized(&mut self) {
+        let n = self.norm();
+        if n == T::zero() {
+            return;
+        }
+        self.x = self.x / n;
+        self.y = self.y / n;
+        self.z = self.z / n;
+        self.w = self.w / n;
+    }
+
+    /// Return the unit vector with the same direction, a zero vector stays zero
+    #[inline]
+    pub fn normalize(&self) -> Vector4d<T> {
+        let n = self.norm();
+        if n == T::zero() {
+            return *self;
+        }
+        Vector4d {
+            x: self.x / n,
+            y: self.y / n,
+            z: self.z / n,
+            w: self.w / n,
+            major: self.major
+        }
+    }
+}
+
 impl<T> fmt::Display for Vector4d<T>
     where T:
     fmt::Debug +
@@ -0,0 +1,165 @@
+use crate::matrix::vector4d::Vector4d;
+
+/// Weight of the source or the destination color in the blend equation, src is the fragment and dst the color already in the frame buffer
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SrcColor,
+    OneMinusSrcColor,
+    DstColor,
+    OneMinusDstColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstAlpha,
+    OneMinusDstAlpha,
+}
+
+impl BlendFactor {
+    /// The per channel weight for the RGBA colors src and dst in [0, 1]
+    pub fn weight(&self, src: Vector4d<f32>, dst: Vector4d<f32>) -> Vector4d<f32> {
+        let one = Vector4d::fill(1.0_f32);
+        match self {
+            BlendFactor::Zero => Vector4d::fill(0.0_f32),
+            BlendFactor::One => one,
+            BlendFactor::SrcColor => src,
+            BlendFactor::OneMinusSrcColor => one.sub(src),
+            BlendFactor::DstColor => dst,
+            BlendFactor::OneMinusDstColor => one.sub(dst),
+            BlendFactor::SrcAlpha => Vector4d::fill(src.w),
+            BlendFactor::OneMinusSrcAlpha => Vector4d::fill(1.0_f32 - src.w),
+            BlendFactor::DstAlpha => Vector4d::fill(dst.w),
+            BlendFactor::OneMinusDstAlpha => Vector4d::fill(1.0_f32 - dst.w),
+        }
+    }
+}
+
+/// How the weighted source and destination are combined, Min and Max ignore the factors like in OpenGL
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BlendOp {
+    /// src * src_factor + dst * dst_factor
+    Add,
+    /// src * src_factor - dst * dst_factor
+    Subtract,
+    /// dst * dst_factor - src * src_factor
+    ReverseSubtract,
+    Min,
+    Max,
+}
+
+impl BlendOp {
+    fn apply(&self, src: f32, dst: f32, src_weight: f32, dst_weight: f32) -> f32 {
+        match self {
+            BlendOp::Add => src * src_weight + dst * dst_weight,
+            BlendOp::Subtract => src * src_weight - dst * dst_weight,
+            BlendOp::ReverseSubtract => dst * dst_weight - src * src_weight,
+            BlendOp::Min => src.min(dst),
+            BlendOp::Max => src.max(dst),
+        }
+    }
+}
+
+/** How fragments are combined with the frame buffer, the RGB channels use the color factors and op and the alpha channel the alpha ones
+  With premultiply the RGB of the fragment is multiplied by its alpha first, so shaders returning straight alpha can be drawn with premultiplied_alpha.
+  Results are clamped to [0, 1], except for replace which writes the fragment unchanged. Example
+```rust
+use QRender::matrix::vector4d::Vector4d;
+use QRender::rasterizer::blend::BlendState;
+
+let glass = Vector4d::new(0.0, 0.0, 1.0, 0.25);
+let wall = Vector4d::new(1.0, 1.0, 1.0, 1.0);
+let color = BlendState::alpha().blend(glass, wall);
+```
+
+output color:
+```text
+[0.75 0.75 1.0 1.0]
+```
+ */
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BlendState {
+    pub src_color:   BlendFactor,
+    pub dst_color:   BlendFactor,
+    pub color_op:    BlendOp,
+    pub src_alpha:   BlendFactor,
+    pub dst_alpha:   BlendFactor,
+    pub alpha_op:    BlendOp,
+    pub premultiply: bool,
+}
+
+impl BlendState {
+    /// Use the same factors and op for the color and the alpha channel
+    pub fn new(src: BlendFactor, dst: BlendFactor, op: BlendOp) -> BlendState {
+        BlendState::separate(src, dst, op, src, dst, op)
+    }
+
+    pub fn separate(src_color: BlendFactor, dst_color: BlendFactor, color_op: BlendOp,
+                    src_alpha: BlendFactor, dst_alpha: BlendFactor, alpha_op: BlendOp) -> BlendState {
+        BlendState { src_color, dst_color, color_op, src_alpha, dst_alpha, alpha_op, premultiply: false }
+    }
+
+    /// Overwrite the frame buffer, the default of the rasterizer
+    pub fn replace() -> BlendState {
+        BlendState::new(BlendFactor::One, BlendFactor::Zero, BlendOp::Add)
+    }
+
+    /// Straight alpha blending, src * src_alpha + dst * (1 - src_alpha), the alpha channel accumulates coverage
+    pub fn alpha() -> BlendState {
+        BlendState::separate(
+            BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha, BlendOp::Add,
+            BlendFactor::One, BlendFactor::OneMinusSrcAlpha, BlendOp::Add,
+        )
+    }
+
+    /// Blending of colors already multiplied by their alpha, src + dst * (1 - src_alpha)
+    pub fn premultiplied_alpha() -> BlendState {
+        BlendState::new(BlendFactor::One, BlendFactor::OneMinusSrcAlpha, BlendOp::Add)
+    }
+
+    /// Add the fragment weighted by its alpha, for glows and particles
+    pub fn additive() -> BlendState {
+        BlendState::separate(
+            BlendFactor::SrcAlpha, BlendFactor::One, BlendOp::Add,
+            BlendFactor::Zero, BlendFactor::One, BlendOp::Add,
+        )
+    }
+
+    /// Multiply the frame buffer by the fragment, for tinting
+    pub fn multiply() -> BlendState {
+        BlendState::separate(
+            BlendFactor::DstColor, BlendFactor::Zero, BlendOp::Add,
+            BlendFactor::Zero, BlendFactor::One, BlendOp::Add,
+        )
+    }
+
+    pub fn with_premultiply(mut self, premultiply: bool) -> BlendState {
+        self.premultiply = premultiply;
+        self
+    }
+
+    pub fn is_replace(&self) -> bool {
+        *self == BlendState::replace()
+    }
+
+    /// Combine the fragment src with the destination dst, both RGBA in [0, 1]
+    pub fn blend(&self, src: Vector4d<f32>, dst: Vector4d<f32>) -> Vector4d<f32> {
+        if self.is_replace() {
+            return src;
+        }
+        let src = if self.premultiply { Vector4d::new(src.x * src.w, src.y * src.w, src.z * src.w, src.w) } else { src };
+        let (sc, dc) = (self.src_color.weight(src, dst), self.dst_color.weight(src, dst));
+        let (sa, da) = (self.src_alpha.weight(src, dst), self.dst_alpha.weight(src, dst));
+        Vector4d::new(
+            self.color_op.apply(src.x, dst.x, sc.x, dc.x).clamp(0.0_f32, 1.0_f32),
+            self.color_op.apply(src.y, dst.y, sc.y, dc.y).clamp(0.0_f32, 1.0_f32),
+            self.color_op.apply(src.z, dst.z, sc.z, dc.z).clamp(0.0_f32, 1.0_f32),
+            self.alpha_op.apply(src.w, dst.w, sa.w, da.w).clamp(0.0_f32, 1.0_f32),
+        )
+    }
+}
+
+impl Default for BlendState {
+    fn default() -> BlendState {
+        BlendState::replace()
+    }
+}
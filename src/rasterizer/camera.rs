@@ -0,0 +1,290 @@
+use crate::matrix::matrix3d::Matrix3d;
+use crate::matrix::matrix4d::Matrix4d;
+use crate::matrix::quaternion::Quaternion;
+use crate::matrix::transform::{self, DepthRange};
+use crate::matrix::vector3d::Vector3d;
+
+/// How a camera maps its view volume onto the screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    /// Vertical field of view in degrees
+    Perspective { fov_y: f32 },
+    /// Height of the view volume in world units, the width follows the aspect ratio
+    Orthographic { height: f32 },
+}
+
+/** A camera placed at position and turned by orientation. Without rotation it looks down the negative Z axis with Y up,
+  like transform::look_at. near and far are positive distances in front of the camera and aspect_ratio is width / height. Example
+```rust
+use QRender::matrix::vector3d::Vector3d;
+use QRender::rasterizer::camera::Camera;
+
+let mut camera = Camera::perspective(45.0, 1.0, 0.1, 50.0);
+camera.position = Vector3d::new(0.0, 0.0, 5.0);
+camera.look_at(Vector3d::new(5.0, 0.0, 5.0), Vector3d::new(0.0, 1.0, 0.0));
+let forward = camera.forward();
+```
+
+output forward, the camera turned to the right:
+```text
+[1.0 0.0 0.0]
+```
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub position:     Vector3d<f32>,
+    /// Rotation from camera space to world space, a unit quaternion
+    pub orientation:  Quaternion<f32>,
+    pub projection:   Projection,
+    pub near:         f32,
+    pub far:          f32,
+    pub aspect_ratio: f32,
+}
+
+impl Camera {
+    /// A perspective camera at the origin looking down the negative Z axis, fov_y is in degrees
+    pub fn perspective(fov_y: f32, aspect_ratio: f32, near: f32, far: f32) -> Camera {
+        Camera {
+            position: Vector3d::fill(0.0_f32),
+            orientation: Quaternion::identity(1.0_f32),
+            projection: Projection::Perspective { fov_y },
+            near,
+            far,
+            aspect_ratio,
+        }
+    }
+
+    /// An orthographic camera at the origin looking down the negative Z axis, seeing height world units vertically
+    pub fn orthographic(height: f32, aspect_ratio: f32, near: f32, far: f32) -> Camera {
+        Camera { projection: Projection::Orthographic { height }, ..Camera::perspective(0.0_f32, aspect_ratio, near, far) }
+    }
+
+    /// Turn the camera towards target keeping up above the horizon. When target lies straight along up,
+    /// the negative Z axis is used as the up vector, or Y when up is Z
+    pub fn look_at(&mut self, target: Vector3d<f32>, up: Vector3d<f32>) {
+        let forward = target.sub(self.position);
+        if forward.norm() == 0.0_f32 {
+            return;
+        }
+        let f = forward.normalize();
+        let mut s = f.cross(up);
+        if s.norm() < 1e-6_f32 {
+            let fallback = if up.cross(Vector3d::new(0.0_f32, 0.0_f32, 1.0_f32)).norm() < 1e-6_f32 {
+                Vector3d::new(0.0_f32, 1.0_f32, 0.0_f32)
+            } else {
+                Vector3d::new(0.0_f32, 0.0_f32, -1.0_f32)
+            };
+            s = f.cross(fallback);
+        }
+        let s = s.normalize();
+        let u = s.cross(f);
+        // The columns are the camera axes in world space: right, up and backward
+        let rotation = Matrix3d::new(
+            Vector3d::new(s.x, u.x, -f.x),
+            Vector3d::new(s.y, u.y, -f.y),
+            Vector3d::new(s.z, u.z, -f.z),
+        );
+        self.orientation = Quaternion::from_matrix3d(rotation).normalized();
+    }
+
+    /// The direction the camera looks in
+    pub fn forward(&self) -> Vector3d<f32> {
+        self.orientation.rotate_vector(Vector3d::new(0.0_f32, 0.0_f32, -1.0_f32))
+    }
+
+    pub fn right(&self) -> Vector3d<f32> {
+        self.orientation.rotate_vector(Vector3d::new(1.0_f32, 0.0_f32, 0.0_f32))
+    }
+
+    pub fn up(&self) -> Vector3d<f32> {
+        self.orientation.rotate_vector(Vector3d::new(0.0_f32, 1.0_f32, 0.0_f32))
+    }
+
+    /// World to camera space, the inverse of the pose of the camera
+    pub fn view_matrix(&self) -> Matrix4d<f32> {
+        self.orientation.conjugate().to_matrix4d() * transform::translate(Vector3d::mul_item(self.position, -1.0_f32))
+    }
+
+    pub fn projection_matrix(&self, depth: DepthRange) -> Matrix4d<f32> {
+        match self.projection {
+            Projection::Perspective { fov_y } => transform::perspective(fov_y, self.aspect_ratio, self.near, self.far, depth),
+            Projection::Orthographic { height } => {
+                let (half_w, half_h) = (height * self.aspect_ratio / 2.0_f32, height / 2.0_f32);
+                transform::ortho(-half_w, half_w, -half_h, half_h, self.near, self.far, depth)
+            }
+        }
+    }
+}
+
+/// Places a camera from a few parameters that animation scripts change by small deltas between frames
+pub trait CameraController {
+    /// Move and turn camera to the pose of the controller, the projection is left alone
+    fn update(&self, camera: &mut Camera);
+}
+
+/// Orientation of a camera turned yaw degrees to the left around the world Y axis, then pitch degrees up
+fn yaw_pitch(yaw: f32, pitch: f32) -> Quaternion<f32> {
+    let q_yaw = Quaternion::from_axis_angle(Vector3d::new(0.0_f32, 1.0_f32, 0.0_f32), yaw);
+    let q_pitch = Quaternion::from_axis_angle(Vector3d::new(1.0_f32, 0.0_f32, 0.0_f32), pitch);
+    q_yaw.product(q_pitch).normalized()
+}
+
+/// Pose of a camera on a sphere around target looking at it, yaw 0 and pitch 0 put the camera on the +Z side
+fn orbit_pose(camera: &mut Camera, target: Vector3d<f32>, distance: f32, yaw: f32, pitch: f32) {
+    camera.orientation = yaw_pitch(yaw, -pitch);
+    camera.position = target.sub(Vector3d::mul_item(camera.forward(), distance));
+}
+
+const MAX_PITCH: f32 = 89.0_f32;
+
+/** Orbit around a target point, dragging the mouse usually maps to rotate and the wheel to zoom
+  yaw turns the camera around the world Y axis, counter-clockwise seen from above, and pitch raises it above the target,
+  it is clamped to (-90, 90) degrees so that the camera never flips over the pole. Example
+```rust
+use QRender::matrix::vector3d::Vector3d;
+use QRender::rasterizer::camera::{Camera, CameraController, OrbitController};
+
+let mut camera = Camera::perspective(45.0, 1.0, 0.1, 50.0);
+let mut orbit = OrbitController::new(Vector3d::fill(0.0), 4.0);
+orbit.rotate(90.0, 0.0);
+orbit.update(&mut camera);
+let position = camera.position;
+```
+
+output position, a quarter turn from the +Z side:
+```text
+[4.0 0.0 0.0]
+```
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitController {
+    pub target:       Vector3d<f32>,
+    pub distance:     f32,
+    /// Degrees around the world Y axis
+    pub yaw:          f32,
+    /// Degrees above the horizontal plane through target
+    pub pitch:        f32,
+    /// zoom keeps the distance in [min_distance, max_distance]
+    pub min_distance: f32,
+    pub max_distance: f32,
+}
+
+impl OrbitController {
+    pub fn new(target: Vector3d<f32>, distance: f32) -> OrbitController {
+        OrbitController { target, distance, yaw: 0.0_f32, pitch: 0.0_f32, min_distance: 1e-3_f32, max_distance: f32::INFINITY }
+    }
+
+    /// Turn around the target by degrees, the pitch stays within (-90, 90)
+    pub fn rotate(&mut self, d_yaw: f32, d_pitch: f32) {
+        self.yaw = (self.yaw + d_yaw) % 360.0_f32;
+        self.pitch = (self.pitch + d_pitch).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    /// Multiply the distance by factor, below 1 moves closer
+    pub fn zoom(&mut self, factor: f32) {
+        self.distance = (self.distance * factor).clamp(self.min_distance, self.max_distance);
+    }
+
+    /// Move the target and the camera by dx and dy world units along the right and up axes of the camera
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let orientation = yaw_pitch(self.yaw, -self.pitch);
+        let right = orientation.rotate_vector(Vector3d::new(1.0_f32, 0.0_f32, 0.0_f32));
+        let up = orientation.rotate_vector(Vector3d::new(0.0_f32, 1.0_f32, 0.0_f32));
+        self.target = self.target.add(Vector3d::mul_item(right, dx)).add(Vector3d::mul_item(up, dy));
+    }
+}
+
+impl CameraController for OrbitController {
+    fn update(&self, camera: &mut Camera) {
+        orbit_pose(camera, self.target, self.distance, self.yaw, self.pitch);
+    }
+}
+
+/** First person camera moving freely, yaw turns left around the world Y axis and pitch looks up, clamped to (-90, 90) degrees.
+  Translations follow the current view direction, up is the world Y axis.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlyController {
+    pub position: Vector3d<f32>,
+    pub yaw:      f32,
+    pub pitch:    f32,
+}
+
+impl FlyController {
+    /// A camera at position looking down the negative Z axis
+    pub fn new(position: Vector3d<f32>) -> FlyController {
+        FlyController { position, yaw: 0.0_f32, pitch: 0.0_f32 }
+    }
+
+    pub fn rotate(&mut self, d_yaw: f32, d_pitch: f32) {
+        self.yaw = (self.yaw + d_yaw) % 360.0_f32;
+        self.pitch = (self.pitch + d_pitch).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    /// Move forward along the view direction, right along the horizontal right axis and up along the world Y axis, in world units
+    pub fn translate(&mut self, forward: f32, right: f32, up: f32) {
+        let orientation = yaw_pitch(self.yaw, self.pitch);
+        let forward_axis = orientation.rotate_vector(Vector3d::new(0.0_f32, 0.0_f32, -1.0_f32));
+        let right_axis = orientation.rotate_vector(Vector3d::new(1.0_f32, 0.0_f32, 0.0_f32));
+        self.position = self.position
+            .add(Vector3d::mul_item(forward_axis, forward))
+            .add(Vector3d::mul_item(right_axis, right))
+            .add(Vector3d::new(0.0_f32, up, 0.0_f32));
+    }
+}
+
+impl CameraController for FlyController {
+    fn update(&self, camera: &mut Camera) {
+        camera.orientation = yaw_pitch(self.yaw, self.pitch);
+        camera.position = self.position;
+    }
+}
+
+/** A camera circling a target at a fixed distance and elevation, like a model on a turntable seen from a still camera
+  advance turns it by speed degrees per unit of time, a full turn takes 360 / speed. Example
+```rust
+use QRender::matrix::vector3d::Vector3d;
+use QRender::rasterizer::camera::{Camera, CameraController, TurntableController};
+
+let mut camera = Camera::perspective(45.0, 1.0, 0.1, 50.0);
+let mut turntable = TurntableController::new(Vector3d::fill(0.0), 2.0, 0.0, 30.0);
+// 3 frames of 1 / 24 second at 30 degrees per second
+for _ in 0..3 {
+    turntable.advance(1.0 / 24.0);
+}
+let angle = turntable.angle;
+```
+
+output angle:
+```text
+3.75
+```
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TurntableController {
+    pub target:    Vector3d<f32>,
+    pub distance:  f32,
+    /// Degrees above the horizontal plane through target
+    pub elevation: f32,
+    /// Degrees around the world Y axis, 0 is the +Z side
+    pub angle:     f32,
+    /// Degrees per unit of time
+    pub speed:     f32,
+}
+
+impl TurntableController {
+    pub fn new(target: Vector3d<f32>, distance: f32, elevation: f32, speed: f32) -> TurntableController {
+        TurntableController { target, distance, elevation: elevation.clamp(-MAX_PITCH, MAX_PITCH), angle: 0.0_f32, speed }
+    }
+
+    /// Turn by speed * dt degrees
+    pub fn advance(&mut self, dt: f32) {
+        self.angle = (self.angle + self.speed * dt) % 360.0_f32;
+    }
+}
+
+impl CameraController for TurntableController {
+    fn update(&self, camera: &mut Camera) {
+        orbit_pose(camera, self.target, self.distance, self.angle, self.elevation);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::matrix::transform::DepthRange;
+use crate::matrix::vector4d::Vector4d;
+use crate::rasterizer::shader::{Varyings, VertexOutput};
+
+/// The six planes of the view frustum in clip space, a vertex is inside a plane when distance returns a non-negative value
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ClipPlane {
+    Left,
+    Right,
+    Bottom,
+    Top,
+    Near,
+    Far,
+}
+
+impl ClipPlane {
+    pub const ALL: [ClipPlane; 6] = [ClipPlane::Near, ClipPlane::Far, ClipPlane::Left, ClipPlane::Right, ClipPlane::Bottom, ClipPlane::Top];
+
+    /// Signed distance of the homogeneous position p to the plane, scaled by an arbitrary positive factor
+    #[inline]
+    pub fn distance(&self, p: Vector4d<f32>, depth: DepthRange) -> f32 {
+        match self {
+            ClipPlane::Left => p.w + p.x,
+            ClipPlane::Right => p.w - p.x,
+            ClipPlane::Bottom => p.w + p.y,
+            ClipPlane::Top => p.w - p.y,
+            ClipPlane::Near => match depth {
+                DepthRange::NegativeOneToOne => p.w + p.z,
+                DepthRange::ZeroToOne => p.z,
+            },
+            ClipPlane::Far => p.w - p.z,
+        }
+    }
+}
+
+/** Clip a triangle in homogeneous clip space against the view frustum with the Sutherland-Hodgman algorithm
+  Returns the vertices of the clipped convex polygon in the winding order of the triangle, empty when the triangle is outside the frustum.
+  The positions and varyings of new vertices are interpolated linearly in clip space, so no division by w happens before clipping. Example
+```rust
+use QRender::matrix::transform::DepthRange;
+use QRender::matrix::vector2d::Vector2d;
+use QRender::matrix::vector3d::Vector3d;
+use QRender::matrix::vector4d::Vector4d;
+use QRender::rasterizer::clip::clip_triangle;
+use QRender::rasterizer::shader::{Varyings, VertexOutput};
+
+let vertex = |x: f32, y: f32| VertexOutput {
+    position: Vector4d::new(x, y, 0.0, 1.0),
+    varyings: Varyings {
+        color: Vector3d::fill(1.0),
+        normal: Vector3d::fill(0.0),
+        tex_coords: Vector2d::fill(0.0),
+        world_pos: Vector3d::fill(0.0),
+    },
+};
+// The right corner pokes out of the frustum and is cut off
+let polygon = clip_triangle(&[vertex(0.0, 0.0), vertex(2.0, 0.0), vertex(0.0, 0.5)], DepthRange::NegativeOneToOne);
+```
+
+output polygon.len(), a quad:
+```text
+4
+```
+ */
+pub fn clip_triangle(triangle: &[VertexOutput; 3], depth: DepthRange) -> Vec<VertexOutput> {
+    let mut polygon = triangle.to_vec();
+    for plane in ClipPlane::ALL.iter() {
+        let distances: Vec<f32> = polygon.iter().map(|v| plane.distance(v.position, depth)).collect();
+        if distances.iter().all(|d| *d >= 0.0_f32) {
+            continue;
+        }
+        if distances.iter().all(|d| *d < 0.0_f32) {
+            return vec![];
+        }
+
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for i in 0..polygon.len() {
+            let j = (i + 1) % polygon.len();
+            let (d0, d1) = (distances[i], distances[j]);
+            if d0 >= 0.0_f32 {
+                clipped.push(polygon[i]);
+            }
+            if (d0 >= 0.0_f32) != (d1 >= 0.0_f32) {
+                clipped.push(intersect(&polygon[i], &polygon[j], d0 / (d0 - d1)));
+            }
+        }
+        polygon = clipped;
+    }
+    polygon
+}
+
+/// Clip the segment a -> b in homogeneous clip space against the view frustum, returns the range (t0, t1) of its visible part
+/// where t = 0 is a and t = 1 is b, or None when the whole segment is outside
+pub fn clip_line(a: Vector4d<f32>, b: Vector4d<f32>, depth: DepthRange) -> Option<(f32, f32)> {
+    let (mut t0, mut t1) = (0.0_f32, 1.0_f32);
+    for plane in ClipPlane::ALL.iter() {
+        let (da, db) = (plane.distance(a, depth), plane.distance(b, depth));
+        if da < 0.0_f32 && db < 0.0_f32 {
+            return None;
+        }
+        if da < 0.0_f32 {
+            t0 = t0.max(da / (da - db));
+        } else if db < 0.0_f32 {
+            t1 = t1.min(da / (da - db));
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+    Some((t0, t1))
+}
+
+/// The vertex at t along the edge a -> b
+fn intersect(a: &VertexOutput, b: &VertexOutput, t: f32) -> VertexOutput {
+    VertexOutput {
+        position: Vector4d::mul_item(a.position, 1.0_f32 - t).add(Vector4d::mul_item(b.position, t)),
+        varyings: Varyings::lerp(&a.varyings, &b.varyings, t),
+    }
+}
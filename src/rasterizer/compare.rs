@@ -0,0 +1,183 @@
+use image::{Rgb, RgbImage};
+use crate::rasterizer::rasterizer_errors::RasterizerError;
+
+/// Thresholds for ImageDiff::within, the default accepts the rounding differences between platforms and thread counts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// Largest difference of a channel for two pixels to match
+    pub pixel:          u8,
+    /// Fraction of the pixels allowed to not match
+    pub max_mismatched: f32,
+    /// Smallest accepted PSNR in dB
+    pub min_psnr:       f32,
+    /// Smallest accepted SSIM
+    pub min_ssim:       f32,
+}
+
+impl Tolerance {
+    pub fn new(pixel: u8, max_mismatched: f32, min_psnr: f32, min_ssim: f32) -> Tolerance {
+        Tolerance { pixel, max_mismatched, min_psnr, min_ssim }
+    }
+
+    /// Only identical images pass
+    pub fn exact() -> Tolerance {
+        Tolerance::new(0, 0.0_f32, f32::INFINITY, 1.0_f32)
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Tolerance {
+        Tolerance::new(2, 0.001_f32, 40.0_f32, 0.99_f32)
+    }
+}
+
+/// How far an image is from a reference image of the same size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageDiff {
+    /// Largest difference of a channel over the image
+    pub max_diff:   u8,
+    /// Number of pixels with a channel differing by more than the pixel tolerance
+    pub mismatched: usize,
+    pub pixels:     usize,
+    /// Peak signal to noise ratio in dB, infinite for identical images
+    pub psnr:       f32,
+    /// Mean structural similarity of the luma over 7 x 7 windows, 1 for identical images
+    pub ssim:       f32,
+}
+
+impl ImageDiff {
+    /// Whether every metric is within tolerance, the pixel tolerance is the one the diff was computed with
+    pub fn within(&self, tolerance: &Tolerance) -> bool {
+        self.mismatched as f32 <= tolerance.max_mismatched * self.pixels as f32
+            && self.psnr >= tolerance.min_psnr
+            && self.ssim >= tolerance.min_ssim
+    }
+}
+
+/** Compare an image with a reference image, pixels match when no channel differs by more than tolerance.pixel, Example
+```rust
+use image::{Rgb, RgbImage};
+use QRender::rasterizer::compare::{self, Tolerance};
+
+let expected = RgbImage::from_pixel(4, 4, Rgb([100, 100, 100]));
+let mut actual = expected.clone();
+actual.put_pixel(0, 0, Rgb([101, 100, 100]));
+actual.put_pixel(3, 3, Rgb([200, 100, 100]));
+let diff = compare::compare(&actual, &expected, &Tolerance::default()).unwrap();
+let result = (diff.max_diff, diff.mismatched, diff.within(&Tolerance::default()));
+```
+
+output result, only the second pixel is out of tolerance:
+```text
+(100, 1, false)
+```
+ */
+pub fn compare(actual: &RgbImage, expected: &RgbImage, tolerance: &Tolerance) -> Result<ImageDiff, RasterizerError> {
+    check_size(actual, expected)?;
+    let mut max_diff = 0_u8;
+    let mut mismatched = 0_usize;
+    let mut squared_error = 0.0_f64;
+    for (a, e) in actual.pixels().zip(expected.pixels()) {
+        let diff = channel_diff(a, e);
+        max_diff = max_diff.max(diff);
+        if diff > tolerance.pixel {
+            mismatched += 1;
+        }
+        squared_error += a.0.iter().zip(e.0.iter()).map(|(x, y)| (*x as f64 - *y as f64).powi(2)).sum::<f64>();
+    }
+    let pixels = (actual.width() * actual.height()) as usize;
+    Ok(ImageDiff { max_diff, mismatched, pixels, psnr: psnr_of(squared_error, pixels * 3), ssim: ssim(actual, expected)? })
+}
+
+/// Peak signal to noise ratio of the RGB channels in dB, infinite for identical images
+pub fn psnr(actual: &RgbImage, expected: &RgbImage) -> Result<f32, RasterizerError> {
+    check_size(actual, expected)?;
+    let squared_error = actual.as_raw().iter().zip(expected.as_raw().iter()).map(|(x, y)| (*x as f64 - *y as f64).powi(2)).sum();
+    Ok(psnr_of(squared_error, actual.as_raw().len()))
+}
+
+/** Mean structural similarity of the luma over every 7 x 7 window, smaller images use one window of their size
+  Identical images give 1, unrelated ones give values near 0.
+ */
+pub fn ssim(actual: &RgbImage, expected: &RgbImage) -> Result<f32, RasterizerError> {
+    check_size(actual, expected)?;
+    let (width, height) = (actual.width() as usize, actual.height() as usize);
+    if width == 0 || height == 0 {
+        return Ok(1.0_f32);
+    }
+    let a = luma(actual);
+    let e = luma(expected);
+    let (window_w, window_h) = (width.min(7), height.min(7));
+    let c1 = (0.01_f64 * 255.0_f64).powi(2);
+    let c2 = (0.03_f64 * 255.0_f64).powi(2);
+    let n = (window_w * window_h) as f64;
+
+    let mut total = 0.0_f64;
+    let mut windows = 0_usize;
+    for y in 0..=height - window_h {
+        for x in 0..=width - window_w {
+            let (mut sum_a, mut sum_e, mut sum_aa, mut sum_ee, mut sum_ae) = (0.0_f64, 0.0_f64, 0.0_f64, 0.0_f64, 0.0_f64);
+            for wy in y..y + window_h {
+                for wx in x..x + window_w {
+                    let (va, ve) = (a[wy * width + wx], e[wy * width + wx]);
+                    sum_a += va;
+                    sum_e += ve;
+                    sum_aa += va * va;
+                    sum_ee += ve * ve;
+                    sum_ae += va * ve;
+                }
+            }
+            let (mean_a, mean_e) = (sum_a / n, sum_e / n);
+            let var_a = sum_aa / n - mean_a * mean_a;
+            let var_e = sum_ee / n - mean_e * mean_e;
+            let covariance = sum_ae / n - mean_a * mean_e;
+            total += (2.0_f64 * mean_a * mean_e + c1) * (2.0_f64 * covariance + c2)
+                / ((mean_a * mean_a + mean_e * mean_e + c1) * (var_a + var_e + c2));
+            windows += 1;
+        }
+    }
+    Ok((total / windows as f64) as f32)
+}
+
+/** Visualize the difference of two images: matching pixels are a dark gray copy of the reference,
+  pixels differing by more than pixel_tolerance are red, brighter for larger differences.
+ */
+pub fn diff_image(actual: &RgbImage, expected: &RgbImage, pixel_tolerance: u8) -> Result<RgbImage, RasterizerError> {
+    check_size(actual, expected)?;
+    Ok(RgbImage::from_fn(actual.width(), actual.height(), |x, y| {
+        let (a, e) = (actual.get_pixel(x, y), expected.get_pixel(x, y));
+        let diff = channel_diff(a, e);
+        if diff > pixel_tolerance {
+            Rgb([128 + diff / 2, 0, 0])
+        } else {
+            let gray = ((e.0[0] as u16 + e.0[1] as u16 + e.0[2] as u16) / 12) as u8;
+            Rgb([gray, gray, gray])
+        }
+    }))
+}
+
+fn check_size(actual: &RgbImage, expected: &RgbImage) -> Result<(), RasterizerError> {
+    if actual.dimensions() != expected.dimensions() {
+        return Err(RasterizerError {
+            err_code: 5009,
+            message: format!("{}x{} and {}x{}", actual.width(), actual.height(), expected.width(), expected.height()),
+        });
+    }
+    Ok(())
+}
+
+fn channel_diff(a: &Rgb<u8>, e: &Rgb<u8>) -> u8 {
+    a.0.iter().zip(e.0.iter()).map(|(x, y)| x.abs_diff(*y)).max().unwrap_or(0)
+}
+
+fn psnr_of(squared_error: f64, samples: usize) -> f32 {
+    if squared_error == 0.0_f64 {
+        return f32::INFINITY;
+    }
+    (10.0_f64 * (255.0_f64 * 255.0_f64 * samples as f64 / squared_error).log10()) as f32
+}
+
+/// BT.601 luma of every pixel
+fn luma(image: &RgbImage) -> Vec<f64> {
+    image.pixels().map(|p| 0.299_f64 * p.0[0] as f64 + 0.587_f64 * p.0[1] as f64 + 0.114_f64 * p.0[2] as f64).collect()
+}
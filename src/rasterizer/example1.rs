@@ -1,6 +1,6 @@
 use crate::matrix::vector3d::Vector3d;
+use crate::rasterizer::camera::Camera;
 use crate::rasterizer::rasterizer::{BasicRasterizer, Rasterizer};
-use image::{RgbImage, Rgb};
 
 pub fn draw_green_triangle(args: Vec<&str>) {
     let mut angle = 0.0_f32;
@@ -20,7 +20,8 @@ pub fn draw_green_triangle(args: Vec<&str>) {
     }
 
     let mut raster = Rasterizer::new(700, 700);
-    let eye_pos = Vector3d::new(0_f32, 0_f32, 5_f32);
+    let mut camera = Camera::perspective(45_f32, 1_f32, 0.1_f32, 50_f32);
+    camera.position = Vector3d::new(0_f32, 0_f32, 5_f32);
 
     // 这里的参数控制三角形的三个顶点的位置
     // 如果希望看自转，直接把z轴位置设置为0即可
@@ -44,19 +45,11 @@ pub fn draw_green_triangle(args: Vec<&str>) {
         raster.clear_buf();
 
         raster.set_model(Rasterizer::model_matrix(axis, angle));
-        raster.set_view(Rasterizer::view_matrix(eye_pos));
-        raster.set_projection(Rasterizer::projection_matrix(45_f32, 1_f32, 0.1_f32, 50_f32));
+        raster.set_camera(&camera);
 
         raster.draw_triangle(pos_id, ind_id, Vector3d::new(0_f32, 255_f32, 0_f32)).unwrap();
 
-        let mut image = RgbImage::new(700, 700);
-        for x in 1 .. 700 {
-            for y in 1 .. 700 {
-                let color = raster.frame_buf[raster.get_index(x as usize, y as usize)];
-                image.put_pixel(x, y, Rgb([color.x as u8, color.y as u8, color.z as u8]));
-            }
-        }
-        image.save(filename).unwrap();
+        raster.save(filename).unwrap();
         return;
     }
 }
\ No newline at end of file
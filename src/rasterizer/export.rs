@@ -0,0 +1,58 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use crate::matrix::vector3d::Vector3d;
+use crate::rasterizer::rasterizer_errors::RasterizerError;
+
+/// Write width x height RGB colors in [0, 255], stored from the top row down like frame_buf, as a binary PPM (P6) file
+pub fn write_ppm<P: AsRef<Path>>(path: P, width: usize, height: usize, colors: &[Vector3d<f32>]) -> Result<(), RasterizerError> {
+    let path = path.as_ref();
+    check_color_count(path, width, height, colors)?;
+    let mut bytes = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for color in colors.iter() {
+        bytes.extend_from_slice(&[to_u8(color.x), to_u8(color.y), to_u8(color.z)]);
+    }
+    write_file(path, &bytes)
+}
+
+/** Write width x height RGB colors in [0, 255], stored from the top row down like frame_buf, as a little endian PFM file
+  The colors are divided by 255 but not clamped. PFM stores the bottom row first, so the rows are written in reverse.
+ */
+pub fn write_pfm<P: AsRef<Path>>(path: P, width: usize, height: usize, colors: &[Vector3d<f32>]) -> Result<(), RasterizerError> {
+    let path = path.as_ref();
+    check_color_count(path, width, height, colors)?;
+    let mut bytes = format!("PF\n{} {}\n-1.0\n", width, height).into_bytes();
+    for row in (0..height).rev() {
+        for color in colors[row * width..(row + 1) * width].iter() {
+            for channel in [color.x, color.y, color.z].iter() {
+                bytes.extend_from_slice(&(channel / 255.0_f32).to_le_bytes());
+            }
+        }
+    }
+    write_file(path, &bytes)
+}
+
+/// The writers need exactly one color per pixel
+fn check_color_count(path: &Path, width: usize, height: usize, colors: &[Vector3d<f32>]) -> Result<(), RasterizerError> {
+    if width.checked_mul(height) != Some(colors.len()) {
+        return Err(RasterizerError {
+            err_code: 5008,
+            message: format!("{}, {} x {} pixels, {} colors", path.display(), width, height, colors.len()),
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn to_u8(value: f32) -> u8 {
+    value.round().clamp(0.0_f32, 255.0_f32) as u8
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), RasterizerError> {
+    let file = File::create(path).map_err(|e| write_error(path, e))?;
+    let mut writer = BufWriter::new(file);
+    writer.write_all(bytes).and_then(|_| writer.flush()).map_err(|e| write_error(path, e))
+}
+
+pub(crate) fn write_error(path: &Path, e: impl std::fmt::Display) -> RasterizerError {
+    RasterizerError { err_code: 5006, message: format!("{}, {}", path.display(), e) }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use crate::geometry::obj::ObjMaterial;
+use crate::matrix::vector3d::Vector3d;
+use crate::rasterizer::shader::{FragmentPayload, FragmentShader};
+use crate::rasterizer::shadow::ShadowMap;
+
+/// Distance falloff of point and spot lights, the intensity is divided by constant + linear * d + quadratic * d^2
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attenuation {
+    pub constant:  f32,
+    pub linear:    f32,
+    pub quadratic: f32,
+}
+
+impl Attenuation {
+    pub fn new(constant: f32, linear: f32, quadratic: f32) -> Attenuation {
+        Attenuation { constant, linear, quadratic }
+    }
+
+    /// No falloff
+    pub fn none() -> Attenuation {
+        Attenuation::new(1.0_f32, 0.0_f32, 0.0_f32)
+    }
+
+    /// Physically based inverse square falloff
+    pub fn inverse_square() -> Attenuation {
+        Attenuation::new(0.0_f32, 0.0_f32, 1.0_f32)
+    }
+
+    pub fn factor(&self, distance: f32) -> f32 {
+        1.0_f32 / (self.constant + self.linear * distance + self.quadratic * distance * distance)
+    }
+}
+
+/// A light source in world space, color is the RGB intensity and may exceed 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Light {
+    Point {
+        position:    Vector3d<f32>,
+        color:       Vector3d<f32>,
+        attenuation: Attenuation,
+    },
+    /// A light infinitely far away, like the sun
+    Directional {
+        /// The direction the light travels in
+        direction: Vector3d<f32>,
+        color:     Vector3d<f32>,
+    },
+    /// A point light restricted to a cone, the intensity fades from full inside inner_angle to zero at outer_angle
+    Spot {
+        position:    Vector3d<f32>,
+        direction:   Vector3d<f32>,
+        color:       Vector3d<f32>,
+        /// Half angle of the cone in degrees
+        inner_angle: f32,
+        /// Half angle of the cone in degrees
+        outer_angle: f32,
+        attenuation: Attenuation,
+    },
+}
+
+impl Light {
+    pub fn point(position: Vector3d<f32>, color: Vector3d<f32>) -> Light {
+        Light::Point { position, color, attenuation: Attenuation::none() }
+    }
+
+    pub fn directional(direction: Vector3d<f32>, color: Vector3d<f32>) -> Light {
+        Light::Directional { direction, color }
+    }
+
+    pub fn spot(position: Vector3d<f32>, direction: Vector3d<f32>, color: Vector3d<f32>, inner_angle: f32, outer_angle: f32) -> Light {
+        Light::Spot { position, direction, color, inner_angle, outer_angle, attenuation: Attenuation::none() }
+    }
+
+    /// Replace the attenuation of point and spot lights, directional lights are returned unchanged
+    pub fn with_attenuation(self, a: Attenuation) -> Light {
+        match self {
+            Light::Point { position, color, .. } => Light::Point { position, color, attenuation: a },
+            Light::Spot { position, direction, color, inner_angle, outer_angle, .. } =>
+                Light::Spot { position, direction, color, inner_angle, outer_angle, attenuation: a },
+            Light::Directional { .. } => self,
+        }
+    }
+
+    /** The normalized direction from point towards the light and the intensity reaching point, Example
+    ```rust
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::light::{Attenuation, Light};
+
+    let light = Light::point(Vector3d::new(0.0, 2.0, 0.0), Vector3d::fill(8.0)).with_attenuation(Attenuation::inverse_square());
+    let (l, intensity) = light.illuminate(Vector3d::new(0.0, 0.0, 0.0));
+    ```
+
+    output l and intensity:
+    ```text
+    [0.0 1.0 0.0]
+    [2.0 2.0 2.0]
+    ```
+     */
+    pub fn illuminate(&self, point: Vector3d<f32>) -> (Vector3d<f32>, Vector3d<f32>) {
+        match *self {
+            Light::Point { position, color, attenuation } => {
+                let to_light = position.sub(point);
+                let distance = to_light.norm();
+                (to_light.normalize(), Vector3d::mul_item(color, attenuation.factor(distance)))
+            }
+            Light::Directional { direction, color } => (Vector3d::mul_item(direction.normalize(), -1.0_f32), color),
+            Light::Spot { position, direction, color, inner_angle, outer_angle, attenuation } => {
+                let to_light = position.sub(point);
+                let distance = to_light.norm();
+                let l = to_light.normalize();
+                let cos_theta = -l.dot(direction.normalize());
+                let cos_inner = inner_angle.to_radians().cos();
+                let cos_outer = outer_angle.to_radians().cos();
+                let cone = if cos_inner > cos_outer {
+                    smoothstep((cos_theta - cos_outer) / (cos_inner - cos_outer))
+                } else if cos_theta >= cos_outer {
+                    1.0_f32
+                } else {
+                    0.0_f32
+                };
+                (l, Vector3d::mul_item(color, attenuation.factor(distance) * cone))
+            }
+        }
+    }
+}
+
+fn smoothstep(t: f32) -> f32 {
+    let t = t.clamp(0.0_f32, 1.0_f32);
+    t * t * (3.0_f32 - 2.0_f32 * t)
+}
+
+/// Surface reflectance of the Blinn-Phong model, colors are RGB in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Material {
+    pub ambient:   Vector3d<f32>,
+    pub diffuse:   Vector3d<f32>,
+    pub specular:  Vector3d<f32>,
+    /// Exponent of the specular highlight, larger values give smaller and sharper highlights
+    pub shininess: f32,
+}
+
+impl Material {
+    pub fn new(ambient: Vector3d<f32>, diffuse: Vector3d<f32>, specular: Vector3d<f32>, shininess: f32) -> Material {
+        Material { ambient, diffuse, specular, shininess }
+    }
+}
+
+impl Default for Material {
+    /// A plain white plastic
+    fn default() -> Material {
+        Material::new(Vector3d::fill(0.1_f32), Vector3d::fill(0.8_f32), Vector3d::fill(0.5_f32), 32.0_f32)
+    }
+}
+
+impl From<&ObjMaterial> for Material {
+    fn from(m: &ObjMaterial) -> Material {
+        Material::new(m.ambient, m.diffuse, m.specular, m.shininess)
+    }
+}
+
+/** Blinn-Phong shading of the interpolated world space position and normal
+  The color is ambient_light * ambient + sum over the lights of intensity * (diffuse * max(n.l, 0) + specular * max(n.h, 0)^shininess),
+  clamped to [0, 1]. eye_pos must match the camera of the view matrix.
+  A light with a shadow map only reaches the fraction of a point given by ShadowMap::visibility, the ambient term is never shadowed.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlinnPhongFragmentShader {
+    pub material:      Material,
+    pub lights:        Vec<Light>,
+    pub ambient_light: Vector3d<f32>,
+    pub eye_pos:       Vector3d<f32>,
+    /// Shadow maps keyed by the index of their light in lights
+    pub shadow_maps:   HashMap<usize, ShadowMap>,
+}
+
+impl BlinnPhongFragmentShader {
+    pub fn new(material: Material, lights: Vec<Light>, eye_pos: Vector3d<f32>) -> BlinnPhongFragmentShader {
+        BlinnPhongFragmentShader {
+            material,
+            lights,
+            ambient_light: Vector3d::fill(1.0_f32),
+            eye_pos,
+            shadow_maps: HashMap::new(),
+        }
+    }
+
+    /// Cast shadows from lights[light] with the shadow map rendered from it
+    pub fn set_shadow_map(&mut self, light: usize, shadow_map: ShadowMap) {
+        self.shadow_maps.insert(light, shadow_map);
+    }
+}
+
+impl FragmentShader for BlinnPhongFragmentShader {
+    fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+        let point = fragment.varyings.world_pos;
+        let n = fragment.varyings.normal.normalize();
+        let v = self.eye_pos.sub(point).normalize();
+
+        let mut color = self.ambient_light.mul(self.material.ambient);
+        for (i, light) in self.lights.iter().enumerate() {
+            let (l, intensity) = light.illuminate(point);
+            let n_dot_l = n.dot(l);
+            if n_dot_l <= 0.0_f32 {
+                continue;
+            }
+            let visibility = self.shadow_maps.get(&i).map_or(1.0_f32, |m| m.visibility(point, n_dot_l));
+            if visibility <= 0.0_f32 {
+                continue;
+            }
+            let h = l.add(v).normalize();
+            let specular = n.dot(h).max(0.0_f32).powf(self.material.shininess);
+            let reflected = Vector3d::mul_item(self.material.diffuse, n_dot_l)
+                .add(Vector3d::mul_item(self.material.specular, specular));
+            color = color.add(Vector3d::mul_item(intensity.mul(reflected), visibility));
+        }
+        Vector3d::new(color.x.clamp(0.0_f32, 1.0_f32), color.y.clamp(0.0_f32, 1.0_f32), color.z.clamp(0.0_f32, 1.0_f32))
+    }
+}
@@ -0,0 +1,197 @@
+use crate::matrix::vector2d::Vector2d;
+
+/// A pixel touched by a line, t is the position along the line from 0 at the begin to 1 at the end
+/// and coverage the fraction of the pixel covered by the line in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinePixel {
+    pub x:        i64,
+    pub y:        i64,
+    pub t:        f32,
+    pub coverage: f32,
+}
+
+/** The pixels of the line between the pixels containing begin and end with Bresenham's algorithm, in screen space with y pointing up
+  Every octant steps one pixel along the major axis at a time from begin to end, and swapping begin and end gives the same pixels. Example
+```rust
+use QRender::matrix::vector2d::Vector2d;
+use QRender::rasterizer::line::bresenham;
+
+let pixels: Vec<(i64, i64)> = bresenham(Vector2d::new(0.5, 0.5), Vector2d::new(4.5, 2.5)).iter().map(|p| (p.x, p.y)).collect();
+```
+
+output pixels:
+```text
+[(0, 0), (1, 0), (2, 1), (3, 1), (4, 2)]
+```
+ */
+pub fn bresenham(begin: Vector2d<f32>, end: Vector2d<f32>) -> Vec<LinePixel> {
+    let (x0, y0) = (begin.x.floor() as i64, begin.y.floor() as i64);
+    let (x1, y1) = (end.x.floor() as i64, end.y.floor() as i64);
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    // Walk along the major axis a from the smaller end, the minor axis b follows the error term
+    let (mut a0, mut b0, mut a1, mut b1) = if steep { (y0, x0, y1, x1) } else { (x0, y0, x1, y1) };
+    let reversed = a0 > a1;
+    if reversed {
+        std::mem::swap(&mut a0, &mut a1);
+        std::mem::swap(&mut b0, &mut b1);
+    }
+
+    let da = a1 - a0;
+    let db = (b1 - b0).abs();
+    let step = if b0 < b1 { 1 } else { -1 };
+    let mut error = 2 * db - da;
+    let mut b = b0;
+    let mut pixels = Vec::with_capacity(da as usize + 1);
+    for a in a0..=a1 {
+        let t = if da == 0 { 0.0_f32 } else { (a - a0) as f32 / da as f32 };
+        let (x, y) = if steep { (b, a) } else { (a, b) };
+        pixels.push(LinePixel { x, y, t: if reversed { 1.0_f32 - t } else { t }, coverage: 1.0_f32 });
+        if error > 0 {
+            b += step;
+            error -= 2 * da;
+        }
+        error += 2 * db;
+    }
+    // Order the pixels from begin to end
+    if reversed {
+        pixels.reverse();
+    }
+    pixels
+}
+
+/** The pixels of the anti-aliased line from begin to end with Xiaolin Wu's algorithm, in screen space with pixel centers at half integers
+  Every step along the major axis splits the coverage between the two pixels closest to the line, the ends are weighted by how far they reach into their pixel.
+ */
+pub fn wu(begin: Vector2d<f32>, end: Vector2d<f32>) -> Vec<LinePixel> {
+    // Move the pixel centers to integer coordinates
+    let (mut x0, mut y0, mut x1, mut y1) = (begin.x - 0.5_f32, begin.y - 0.5_f32, end.x - 0.5_f32, end.y - 0.5_f32);
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    if steep {
+        std::mem::swap(&mut x0, &mut y0);
+        std::mem::swap(&mut x1, &mut y1);
+    }
+    let reversed = x0 > x1;
+    if reversed {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
+    }
+
+    let dx = x1 - x0;
+    let gradient = if dx == 0.0_f32 { 1.0_f32 } else { (y1 - y0) / dx };
+    let mut pixels = Vec::new();
+    let mut plot = |a: i64, b: i64, coverage: f32| {
+        if coverage <= 0.0_f32 {
+            return;
+        }
+        let t = if dx == 0.0_f32 { 0.0_f32 } else { ((a as f32 - x0) / dx).clamp(0.0_f32, 1.0_f32) };
+        let (x, y) = if steep { (b, a) } else { (a, b) };
+        pixels.push(LinePixel { x, y, t: if reversed { 1.0_f32 - t } else { t }, coverage: coverage.min(1.0_f32) });
+    };
+
+    // The first end
+    let x_end = x0.round();
+    let y_end = y0 + gradient * (x_end - x0);
+    let x_gap = 1.0_f32 - fpart(x0 + 0.5_f32);
+    let first = x_end as i64;
+    plot(first, y_end.floor() as i64, (1.0_f32 - fpart(y_end)) * x_gap);
+    plot(first, y_end.floor() as i64 + 1, fpart(y_end) * x_gap);
+    let mut y = y_end + gradient;
+
+    // The second end
+    let x_end = x1.round();
+    let y_end = y1 + gradient * (x_end - x1);
+    let x_gap = fpart(x1 + 0.5_f32);
+    let last = x_end as i64;
+    if last != first {
+        plot(last, y_end.floor() as i64, (1.0_f32 - fpart(y_end)) * x_gap);
+        plot(last, y_end.floor() as i64 + 1, fpart(y_end) * x_gap);
+    }
+
+    for a in first + 1..last {
+        plot(a, y.floor() as i64, 1.0_f32 - fpart(y));
+        plot(a, y.floor() as i64 + 1, fpart(y));
+        y += gradient;
+    }
+    pixels
+}
+
+/// The pixels whose centers lie within width / 2 of the segment from begin to end, a line with round caps
+pub fn thick(begin: Vector2d<f32>, end: Vector2d<f32>, width: f32) -> Vec<LinePixel> {
+    thick_within(begin, end, width, (i64::MIN, i64::MAX), (i64::MIN, i64::MAX))
+}
+
+/// The pixels of thick with 0 <= x < columns and 0 <= y < rows, only the part of the bounding box inside the viewport is scanned
+pub fn thick_clipped(begin: Vector2d<f32>, end: Vector2d<f32>, width: f32, columns: usize, rows: usize) -> Vec<LinePixel> {
+    thick_within(begin, end, width, (0, columns as i64 - 1), (0, rows as i64 - 1))
+}
+
+/// The pixels of thick within the inclusive ranges of columns and rows
+fn thick_within(begin: Vector2d<f32>, end: Vector2d<f32>, width: f32, columns: (i64, i64), rows: (i64, i64)) -> Vec<LinePixel> {
+    let radius = 0.5_f32 * width;
+    let direction = end.sub(begin);
+    let length_squared = direction.length_squared();
+    let x_min = ((begin.x.min(end.x) - radius).floor() as i64).max(columns.0);
+    let x_max = ((begin.x.max(end.x) + radius).ceil() as i64).min(columns.1);
+    let y_min = ((begin.y.min(end.y) - radius).floor() as i64).max(rows.0);
+    let y_max = ((begin.y.max(end.y) + radius).ceil() as i64).min(rows.1);
+    let mut pixels = Vec::new();
+    for y in y_min..=y_max {
+        for x in x_min..=x_max {
+            let center = Vector2d::new(x as f32 + 0.5_f32, y as f32 + 0.5_f32);
+            let t = if length_squared > 0.0_f32 { (center.sub(begin).dot(direction) / length_squared).clamp(0.0_f32, 1.0_f32) } else { 0.0_f32 };
+            let closest = begin.add(Vector2d::mul_item(direction, t));
+            if center.sub(closest).length_squared() <= radius * radius {
+                pixels.push(LinePixel { x, y, t, coverage: 1.0_f32 });
+            }
+        }
+    }
+    pixels
+}
+
+/** Clip the segment from begin to end to the rectangle from min to max with the Liang-Barsky algorithm, like clip::clip_line in clip space
+  Returns the range (t0, t1) of the part inside where t = 0 is begin and t = 1 is end,
+  or None when the segment lies outside or one of its coordinates is not finite. Example
+```rust
+use QRender::matrix::vector2d::Vector2d;
+use QRender::rasterizer::line::clip_segment;
+
+let range = clip_segment(Vector2d::new(-10.0, 5.0), Vector2d::new(30.0, 5.0), Vector2d::fill(0.0), Vector2d::fill(10.0));
+```
+
+output range:
+```text
+Some((0.25, 0.5))
+```
+ */
+pub fn clip_segment(begin: Vector2d<f32>, end: Vector2d<f32>, min: Vector2d<f32>, max: Vector2d<f32>) -> Option<(f32, f32)> {
+    if !(begin.x.is_finite() && begin.y.is_finite() && end.x.is_finite() && end.y.is_finite()) {
+        return None;
+    }
+    // How far begin and end lie inside each edge of the rectangle
+    let edges = [
+        (begin.x - min.x, end.x - min.x),
+        (max.x - begin.x, max.x - end.x),
+        (begin.y - min.y, end.y - min.y),
+        (max.y - begin.y, max.y - end.y),
+    ];
+    let (mut t0, mut t1) = (0.0_f32, 1.0_f32);
+    for (da, db) in edges.iter() {
+        if *da < 0.0_f32 && *db < 0.0_f32 {
+            return None;
+        }
+        if *da < 0.0_f32 {
+            t0 = t0.max(da / (da - db));
+        } else if *db < 0.0_f32 {
+            t1 = t1.min(da / (da - db));
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+    Some((t0, t1))
+}
+
+/// The fractional part of v in [0, 1), also for negative values
+fn fpart(v: f32) -> f32 {
+    v - v.floor()
+}
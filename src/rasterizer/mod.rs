@@ -1,2 +1,13 @@
 pub mod rasterizer;
-pub mod example1;
\ No newline at end of file
+pub mod rasterizer_errors;
+pub mod example1;
+pub mod shader;
+pub mod clip;
+pub mod texture;
+pub mod light;
+pub mod shadow;
+pub mod blend;
+pub mod line;
+pub mod export;
+pub mod compare;
+pub mod camera;
@@ -1,19 +1,198 @@
 use crate::matrix::{vector3d::Vector3d, matrix4d::Matrix4d};
-use std::collections::HashMap;
-use crate::geometry::geometry_errors::TriangleError;
+use image::{ImageFormat, Rgb, RgbImage, Rgba, RgbaImage};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+#[cfg(not(feature = "rayon"))]
+use std::sync::atomic::{AtomicUsize, Ordering};
 use crate::geometry::triangle::Triangle;
+use crate::geometry::mesh::Mesh;
+use crate::geometry::obj::ObjMesh;
 use crate::matrix::vector4d::Vector4d;
-use std::f32::consts::PI;
-use crate::matrix::matrix3d::Matrix3d;
+use crate::matrix::transform::{self, DepthRange};
+use crate::rasterizer::clip::{clip_line, clip_triangle};
+use crate::rasterizer::blend::BlendState;
+use crate::rasterizer::camera::Camera;
+use crate::rasterizer::export;
+use crate::rasterizer::line;
+use crate::rasterizer::rasterizer_errors::RasterizerError;
+use crate::matrix::vector2d::Vector2d;
+use crate::rasterizer::shader::{ColorFragmentShader, DefaultVertexShader, FragmentPayload, FragmentShader, Transforms, Varyings, VertexPayload, VertexShader};
 
+/// Screen space depth of the near plane, whichever DepthRange the projection uses. The depth buffer keeps the
+/// [0.1, 100] range draw_triangle always mapped to instead of [0, 1], so depth_buf values seen by callers are unchanged
+const SCREEN_DEPTH_NEAR: f32 = 0.1_f32;
+/// Screen space depth of the far plane
+const SCREEN_DEPTH_FAR: f32 = 100.0_f32;
+
+/// Handle of a position buffer returned by Rasterizer::load_positions, valid until the buffer is removed
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct PositionBufferId(usize);
+
+/// Handle of an index buffer returned by Rasterizer::load_indices or load_index_list, valid until the buffer is removed
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct IndexBufferId(usize);
+
+/// Handle of a vertex attribute buffer returned by Rasterizer::load_attributes, valid until the buffer is removed
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct AttributeBufferId(usize);
+
+/// Per-vertex data stored next to the positions, element i belongs to position i
+#[derive(Debug, Clone, PartialEq)]
+pub enum VertexAttributes {
+    /// RGB colors in [0, 255]
+    Colors(Vec<Vector3d<f32>>),
+    Normals(Vec<Vector3d<f32>>),
+    TexCoords(Vec<Vector2d<f32>>),
+}
+
+/// The kind of primitive a topology assembles its indices into
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Primitive {
+    Point,
     Line,
     Triangle,
 }
 
+/// The index that ends a strip or fan, the next index starts a new one. In lists it drops the unfinished primitive
+pub const PRIMITIVE_RESTART: i32 = -1;
+
+/** How Rasterizer::draw assembles the indices of an index buffer into primitives, n is the number of indices between restarts
+  TriangleStrip keeps the winding of its first triangle by swapping the first two vertices of every other triangle. Example
+```rust
+use QRender::rasterizer::rasterizer::{PrimitiveTopology, PRIMITIVE_RESTART};
+
+let strip = PrimitiveTopology::TriangleStrip.assemble(&[0, 1, 2, 3, PRIMITIVE_RESTART, 4, 5, 6]);
+```
+
+output strip:
+```text
+[[0, 1, 2], [2, 1, 3], [4, 5, 6]]
+```
+ */
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PrimitiveTopology {
+    /// n points
+    PointList,
+    /// n / 2 separate lines
+    LineList,
+    /// n - 1 connected lines
+    LineStrip,
+    /// n / 3 separate triangles
+    TriangleList,
+    /// n - 2 triangles, each sharing an edge with the one before
+    TriangleStrip,
+    /// n - 2 triangles sharing the first vertex
+    TriangleFan,
+}
+
+impl PrimitiveTopology {
+    pub fn primitive(&self) -> Primitive {
+        match self {
+            PrimitiveTopology::PointList => Primitive::Point,
+            PrimitiveTopology::LineList | PrimitiveTopology::LineStrip => Primitive::Line,
+            PrimitiveTopology::TriangleList | PrimitiveTopology::TriangleStrip | PrimitiveTopology::TriangleFan => Primitive::Triangle,
+        }
+    }
+
+    /// Split the indices into the vertex indices of every primitive, with 1, 2 or 3 indices each
+    pub fn assemble(&self, indices: &[i32]) -> Vec<Vec<i32>> {
+        let mut primitives = Vec::new();
+        for run in indices.split(|index| *index == PRIMITIVE_RESTART) {
+            match self {
+                PrimitiveTopology::PointList => primitives.extend(run.chunks_exact(1).map(|c| c.to_vec())),
+                PrimitiveTopology::LineList => primitives.extend(run.chunks_exact(2).map(|c| c.to_vec())),
+                PrimitiveTopology::TriangleList => primitives.extend(run.chunks_exact(3).map(|c| c.to_vec())),
+                PrimitiveTopology::LineStrip => primitives.extend(run.windows(2).map(|w| w.to_vec())),
+                PrimitiveTopology::TriangleStrip => primitives.extend(run.windows(3).enumerate().map(|(i, w)| {
+                    if i % 2 == 0 { vec![w[0], w[1], w[2]] } else { vec![w[1], w[0], w[2]] }
+                })),
+                PrimitiveTopology::TriangleFan => {
+                    if let Some((first, rest)) = run.split_first() {
+                        primitives.extend(rest.windows(2).map(|w| vec![*first, w[0], w[1]]));
+                    }
+                }
+            }
+        }
+        primitives
+    }
+}
+
+/// Winding order of the vertices of a front facing triangle, as seen on the screen
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FrontFace {
+    CounterClockwise,
+    Clockwise,
+}
+
+/** Anti-aliasing of triangle edges, every pixel stores one color and depth per sample
+  Multisampling tests coverage and depth per sample but runs the fragment shader once per pixel,
+  supersampling runs the fragment shader for every covered sample, which is slower but also smooths aliasing inside triangles.
+  frame_buf and depth_buf always hold the resolved average color and the closest depth of the samples.
+ */
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AntiAliasing {
+    None,
+    Msaa2,
+    Msaa4,
+    Msaa8,
+    Ssaa2,
+    Ssaa4,
+    Ssaa8,
+}
+
+impl AntiAliasing {
+    pub fn sample_count(&self) -> usize {
+        self.sample_offsets().len()
+    }
+
+    /// Whether the fragment shader runs once per sample instead of once per pixel
+    pub fn is_supersampling(&self) -> bool {
+        matches!(self, AntiAliasing::Ssaa2 | AntiAliasing::Ssaa4 | AntiAliasing::Ssaa8)
+    }
+
+    /// Sample positions relative to the pixel center, the rotated grid patterns of Direct3D
+    pub fn sample_offsets(&self) -> &'static [(f32, f32)] {
+        match self {
+            AntiAliasing::None => &[(0.0_f32, 0.0_f32)],
+            AntiAliasing::Msaa2 | AntiAliasing::Ssaa2 => &[(0.25_f32, 0.25_f32), (-0.25_f32, -0.25_f32)],
+            AntiAliasing::Msaa4 | AntiAliasing::Ssaa4 => &[
+                (-0.125_f32, -0.375_f32), (0.375_f32, -0.125_f32), (-0.375_f32, 0.125_f32), (0.125_f32, 0.375_f32),
+            ],
+            AntiAliasing::Msaa8 | AntiAliasing::Ssaa8 => &[
+                (0.0625_f32, -0.1875_f32), (-0.0625_f32, 0.1875_f32), (0.3125_f32, 0.0625_f32), (-0.1875_f32, -0.3125_f32),
+                (-0.3125_f32, 0.3125_f32), (-0.4375_f32, -0.0625_f32), (0.1875_f32, 0.4375_f32), (0.4375_f32, -0.4375_f32),
+            ],
+        }
+    }
+}
+
+/// Which triangles are discarded before they are filled
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CullMode {
+    None,
+    Front,
+    Back,
+}
+
+/// How draw_line and draw_line_3d rasterize lines
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineMode {
+    /// One pixel wide Bresenham lines
+    Aliased,
+    /// One pixel wide Xiaolin Wu lines, the coverage of every pixel is alpha blended over frame_buf
+    AntiAliased,
+    /// Lines of the given width in pixels with round caps, drawn with the blend state like set_pixel
+    Thick(f32),
+}
+
+/// Shortcuts to the common matrices, see matrix::transform for look_at, orthographic projection and the [0, 1] depth range
 pub trait BasicRasterizer {
+    /// Move the camera to eye_pos, looking down the negative Z axis
     fn view_matrix(eye_pos: Vector3d<f32>) -> Matrix4d<f32>;
+    /// Rotate angle degrees around the axis norm
     fn model_matrix(norm: Vector3d<f32>, angle: f32) -> Matrix4d<f32>;
+    /// OpenGL style perspective projection, eye_fov is the vertical field of view in degrees
     fn projection_matrix(eye_fov: f32, aspect_ratio: f32, z_near: f32, z_far: f32) -> Matrix4d<f32>;
 }
 
@@ -22,26 +201,53 @@ pub struct Rasterizer {
     pub view:          Matrix4d<f32>,
     pub projection:    Matrix4d<f32>,
 
-    pos_buf:       HashMap<usize, Vec<Vector3d<f32>>>,
-    ind_buf:       HashMap<usize, Vec<Vector3d<i32>>>,
+    pos_buf:       HashMap<PositionBufferId, Vec<Vector3d<f32>>>,
+    /// Flat index lists, assembled into primitives by the topology of the draw call
+    ind_buf:       HashMap<IndexBufferId, Vec<i32>>,
+    attr_buf:      HashMap<AttributeBufferId, VertexAttributes>,
+    /// The attribute buffers bound to each position buffer
+    layouts:       HashMap<PositionBufferId, VertexLayout>,
 
     pub frame_buf:     Vec<Vector3d<f32>>,
+    /// The alpha channel of frame_buf in [0, 255], 0 where nothing was drawn
+    pub alpha_buf:     Vec<f32>,
     pub depth_buf:     Vec<f32>,
+    /// RGBA colors and depths of the samples of every pixel, the samples of pixel i are stored at [i * n, (i + 1) * n)
+    sample_frame_buf:  Vec<Vector4d<f32>>,
+    sample_depth_buf:  Vec<f32>,
+    anti_aliasing:     AntiAliasing,
 
     pub width:     usize,
     pub height:    usize,
-    next_id:       u32
+    next_id:       u32,
+
+    depth_range:   DepthRange,
+    front_face:    FrontFace,
+    cull_mode:     CullMode,
+    depth_only:    bool,
+    depth_write:   bool,
+    blend:         BlendState,
+    threads:       usize,
+    tile_size:     usize,
+    line_mode:     LineMode,
+    line_depth_bias: f32,
+    point_size:    f32,
+
+    vertex_shader:   Box<dyn VertexShader>,
+    fragment_shader: Arc<dyn FragmentShader>,
+    /// Transparent triangles waiting for flush_transparent
+    transparent_queue: Vec<ScreenTriangle>,
 }
 
 impl Rasterizer {
     /// To initialize a raster renderer, the model view and projection matrix need to be filled using function a, function b and function c.
     pub fn new(width: usize, height: usize) -> Rasterizer {
-        let pos_buf: HashMap<usize, Vec<Vector3d<f32>>> = HashMap::new();
-        let ind_buf: HashMap<usize, Vec<Vector3d<i32>>> = HashMap::new();
+        let pos_buf: HashMap<PositionBufferId, Vec<Vector3d<f32>>> = HashMap::new();
+        let ind_buf: HashMap<IndexBufferId, Vec<i32>> = HashMap::new();
         let mut frame_buf: Vec<Vector3d<f32>> = Vec::new();
         frame_buf.resize(width * height, Vector3d::fill(0.0_f32));
         let mut depth_buf: Vec<f32> = Vec::new();
-        depth_buf.resize(width * height, 0.0_f32);
+        depth_buf.resize(width * height, f32::INFINITY);
 
         Rasterizer {
             model: Matrix4d::fill(0.0_f32),
@@ -49,36 +255,298 @@ impl Rasterizer {
             projection: Matrix4d::fill(0.0_f32),
             pos_buf,
             ind_buf,
+            attr_buf: HashMap::new(),
+            layouts: HashMap::new(),
+            sample_frame_buf: vec![Vector4d::fill(0.0_f32); width * height],
+            sample_depth_buf: depth_buf.clone(),
+            anti_aliasing: AntiAliasing::None,
             frame_buf,
+            alpha_buf: vec![0.0_f32; width * height],
             depth_buf,
             width,
             height,
-            next_id: 0
+            next_id: 0,
+            depth_range: DepthRange::NegativeOneToOne,
+            front_face: FrontFace::CounterClockwise,
+            cull_mode: CullMode::None,
+            depth_only: false,
+            depth_write: true,
+            blend: BlendState::replace(),
+            threads: 1,
+            tile_size: 64,
+            line_mode: LineMode::Aliased,
+            line_depth_bias: 0.01_f32,
+            point_size: 1.0_f32,
+            vertex_shader: Box::new(DefaultVertexShader),
+            fragment_shader: Arc::new(ColorFragmentShader),
+            transparent_queue: Vec::new(),
         }
     }
 
+    /// Ids are never reused, so the handle of a removed buffer stays invalid
     fn get_next_id(&mut self) -> u32 {
         let res = self.next_id;
         self.next_id += 1;
         res
     }
 
+    /// Index of the pixel (x, y) in frame_buf and depth_buf, y grows upwards from the bottom row
     pub fn get_index(&self, x: usize, y: usize) -> usize {
-        (self.height - y) * self.width + x
+        (self.height - 1 - y) * self.width + x
     }
 
-    pub fn load_positions(&mut self, positions: Vec<Vector3d<f32>>) -> usize {
-        let id = self.get_next_id() as usize;
+    pub fn load_positions(&mut self, positions: Vec<Vector3d<f32>>) -> PositionBufferId {
+        let id = PositionBufferId(self.get_next_id() as usize);
         self.pos_buf.insert(id, positions);
         id
     }
 
-    pub fn load_indices(&mut self, indices: Vec<Vector3d<i32>>) -> usize {
-        let id = self.get_next_id() as usize;
+    /// Load the indices of a triangle list, stored flat like load_index_list
+    pub fn load_indices(&mut self, indices: Vec<Vector3d<i32>>) -> IndexBufferId {
+        self.load_index_list(flatten_indices(&indices))
+    }
+
+    /// Load indices for any topology of draw, PRIMITIVE_RESTART separates strips and fans
+    pub fn load_index_list(&mut self, indices: Vec<i32>) -> IndexBufferId {
+        let id = IndexBufferId(self.get_next_id() as usize);
         self.ind_buf.insert(id, indices);
         id
     }
 
+    pub fn load_attributes(&mut self, attributes: VertexAttributes) -> AttributeBufferId {
+        let id = AttributeBufferId(self.get_next_id() as usize);
+        self.attr_buf.insert(id, attributes);
+        id
+    }
+
+    /// Per-vertex RGB colors in [0, 255], bind them to a position buffer with bind_attributes
+    pub fn load_colors(&mut self, colors: Vec<Vector3d<f32>>) -> AttributeBufferId {
+        self.load_attributes(VertexAttributes::Colors(colors))
+    }
+
+    /// Per-vertex normals in model space, bind them to a position buffer with bind_attributes
+    pub fn load_normals(&mut self, normals: Vec<Vector3d<f32>>) -> AttributeBufferId {
+        self.load_attributes(VertexAttributes::Normals(normals))
+    }
+
+    /// Per-vertex texture coordinates, bind them to a position buffer with bind_attributes
+    pub fn load_texcoords(&mut self, tex_coords: Vec<Vector2d<f32>>) -> AttributeBufferId {
+        self.load_attributes(VertexAttributes::TexCoords(tex_coords))
+    }
+
+    /** Use an attribute buffer for the vertices of a position buffer in every draw call, replacing the buffer of the same kind bound before
+      Element i of the attributes belongs to position i, so the attribute buffer needs at least as many elements as the position buffer when drawing.
+      One attribute buffer can be bound to several position buffers. Example
+    ```rust
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::Rasterizer;
+
+    let mut raster = Rasterizer::new(4, 4);
+    raster.set_model(Matrix4d::identity(1.0));
+    raster.set_view(Matrix4d::identity(1.0));
+    raster.set_projection(Matrix4d::identity(1.0));
+    let pos_id = raster.load_positions(vec![Vector3d::new(-4.0, -4.0, 0.0), Vector3d::new(4.0, -4.0, 0.0), Vector3d::new(0.0, 4.0, 0.0)]);
+    let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+    let color_id = raster.load_colors(vec![Vector3d::new(255.0, 128.0, 0.0); 3]);
+    raster.bind_attributes(pos_id, color_id).unwrap();
+    // The color argument only applies to vertices without a color buffer
+    raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+    let color = raster.frame_buf[0];
+    ```
+
+    output color:
+    ```text
+    [255.0 128.0 0.0]
+    ```
+     */
+    pub fn bind_attributes(&mut self, pos_id: PositionBufferId, attr_id: AttributeBufferId) -> Result<(), RasterizerError> {
+        self.positions(pos_id)?;
+        let layout = self.layouts.entry(pos_id).or_default();
+        match self.attr_buf.get(&attr_id) {
+            None => return Err(unknown_buffer(5003, attr_id)),
+            Some(VertexAttributes::Colors(_)) => layout.colors = Some(attr_id),
+            Some(VertexAttributes::Normals(_)) => layout.normals = Some(attr_id),
+            Some(VertexAttributes::TexCoords(_)) => layout.tex_coords = Some(attr_id),
+        }
+        Ok(())
+    }
+
+    /// Stop using the attribute buffers bound to a position buffer, the attribute buffers themselves stay loaded
+    pub fn unbind_attributes(&mut self, pos_id: PositionBufferId) -> Result<(), RasterizerError> {
+        self.positions(pos_id)?;
+        self.layouts.remove(&pos_id);
+        Ok(())
+    }
+
+    /** Load an OBJ mesh like load_mesh, returns the ids of the position and index buffers
+      Only the normals and texture coordinates the file provides are bound, a file without normals gets smooth normals
+      computed from its faces so that it can be lit.
+     */
+    pub fn load_obj_mesh(&mut self, mesh: &ObjMesh) -> (PositionBufferId, IndexBufferId) {
+        let mut mesh = Mesh::from(mesh);
+        if mesh.normals.is_empty() {
+            // Indices out of range leave the mesh without normals, the draw calls report them
+            let _ = mesh.compute_smooth_normals();
+        }
+        self.load_mesh(&mesh)
+    }
+
+    /** Load a mesh with its normals, texture coordinates and colors bound to the positions, returns the ids of the position and index buffers
+      Attributes the mesh does not have are not bound and the colors in [0, 1] are scaled to the [0, 255] of load_colors.
+      The shaders have no tangent input, so the tangents are not loaded.
+     */
+    pub fn load_mesh(&mut self, mesh: &Mesh) -> (PositionBufferId, IndexBufferId) {
+        let pos_id = self.load_positions(mesh.positions.clone());
+        let mut layout = VertexLayout::default();
+        if !mesh.normals.is_empty() {
+            layout.normals = Some(self.load_normals(mesh.normals.clone()));
+        }
+        if !mesh.tex_coords.is_empty() {
+            layout.tex_coords = Some(self.load_texcoords(mesh.tex_coords.clone()));
+        }
+        if !mesh.colors.is_empty() {
+            layout.colors = Some(self.load_colors(mesh.colors.iter().map(|c| Vector3d::mul_item(*c, 255.0_f32)).collect()));
+        }
+        self.layouts.insert(pos_id, layout);
+        (pos_id, self.load_index_list(mesh.indices.iter().map(|i| *i as i32).collect()))
+    }
+
+    /// Replace the content of a position buffer, the handle stays the same
+    pub fn update_positions(&mut self, id: PositionBufferId, positions: Vec<Vector3d<f32>>) -> Result<(), RasterizerError> {
+        match self.pos_buf.get_mut(&id) {
+            None => Err(unknown_buffer(5001, id)),
+            Some(buf) => {
+                *buf = positions;
+                Ok(())
+            }
+        }
+    }
+
+    /// Replace the content of an index buffer with the indices of a triangle list, the handle stays the same
+    pub fn update_indices(&mut self, id: IndexBufferId, indices: Vec<Vector3d<i32>>) -> Result<(), RasterizerError> {
+        self.update_index_list(id, flatten_indices(&indices))
+    }
+
+    /// Replace the content of an index buffer with a flat index list, the handle stays the same
+    pub fn update_index_list(&mut self, id: IndexBufferId, indices: Vec<i32>) -> Result<(), RasterizerError> {
+        match self.ind_buf.get_mut(&id) {
+            None => Err(unknown_buffer(5002, id)),
+            Some(buf) => {
+                *buf = indices;
+                Ok(())
+            }
+        }
+    }
+
+    /// Replace the content of an attribute buffer, the handle stays the same
+    pub fn update_attributes(&mut self, id: AttributeBufferId, attributes: VertexAttributes) -> Result<(), RasterizerError> {
+        match self.attr_buf.get_mut(&id) {
+            None => Err(unknown_buffer(5003, id)),
+            Some(buf) => {
+                *buf = attributes;
+                Ok(())
+            }
+        }
+    }
+
+    /// Free a position buffer and return its content, the handle is invalid afterwards. The attribute buffers bound to it stay loaded
+    pub fn remove_positions(&mut self, id: PositionBufferId) -> Result<Vec<Vector3d<f32>>, RasterizerError> {
+        self.layouts.remove(&id);
+        self.pos_buf.remove(&id).ok_or_else(|| unknown_buffer(5001, id))
+    }
+
+    /// Free an index buffer and return its content, the handle is invalid afterwards
+    pub fn remove_indices(&mut self, id: IndexBufferId) -> Result<Vec<i32>, RasterizerError> {
+        self.ind_buf.remove(&id).ok_or_else(|| unknown_buffer(5002, id))
+    }
+
+    /// Free an attribute buffer and return its content, the handle is invalid afterwards
+    pub fn remove_attributes(&mut self, id: AttributeBufferId) -> Result<VertexAttributes, RasterizerError> {
+        self.attr_buf.remove(&id).ok_or_else(|| unknown_buffer(5003, id))
+    }
+
+    pub fn positions(&self, id: PositionBufferId) -> Result<&Vec<Vector3d<f32>>, RasterizerError> {
+        self.pos_buf.get(&id).ok_or_else(|| unknown_buffer(5001, id))
+    }
+
+    /// The flat index list of an index buffer, three indices per triangle for buffers loaded with load_indices
+    pub fn indices(&self, id: IndexBufferId) -> Result<&Vec<i32>, RasterizerError> {
+        self.ind_buf.get(&id).ok_or_else(|| unknown_buffer(5002, id))
+    }
+
+    pub fn attributes(&self, id: AttributeBufferId) -> Result<&VertexAttributes, RasterizerError> {
+        self.attr_buf.get(&id).ok_or_else(|| unknown_buffer(5003, id))
+    }
+
+    /// The number of position, index and attribute buffers currently loaded
+    pub fn buffer_count(&self) -> usize {
+        self.pos_buf.len() + self.ind_buf.len() + self.attr_buf.len()
+    }
+
+    /// Free every buffer, the handles loaded so far are invalid afterwards
+    pub fn clear_buffers(&mut self) {
+        self.pos_buf.clear();
+        self.ind_buf.clear();
+        self.attr_buf.clear();
+        self.layouts.clear();
+    }
+
+    /** The triangles of an index buffer with the positions and bound attributes of their vertices, before any transformation
+      Vertices without a color buffer take color, RGB in [0, 255], and vertices without normals or texture coordinates get zero.
+     */
+    pub fn triangles(&self, pos_id: PositionBufferId, ind_id: IndexBufferId, color: Vector3d<f32>) -> Result<Vec<Triangle>, RasterizerError> {
+        let vertices = self.vertex_payloads(pos_id, color)?;
+        let primitives = PrimitiveTopology::TriangleList.assemble(self.indices(ind_id)?);
+        let mut triangles = Vec::with_capacity(primitives.len());
+        for primitive in primitives.iter() {
+            let mut triangle = Triangle::new();
+            for (k, index) in primitive.iter().enumerate() {
+                let vertex = vertex_at(&vertices, *index)?;
+                triangle.vertex[k] = vertex.position;
+                triangle.normal[k] = vertex.normal;
+                triangle.tex_coords[k] = vertex.tex_coords;
+                triangle.color[k] = vertex.color;
+            }
+            triangles.push(triangle);
+        }
+        Ok(triangles)
+    }
+
+    /// The vertex shader input of every position of a buffer with its bound attributes, color in [0, 255] stands in for a missing color buffer
+    fn vertex_payloads(&self, pos_id: PositionBufferId, color: Vector3d<f32>) -> Result<Vec<VertexPayload>, RasterizerError> {
+        let positions = self.positions(pos_id)?;
+        let layout = self.layouts.get(&pos_id).copied().unwrap_or_default();
+        let mut vertices: Vec<VertexPayload> = positions.iter().map(|position| VertexPayload {
+            position: *position,
+            normal: Vector3d::fill(0.0_f32),
+            tex_coords: Vector2d::fill(0.0_f32),
+            color: Vector3d::div_item(color, 255.0_f32),
+        }).collect();
+
+        for attr_id in [layout.colors, layout.normals, layout.tex_coords].iter().flatten() {
+            let attributes = self.attributes(*attr_id)?;
+            let length = match attributes {
+                VertexAttributes::Colors(v) | VertexAttributes::Normals(v) => v.len(),
+                VertexAttributes::TexCoords(v) => v.len(),
+            };
+            if length < positions.len() {
+                return Err(RasterizerError {
+                    err_code: 5005,
+                    message: format!("{:?}, length: {}, positions: {}", attr_id, length, positions.len()),
+                });
+            }
+            for (i, vertex) in vertices.iter_mut().enumerate() {
+                match attributes {
+                    VertexAttributes::Colors(v) => vertex.color = Vector3d::div_item(v[i], 255.0_f32),
+                    VertexAttributes::Normals(v) => vertex.normal = v[i],
+                    VertexAttributes::TexCoords(v) => vertex.tex_coords = v[i],
+                }
+            }
+        }
+        Ok(vertices)
+    }
+
     pub fn set_model(&mut self, m: Matrix4d<f32>) {
         self.model = m;
     }
@@ -91,14 +559,103 @@ impl Rasterizer {
         self.projection = m;
     }
 
+    /// Set the view and projection matrices of a camera, the projection uses the depth range of the rasterizer
+    pub fn set_camera(&mut self, camera: &Camera) {
+        self.set_view(camera.view_matrix());
+        self.set_projection(camera.projection_matrix(self.depth_range));
+    }
+
+    /// The depth range of the projection matrix, which decides where the near clipping plane lies, NegativeOneToOne by default
+    pub fn set_depth_range(&mut self, depth_range: DepthRange) {
+        self.depth_range = depth_range;
+    }
+
+    /// The winding of front facing triangles, CounterClockwise by default
+    pub fn set_front_face(&mut self, front_face: FrontFace) {
+        self.front_face = front_face;
+    }
+
+    /// Discard front or back facing triangles, nothing is culled by default
+    pub fn set_cull_mode(&mut self, cull_mode: CullMode) {
+        self.cull_mode = cull_mode;
+    }
+
+    pub fn depth_range(&self) -> DepthRange {
+        self.depth_range
+    }
+
+    /// Only write depth_buf and skip the fragment shader, for depth pre-passes and shadow maps
+    pub fn set_depth_only(&mut self, depth_only: bool) {
+        self.depth_only = depth_only;
+    }
+
+    /// How fragments are combined with frame_buf and alpha_buf, BlendState::replace by default
+    pub fn set_blend_state(&mut self, blend: BlendState) {
+        self.blend = blend;
+    }
+
+    /// Whether fragments passing the depth test write their depth, true by default. The depth test itself always happens
+    pub fn set_depth_write(&mut self, depth_write: bool) {
+        self.depth_write = depth_write;
+    }
+
+    /// How lines are rasterized, LineMode::Aliased by default
+    pub fn set_line_mode(&mut self, line_mode: LineMode) {
+        self.line_mode = line_mode;
+    }
+
+    /// How far in screen space depth a line drawn by draw_line_3d may lie behind depth_buf and still be visible, 0.01 by default.
+    /// The depth slope of the surface under the line is added on top
+    pub fn set_line_depth_bias(&mut self, bias: f32) {
+        self.line_depth_bias = bias;
+    }
+
+    /// The diameter in pixels of the points drawn by draw with PrimitiveTopology::PointList, 1 by default
+    pub fn set_point_size(&mut self, point_size: f32) {
+        self.point_size = point_size;
+    }
+
+    /** Rasterize with up to threads threads, 1 by default
+      Every draw call first sorts its triangles into square tiles of tile_size pixels, then the tiles are rasterized in parallel.
+      The result is identical to the single threaded rasterizer. With the rayon feature the tiles run on the global rayon thread pool instead.
+     */
+    pub fn set_threads(&mut self, threads: usize, tile_size: usize) {
+        self.threads = threads.max(1);
+        self.tile_size = tile_size.max(1);
+    }
+
+    /// Change the number of samples per pixel, the current content of frame_buf and depth_buf is copied to every new sample
+    pub fn set_anti_aliasing(&mut self, anti_aliasing: AntiAliasing) {
+        let n = anti_aliasing.sample_count();
+        self.anti_aliasing = anti_aliasing;
+        self.sample_frame_buf = self.frame_buf.iter().zip(self.alpha_buf.iter())
+            .flat_map(|(c, a)| std::iter::repeat_n(c.to_vector4d(*a), n))
+            .collect();
+        self.sample_depth_buf = self.depth_buf.iter().flat_map(|d| std::iter::repeat_n(*d, n)).collect();
+    }
+
+    /// Replace the vertex shader invoked by draw_triangle, DefaultVertexShader is used by default
+    pub fn set_vertex_shader(&mut self, shader: impl VertexShader + 'static) {
+        self.vertex_shader = Box::new(shader);
+    }
+
+    /// Replace the fragment shader invoked for every covered pixel, ColorFragmentShader is used by default
+    pub fn set_fragment_shader(&mut self, shader: impl FragmentShader + 'static) {
+        self.fragment_shader = Arc::new(shader);
+    }
+
     pub fn clear_frame_buf(&mut self) {
         let length = self.frame_buf.len();
-        self.frame_buf = vec![Vector3d::fill(0.0_f32); length]
+        self.frame_buf = vec![Vector3d::fill(0.0_f32); length];
+        self.alpha_buf = vec![0.0_f32; length];
+        self.sample_frame_buf = vec![Vector4d::fill(0.0_f32); length * self.anti_aliasing.sample_count()];
     }
 
+    /// Reset every depth value to infinity, a smaller depth is closer to the camera
     pub fn clear_depth_buf(&mut self) {
         let length = self.depth_buf.len();
-        self.depth_buf = vec![0.0_f32; length]
+        self.depth_buf = vec![f32::INFINITY; length];
+        self.sample_depth_buf = vec![f32::INFINITY; length * self.anti_aliasing.sample_count()];
     }
 
     pub fn clear_buf(&mut self) {
@@ -106,225 +663,825 @@ impl Rasterizer {
         self.clear_depth_buf();
     }
 
+    /// Write an opaque RGB color in [0, 255] to the pixel under point, combined with frame_buf by the blend state
     pub fn set_pixel(&mut self, point: Vector3d<f32>, color: Vector3d<f32>) {
+        self.set_pixel_rgba(point, color.to_vector4d(255.0_f32));
+    }
+
+    /// Write an RGBA color in [0, 255] to the pixel under point, combined with frame_buf and alpha_buf by the blend state
+    pub fn set_pixel_rgba(&mut self, point: Vector3d<f32>, color: Vector4d<f32>) {
         if point.x < 0.0_f32 || point.x >= self.width as f32 || point.y < 0.0_f32 || point.y >= self.height as f32 {
             return;
         }
-        let ind = (self.height as f32 - point.y) * self.width as f32 + point.x;
-        if ind >= (self.width * self.height) as f32 {
-            return;
+        let ind = self.get_index(point.x as usize, point.y as usize);
+        if self.blend.is_replace() {
+            let n = self.anti_aliasing.sample_count();
+            self.sample_frame_buf[ind * n..(ind + 1) * n].fill(color);
+            self.frame_buf[ind] = color.head3();
+            self.alpha_buf[ind] = color.w;
+        } else {
+            let blend = self.blend;
+            self.blend_pixel(ind, &blend, Vector4d::div_item(color, 255.0_f32));
+        }
+    }
+
+    /// Blend the RGBA color in [0, 1] into every sample of the pixel ind and resolve it
+    fn blend_pixel(&mut self, ind: usize, blend: &BlendState, color: Vector4d<f32>) {
+        let n = self.anti_aliasing.sample_count();
+        for s in ind * n..(ind + 1) * n {
+            self.sample_frame_buf[s] = blend_255(blend, color, self.sample_frame_buf[s]);
+        }
+        {
+            let sum = self.sample_frame_buf[ind * n..(ind + 1) * n].iter().fold(Vector4d::fill(0.0_f32), |acc, c| acc.add(*c));
+            let average = Vector4d::div_item(sum, n as f32);
+            self.frame_buf[ind] = average.head3();
+            self.alpha_buf[ind] = average.w;
+        }
+    }
+
+    /** frame_buf as an 8 bit RGB image, the colors are rounded and clamped to [0, 255]
+      The screen y axis of the rasterizer points up while image rows go down,
+      so row r of an image shows the screen pixels with y = height - 1 - r and the first row is the top of the screen.
+      This is the order frame_buf is stored in, get_index(x, y) is the index of pixel (x, height - 1 - y) of the image. Example
+    ```rust
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::Rasterizer;
+
+    let mut raster = Rasterizer::new(2, 2);
+    // The bottom left pixel of the screen
+    raster.set_pixel(Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(255.0, 0.0, 0.0));
+    let image = raster.to_rgb_image();
+    let pixel = image.get_pixel(0, 1);
+    ```
+
+    output pixel, the bottom left pixel of the image:
+    ```text
+    Rgb([255, 0, 0])
+    ```
+     */
+    pub fn to_rgb_image(&self) -> RgbImage {
+        RgbImage::from_fn(self.width as u32, self.height as u32, |x, r| {
+            let color = self.frame_buf[r as usize * self.width + x as usize];
+            Rgb([export::to_u8(color.x), export::to_u8(color.y), export::to_u8(color.z)])
+        })
+    }
+
+    /// frame_buf and alpha_buf as an 8 bit RGBA image, the alpha is 0 where nothing was drawn
+    pub fn to_rgba_image(&self) -> RgbaImage {
+        RgbaImage::from_fn(self.width as u32, self.height as u32, |x, r| {
+            let ind = r as usize * self.width + x as usize;
+            let color = self.frame_buf[ind];
+            Rgba([export::to_u8(color.x), export::to_u8(color.y), export::to_u8(color.z), export::to_u8(self.alpha_buf[ind])])
+        })
+    }
+
+    /// Save frame_buf as an RGB image, the format follows the extension of path:
+    /// png, jpg, jpeg, bmp and tga through the image crate, ppm and pfm with the writers of the export module
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RasterizerError> {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+        match extension.as_str() {
+            "ppm" => export::write_ppm(path, self.width, self.height, &self.frame_buf),
+            "pfm" => export::write_pfm(path, self.width, self.height, &self.frame_buf),
+            "png" | "jpg" | "jpeg" | "bmp" | "tga" => {
+                let format = ImageFormat::from_extension(&extension).unwrap_or(ImageFormat::Png);
+                self.to_rgb_image().save_with_format(path, format).map_err(|e| export::write_error(path, e))
+            }
+            _ => Err(RasterizerError { err_code: 5007, message: path.display().to_string() }),
         }
-        self.frame_buf[ind as usize] = color;
     }
 
+    /// Draw a line between two screen space points with the line mode, the z coordinates are ignored and the line is drawn over everything.
+    /// line_color is RGB in [0, 255]
     pub fn draw_line(&mut self, begin: Vector3d<f32>, end: Vector3d<f32>, line_color: Vector3d<f32>) {
-        let mut point1 = Vector3d::new(0.0_f32, 0.0_f32, 0.0_f32);
-        let mut point2 = Vector3d::new(0.0_f32, 0.0_f32, 0.0_f32);
-        let distance = end.sub(begin);
-        let (mut dx, mut dy) = (f32::abs(distance.x), f32::abs(distance.y));
-        let (mut px, mut py) = (2.0_f32 * dy - dx, 2.0_f32 * dx - dy);
-
-        if dy <= dx {
-            if distance.x >= 0.0_f32 {
-                point1.x = begin.x;
-                point1.y = begin.y;
-                point2.x = end.x;
-            }else {
-                point1.x = end.x;
-                point1.y = end.y;
-                point2.x = begin.x;
+        self.rasterize_line(begin, end, [line_color; 2], false);
+    }
+
+    /** Draw a line between two points transformed by the model, view and projection matrices like draw_triangle
+      The line is clipped against the view frustum and depth tested, it is visible where it lies in front of or on a surface
+      up to the line depth bias, which keeps wireframes drawn over their own triangles visible. Lines do not write depth. Example
+    ```rust
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::{LineMode, Rasterizer};
+
+    let mut raster = Rasterizer::new(16, 16);
+    raster.set_model(Matrix4d::identity(1.0));
+    raster.set_view(Matrix4d::identity(1.0));
+    raster.set_projection(Matrix4d::identity(1.0));
+    raster.set_line_mode(LineMode::AntiAliased);
+    raster.draw_line_3d(Vector3d::new(-0.5, -0.5, 0.0), Vector3d::new(0.5, 0.25, 0.0), Vector3d::fill(255.0));
+    ```
+     */
+    pub fn draw_line_3d(&mut self, begin: Vector3d<f32>, end: Vector3d<f32>, line_color: Vector3d<f32>) {
+        let mvp = self.projection * self.view * self.model;
+        let a = mvp.product_with_vector4d(begin.to_vector4d(1.0_f32));
+        let b = mvp.product_with_vector4d(end.to_vector4d(1.0_f32));
+        self.draw_clip_line(a, b, [line_color; 2]);
+    }
+
+    /// Clip a line between two clip space positions and draw it depth tested, the colors RGB in [0, 255] of its ends are interpolated
+    fn draw_clip_line(&mut self, a: Vector4d<f32>, b: Vector4d<f32>, colors: [Vector3d<f32>; 2]) {
+        if let Some((t0, t1)) = clip_line(a, b, self.depth_range) {
+            let lerp = |t: f32| Vector4d::mul_item(a, 1.0_f32 - t).add(Vector4d::mul_item(b, t));
+            let lerp_color = |t: f32| Vector3d::mul_item(colors[0], 1.0_f32 - t).add(Vector3d::mul_item(colors[1], t));
+            let (begin, end) = (self.to_screen(lerp(t0)), self.to_screen(lerp(t1)));
+            self.rasterize_line(begin, end, [lerp_color(t0), lerp_color(t1)], true);
+        }
+    }
+
+    /// Draw a point at a clip space position depth tested unless it lies outside the view frustum, see set_point_size
+    fn draw_clip_point(&mut self, position: Vector4d<f32>, color: Vector3d<f32>) {
+        if clip_line(position, position, self.depth_range).is_none() {
+            return;
+        }
+        let point = self.to_screen(position);
+        let pixels = if self.point_size <= 1.0_f32 {
+            vec![line::LinePixel { x: point.x.floor() as i64, y: point.y.floor() as i64, t: 0.0_f32, coverage: 1.0_f32 }]
+        } else {
+            line::thick_clipped(point.head2(), point.head2(), self.point_size, self.width, self.height)
+        };
+        self.rasterize_pixels(&pixels, point, point, [color; 2], true, false);
+    }
+
+    /// Draw the edges of the triangles of an index buffer as depth tested lines, edges shared by two triangles are drawn once
+    pub fn draw_wireframe(&mut self, pos_id: PositionBufferId, ind_id: IndexBufferId, line_color: Vector3d<f32>) -> Result<(), RasterizerError> {
+        let buf = self.positions(pos_id)?.clone();
+        let primitives = PrimitiveTopology::TriangleList.assemble(self.indices(ind_id)?);
+        let mut drawn = HashSet::new();
+        for indices in primitives.iter() {
+            for index in indices.iter() {
+                if *index < 0 || *index as usize >= buf.len() {
+                    return Err(
+                        RasterizerError {
+                            err_code: 5004,
+                            message: format!("index: {}, length: {}", index, buf.len())
+                        }
+                    );
+                }
             }
-            self.set_pixel(point1, line_color);
-            for _ in point1.x as usize .. point2.x as usize {
-                point1.x = point1.x + 1.0_f32;
-                if px < 0.0_f32 {
-                    px = px + 2.0_f32 * dy;
-                }else {
-                    if (distance.x < 0.0_f32 && distance.y < 0.0_f32) || (distance.x > 0.0_f32 && distance.y > 0.0_f32)
-                    {
-                        point1.y = point1.y + 1.0_f32;
-                    }else {
-                        point1.y = point1.y - 1.0_f32;
-                    }
-                    px = px + 2.0_f32 * (distance.y - distance.x);
+            for k in 0..3 {
+                let (i, j) = (indices[k], indices[(k + 1) % 3]);
+                if drawn.insert((i.min(j), i.max(j))) {
+                    self.draw_line_3d(buf[i as usize], buf[j as usize], line_color);
                 }
-                self.set_pixel(point1, line_color);
             }
-        }else {
-            if dy >= 0.0_f32 {
-                point1.x = begin.x;
-                point1.y = begin.y;
-                point2.y = end.y;
-            }else {
-                point1.x = end.x;
-                point1.y = end.y;
-                point2.y = begin.y;
+        }
+        Ok(())
+    }
+
+    /// Rasterize a screen space line with the line mode, depth tested against depth_buf when depth_test is set.
+    /// The line is first clipped to the viewport grown by the pixels a thick or anti-aliased line reaches beyond it,
+    /// lines outside it or with a non-finite end are skipped
+    fn rasterize_line(&mut self, begin: Vector3d<f32>, end: Vector3d<f32>, colors: [Vector3d<f32>; 2], depth_test: bool) {
+        let margin = match self.line_mode {
+            LineMode::Thick(width) => 0.5_f32 * width + 1.0_f32,
+            _ => 1.0_f32,
+        };
+        let (min, max) = (Vector2d::fill(-margin), Vector2d::new(self.width as f32 + margin, self.height as f32 + margin));
+        let (t0, t1) = match line::clip_segment(begin.head2(), end.head2(), min, max) {
+            None => return,
+            Some(range) => range
+        };
+        let lerp = |t: f32| Vector3d::mul_item(begin, 1.0_f32 - t).add(Vector3d::mul_item(end, t));
+        let lerp_color = |t: f32| Vector3d::mul_item(colors[0], 1.0_f32 - t).add(Vector3d::mul_item(colors[1], t));
+        let (begin, end, colors) = (lerp(t0), lerp(t1), [lerp_color(t0), lerp_color(t1)]);
+
+        let pixels = match self.line_mode {
+            LineMode::Aliased => line::bresenham(begin.head2(), end.head2()),
+            LineMode::AntiAliased => line::wu(begin.head2(), end.head2()),
+            LineMode::Thick(width) => line::thick_clipped(begin.head2(), end.head2(), width, self.width, self.height),
+        };
+        self.rasterize_pixels(&pixels, begin, end, colors, depth_test, self.line_mode == LineMode::AntiAliased);
+    }
+
+    /// Draw the pixels of a line from begin to end with the colors of its ends interpolated, blending their coverage when anti_aliased is set
+    fn rasterize_pixels(&mut self, pixels: &[line::LinePixel], begin: Vector3d<f32>, end: Vector3d<f32>, colors: [Vector3d<f32>; 2],
+                        depth_test: bool, anti_aliased: bool) {
+        for p in pixels.iter() {
+            if p.x < 0 || p.y < 0 || p.x >= self.width as i64 || p.y >= self.height as i64 {
+                continue;
             }
-            self.set_pixel(point1,line_color);
-            for _ in point1.y as usize .. point2.y as usize {
-                point1.y = point1.y + 1.0_f32;
-                if py <= 0.0_f32 {
-                    py = py + 2.0_f32 * dx;
-                }else {
-                    if (distance.x < 0.0_f32 && distance.y < 0.0_f32) || (distance.x > 0.0_f32 && distance.y > 0.0_f32) {
-                        point1.x = point1.x + 1.0_f32;
-                    }else {
-                        point1.x = point1.x - 1.0_f32;
-                    }
-                    py = py + 2.0_f32 * (dx - dy);
-                }
-                self.set_pixel(point1,line_color);
+            let (x, y) = (p.x as usize, p.y as usize);
+            let ind = self.get_index(x, y);
+            let z = begin.z + (end.z - begin.z) * p.t;
+            // The pixel center lies up to a pixel away from the point of the line it stands for, so the bias grows with the slope of the surface
+            if depth_test && z - self.line_depth_bias - self.depth_slope(x, y) > self.depth_buf[ind] {
+                continue;
+            }
+            let color = Vector3d::mul_item(colors[0], 1.0_f32 - p.t).add(Vector3d::mul_item(colors[1], p.t));
+            if anti_aliased {
+                self.blend_pixel(ind, &BlendState::alpha(), Vector3d::div_item(color, 255.0_f32).to_vector4d(p.coverage));
+            } else {
+                self.set_pixel(Vector3d::new(x as f32, y as f32, z), color);
             }
         }
     }
 
+    /// Sum of the depth differences to the neighbours of pixel (x, y) along both axes, taking the smaller one of each axis
+    /// so the depth discontinuities at silhouettes are ignored. Infinite depths of empty pixels are skipped
+    fn depth_slope(&self, x: usize, y: usize) -> f32 {
+        let depth = self.depth_buf[self.get_index(x, y)];
+        let axis = |neighbours: [Option<(usize, usize)>; 2]| {
+            let slope = neighbours.iter().flatten()
+                .map(|(nx, ny)| (self.depth_buf[self.get_index(*nx, *ny)] - depth).abs())
+                .filter(|d| d.is_finite())
+                .fold(f32::INFINITY, f32::min);
+            if slope.is_finite() { slope } else { 0.0_f32 }
+        };
+        let horizontal = axis([x.checked_sub(1).map(|x| (x, y)), (x + 1 < self.width).then(|| (x + 1, y))]);
+        let vertical = axis([y.checked_sub(1).map(|y| (x, y)), (y + 1 < self.height).then(|| (x, y + 1))]);
+        horizontal + vertical
+    }
+
     pub fn rasterizer_wireframe(&mut self, triangle: Triangle, line_color: Vector3d<f32>) {
         self.draw_line(triangle.get_vertex_c(), triangle.get_vertex_a(), line_color);
         self.draw_line(triangle.get_vertex_c(), triangle.get_vertex_b(), line_color);
         self.draw_line(triangle.get_vertex_b(), triangle.get_vertex_a(), line_color);
     }
 
-    pub fn draw_triangle(&mut self, pos_id: usize, ind_id: usize, color: Vector3d<f32>) -> Result<(), TriangleError>{
-        let buf: Vec<Vector3d<f32>> = match self.pos_buf.get(&pos_id) {
-            None => vec![],
-            Some(v) => v.to_vec()
+    /// Fill a screen space triangle with the bound fragment shader. The attributes of the triangle are interpolated
+    /// linearly with screen space barycentric coordinates, its screen space vertices stand in for the world positions.
+    pub fn rasterize_triangle(&mut self, triangle: &Triangle) {
+        let varyings = [0, 1, 2].map(|i| Varyings {
+            color: triangle.color[i],
+            normal: triangle.normal[i],
+            tex_coords: triangle.tex_coords[i],
+            world_pos: triangle.vertex[i],
+        });
+        let state = self.fragment_state(self.depth_write);
+        self.fill_triangles(&[ScreenTriangle { v: triangle.vertex, inv_w: [1.0_f32; 3], varyings, state }]);
+    }
+
+    /// Fill the triangles in order, binned into tiles which are rasterized in parallel when more than one thread is set
+    fn fill_triangles(&mut self, triangles: &[ScreenTriangle]) {
+        if self.threads > 1 {
+            self.fill_tiled(triangles);
+            return;
+        }
+        let mut tile = Tile {
+            x0: 0,
+            y0: 0,
+            width: self.width,
+            height: self.height,
+            frame_buf: std::mem::take(&mut self.frame_buf),
+            alpha_buf: std::mem::take(&mut self.alpha_buf),
+            depth_buf: std::mem::take(&mut self.depth_buf),
+            sample_frame_buf: std::mem::take(&mut self.sample_frame_buf),
+            sample_depth_buf: std::mem::take(&mut self.sample_depth_buf),
         };
-        let ind: Vec<Vector3d<i32>> = match self.ind_buf.get(&ind_id) {
-            None => vec![],
-            Some(v) => v.to_vec()
+        for triangle in triangles.iter() {
+            self.fill_triangle(&mut tile, triangle);
+        }
+        self.frame_buf = tile.frame_buf;
+        self.alpha_buf = tile.alpha_buf;
+        self.depth_buf = tile.depth_buf;
+        self.sample_frame_buf = tile.sample_frame_buf;
+        self.sample_depth_buf = tile.sample_depth_buf;
+    }
+
+    /// Sort the triangles into the tiles their bounding boxes overlap, then rasterize every tile on its own copy of the buffers.
+    /// Each pixel sees the same triangles in the same order as without tiles, so the result is identical.
+    fn fill_tiled(&mut self, triangles: &[ScreenTriangle]) {
+        let size = self.tile_size;
+        let columns = self.width.div_ceil(size);
+        let rows = self.height.div_ceil(size);
+        let mut bins: Vec<Vec<usize>> = vec![Vec::new(); columns * rows];
+        for (i, triangle) in triangles.iter().enumerate() {
+            if !triangle.is_fillable() {
+                continue;
+            }
+            if let Some((x_min, x_max, y_min, y_max)) = bounding_box(&triangle.v, 0, 0, self.width, self.height) {
+                for row in y_min / size..=y_max / size {
+                    for column in x_min / size..=x_max / size {
+                        bins[row * columns + column].push(i);
+                    }
+                }
+            }
+        }
+        let jobs: Vec<(usize, Vec<usize>)> = bins.into_iter().enumerate().filter(|(_, bin)| !bin.is_empty()).collect();
+        let render = |(tile, bin): &(usize, Vec<usize>)| {
+            let (x0, y0) = ((tile % columns) * size, (tile / columns) * size);
+            let mut tile = Tile::copy_from(self, x0, y0, size.min(self.width - x0), size.min(self.height - y0));
+            for i in bin.iter() {
+                self.fill_triangle(&mut tile, &triangles[*i]);
+            }
+            tile
         };
 
-        let f1 = (100.0_f32 - 0.1_f32) / 2.0_f32;
-        let f2 = (100.0_f32 + 0.1_f32) / 2.0_f32;
+        #[cfg(feature = "rayon")]
+        let tiles: Vec<Tile> = {
+            use rayon::prelude::*;
+            jobs.par_iter().map(render).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let tiles: Vec<Tile> = {
+            let next = AtomicUsize::new(0);
+            std::thread::scope(|scope| {
+                let workers: Vec<_> = (0..self.threads.min(jobs.len()))
+                    .map(|_| scope.spawn(|| {
+                        let mut tiles = Vec::new();
+                        loop {
+                            let job = next.fetch_add(1, Ordering::Relaxed);
+                            if job >= jobs.len() {
+                                break;
+                            }
+                            tiles.push(render(&jobs[job]));
+                        }
+                        tiles
+                    }))
+                    .collect();
+                workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+            })
+        };
 
-        let mvp = self.projection * self.view * self.model;
+        for tile in tiles.iter() {
+            tile.copy_to(self);
+        }
+    }
+
+    /// Cover a screen space triangle inside the tile, every covered sample is depth tested and shaded with the interpolated varyings.
+    /// Without anti-aliasing pixels are sampled at their centers, a sample lying exactly on a shared edge follows the top-left rule and is covered only once.
+    /// inv_w holds 1 / w of the clip space vertices, the depth is interpolated linearly in screen space
+    /// and the varyings perspective-correctly, by interpolating varyings / w and 1 / w and dividing the two.
+    fn fill_triangle(&self, tile: &mut Tile, triangle: &ScreenTriangle) {
+        let (v, state) = (&triangle.v, &triangle.state);
+        let area = triangle.area();
+        if !triangle.is_fillable() || state.is_culled(area) {
+            return;
+        }
+        let (x_min, x_max, y_min, y_max) = match bounding_box(v, tile.x0, tile.y0, tile.width, tile.height) {
+            None => return,
+            Some(b) => b
+        };
+
+        let offsets = self.anti_aliasing.sample_offsets();
+        let n = offsets.len();
+        let supersampling = self.anti_aliasing.is_supersampling();
+        for y in y_min..=y_max {
+            for x in x_min..=x_max {
+                let ind = tile.get_index(x, y);
+                let center = Vector2d::new(x as f32 + 0.5_f32, y as f32 + 0.5_f32);
 
-        for ind_vec in ind.iter() {
-            let mut t = Triangle::new();
-            let v0 = match buf.get(ind_vec.x as usize) {
-                None => {
-                    Err(
-                        TriangleError {
-                            err_code: 3001,
-                            message: format!("index: {}, length: {}", ind_vec.x, buf.len())
+                // Barycentric coordinates and depth of the samples passing the coverage and depth tests
+                let mut passed = [None; 8];
+                for (s, (dx, dy)) in offsets.iter().enumerate() {
+                    let point = Vector2d::new(center.x + dx, center.y + dy);
+                    if let Some((alpha, beta, gamma)) = compute_barycentric_2d(point, v, area) {
+                        let z = alpha * v[0].z + beta * v[1].z + gamma * v[2].z;
+                        if z < tile.sample_depth_buf[ind * n + s] {
+                            passed[s] = Some((point, alpha, beta, gamma, z));
                         }
-                    )
-                },
-                Some(buf_vec) => {
-                    let v = mvp.product_with_vector4d(buf_vec.to_vector4d(1.0_f32));
-                    let mut v = Vector4d::div_item(v, v.w);
-                    v.x = 0.5_f32 * self.width as f32 * (v.x + 1.0_f32);
-                    v.y = 0.5_f32 * self.height as f32 * (v.y + 1.0_f32);
-                    v.z = v.z * f1 + f2;
-                    Ok(v)
+                    }
                 }
-            }?;
-            let v1 = match buf.get(ind_vec.y as usize) {
-                None => {
-                    Err(
-                        TriangleError {
-                            err_code: 3001,
-                            message: format!("index: {}, length: {}", ind_vec.x, buf.len())
+                let first = match passed.iter().flatten().next() {
+                    None => continue,
+                    Some(sample) => *sample
+                };
+                if state.depth_only {
+                    for (s, sample) in passed.iter().enumerate().take(n) {
+                        if let Some(sample) = sample {
+                            tile.sample_depth_buf[ind * n + s] = sample.4;
                         }
-                    )
-                },
-                Some(buf_vec) => {
-                    let v = mvp.product_with_vector4d(buf_vec.to_vector4d(1.0_f32));
-                    let mut v = Vector4d::div_item(v, v.w);
-                    v.x = 0.5_f32 * self.width as f32 * (v.x + 1.0_f32);
-                    v.y = 0.5_f32 * self.height as f32 * (v.y + 1.0_f32);
-                    v.z = v.z * f1 + f2;
-                    Ok(v)
+                    }
+                    tile.depth_buf[ind] = tile.sample_depth_buf[ind * n..(ind + 1) * n].iter().fold(f32::INFINITY, |acc, d| acc.min(*d));
+                    continue;
                 }
-            }?;
-            let v2 = match buf.get(ind_vec.z as usize) {
-                None => {
-                    Err(
-                        TriangleError {
-                            err_code: 3001,
-                            message: format!("index: {}, length: {}", ind_vec.x, buf.len())
+                let blend = |src: Vector4d<f32>, dst: Vector4d<f32>| blend_255(&state.blend, src, dst);
+
+                // Multisampling shades the pixel center, or the first passing sample when the center is not covered
+                let pixel_color = if supersampling {
+                    Vector4d::fill(0.0_f32)
+                } else {
+                    let sample = match compute_barycentric_2d(center, v, area) {
+                        Some((alpha, beta, gamma)) if n > 1 => (center, alpha, beta, gamma, alpha * v[0].z + beta * v[1].z + gamma * v[2].z),
+                        _ => first,
+                    };
+                    shade(triangle, sample)
+                };
+                for (s, sample) in passed.iter().enumerate().take(n) {
+                    if let Some(sample) = sample {
+                        if state.depth_write {
+                            tile.sample_depth_buf[ind * n + s] = sample.4;
                         }
-                    )
-                },
-                Some(buf_vec) => {
-                    let v = mvp.product_with_vector4d(buf_vec.to_vector4d(1.0_f32));
-                    let mut v = Vector4d::div_item(v, v.w);
-                    v.x = 0.5_f32 * self.width as f32 * (v.x + 1.0_f32);
-                    v.y = 0.5_f32 * self.height as f32 * (v.y + 1.0_f32);
-                    v.z = v.z * f1 + f2;
-                    Ok(v)
+                        let color = if supersampling { shade(triangle, *sample) } else { pixel_color };
+                        tile.sample_frame_buf[ind * n + s] = blend(color, tile.sample_frame_buf[ind * n + s]);
+                    }
                 }
-            }?;
+                tile.resolve(ind, n);
+            }
+        }
+    }
 
-            t.set_vertex(0, v0.head3()).expect("draw triangle panic\n");
-            t.set_vertex(1, v1.head3()).expect("draw triangle panic\n");
-            t.set_vertex(2, v2.head3()).expect("draw triangle panic\n");
+    /// The current fragment shader, blend state, culling and depth only state, shared by the triangles of a draw call
+    fn fragment_state(&self, depth_write: bool) -> Arc<FragmentState> {
+        Arc::new(FragmentState {
+            shader: self.fragment_shader.clone(),
+            blend: self.blend,
+            depth_write,
+            depth_only: self.depth_only,
+            front_face: self.front_face,
+            cull_mode: self.cull_mode,
+        })
+    }
 
-            t.set_color(0, color.x, 0.0_f32, 0.0_f32).expect("draw triangle panic\n");
-            t.set_color(1, 0.0_f32, color.y, 0.0_f32).expect("draw triangle panic\n");
-            t.set_color(2, 0.0_f32, 0.0_f32, color.z).expect("draw triangle panic\n");
+    /// Map a clip space position to screen space: divide by w, then scale x and y to the viewport
+    /// and z to [SCREEN_DEPTH_NEAR, SCREEN_DEPTH_FAR]
+    fn to_screen(&self, clip: Vector4d<f32>) -> Vector3d<f32> {
+        viewport_transform(clip, self.width, self.height, self.depth_range)
+    }
 
-            self.rasterizer_wireframe(t, color);
+    /// Draw the triangles of an index buffer. Every vertex goes through the vertex shader and every covered pixel through the fragment shader,
+    /// with the attribute buffers bound to the positions. Vertices without a color buffer take color, RGB in [0, 255].
+    /// Triangles are clipped against the view frustum before the division by w, so geometry behind the camera is discarded.
+    pub fn draw_triangle(&mut self, pos_id: PositionBufferId, ind_id: IndexBufferId, color: Vector3d<f32>) -> Result<(), RasterizerError>{
+        self.draw(PrimitiveTopology::TriangleList, pos_id, ind_id, color)
+    }
+
+    /** Draw the primitives of an index buffer assembled with topology, every vertex goes through the vertex shader
+      Triangles are filled like draw_triangle. Points and lines are drawn with the point size and line mode in the interpolated vertex color,
+      depth tested like draw_line_3d and without the fragment shader. The primitives before an invalid index are still drawn. Example
+    ```rust
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::{PrimitiveTopology, Rasterizer};
+
+    let mut raster = Rasterizer::new(8, 8);
+    raster.set_model(Matrix4d::identity(1.0));
+    raster.set_view(Matrix4d::identity(1.0));
+    raster.set_projection(Matrix4d::identity(1.0));
+    // A polyline through the corners of a square
+    let pos_id = raster.load_positions(vec![
+        Vector3d::new(-0.5, -0.5, 0.0), Vector3d::new(0.5, -0.5, 0.0), Vector3d::new(0.5, 0.5, 0.0), Vector3d::new(-0.5, 0.5, 0.0),
+    ]);
+    let ind_id = raster.load_index_list(vec![0, 1, 2, 3, 0]);
+    raster.draw(PrimitiveTopology::LineStrip, pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+    let lit = raster.frame_buf.iter().filter(|c| c.x > 0.0).count();
+    ```
+
+    output lit:
+    ```text
+    16
+    ```
+     */
+    pub fn draw(&mut self, topology: PrimitiveTopology, pos_id: PositionBufferId, ind_id: IndexBufferId, color: Vector3d<f32>) -> Result<(), RasterizerError> {
+        if topology.primitive() == Primitive::Triangle {
+            let (triangles, result) = self.assemble_triangles(topology, pos_id, ind_id, color, self.depth_write);
+            // The triangles before an invalid index are still drawn
+            self.fill_triangles(&triangles);
+            return result;
+        }
+
+        let vertices = self.vertex_payloads(pos_id, color)?;
+        let transforms = Transforms::new(self.model, self.view, self.projection);
+        for primitive in topology.assemble(self.indices(ind_id)?) {
+            let mut outputs = Vec::with_capacity(2);
+            for index in primitive.iter() {
+                outputs.push(self.vertex_shader.shade(vertex_at(&vertices, *index)?, &transforms));
+            }
+            let color = |k: usize| Vector3d::mul_item(outputs[k].varyings.color, 255.0_f32);
+            if outputs.len() == 1 {
+                self.draw_clip_point(outputs[0].position, color(0));
+            } else {
+                self.draw_clip_line(outputs[0].position, outputs[1].position, [color(0), color(1)]);
+            }
         }
         Ok(())
     }
+
+    /** Queue the triangles of an index buffer for the transparent pass, like draw_triangle with the current shaders and blend state
+      The triangles are drawn by flush_transparent, sorted back-to-front together with every other queued triangle, depth tested but without writing depth.
+      The transforms, shaders, blend state, front face, cull mode and depth only state are those of this call,
+      while the anti-aliasing is that of the sample buffers at the time of the flush.
+      Draw the opaque geometry first, then queue the transparent geometry with a blend state such as BlendState::alpha and flush it. Example
+    ```rust
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::blend::BlendState;
+    use QRender::rasterizer::rasterizer::Rasterizer;
+    use QRender::rasterizer::shader::{ColorFragmentShader, OpacityFragmentShader};
+
+    let mut raster = Rasterizer::new(4, 4);
+    raster.set_model(Matrix4d::identity(1.0));
+    raster.set_view(Matrix4d::identity(1.0));
+    raster.set_projection(Matrix4d::identity(1.0));
+    let pos_id = raster.load_positions(vec![Vector3d::new(-4.0, -4.0, 0.0), Vector3d::new(4.0, -4.0, 0.0), Vector3d::new(0.0, 4.0, 0.0)]);
+    let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+    // A half transparent blue pane in front of a black background
+    raster.set_blend_state(BlendState::alpha());
+    raster.set_fragment_shader(OpacityFragmentShader::new(ColorFragmentShader, 0.5));
+    raster.draw_transparent(pos_id, ind_id, Vector3d::new(0.0, 0.0, 255.0)).unwrap();
+    raster.flush_transparent();
+    let alpha = raster.alpha_buf[0];
+    ```
+
+    output alpha, the coverage of the pane:
+    ```text
+    127.5
+    ```
+     */
+    pub fn draw_transparent(&mut self, pos_id: PositionBufferId, ind_id: IndexBufferId, color: Vector3d<f32>) -> Result<(), RasterizerError> {
+        let (triangles, result) = self.assemble_triangles(PrimitiveTopology::TriangleList, pos_id, ind_id, color, false);
+        self.transparent_queue.extend(triangles);
+        result
+    }
+
+    /// Draw the triangles queued by draw_transparent from the farthest to the closest and empty the queue
+    pub fn flush_transparent(&mut self) {
+        let mut triangles = std::mem::take(&mut self.transparent_queue);
+        triangles.sort_by(|a, b| b.depth().total_cmp(&a.depth()));
+        self.fill_triangles(&triangles);
+    }
+
+    /// Run the vertex shader on the triangles of an index buffer assembled with a triangle topology, clip them and map them to the screen.
+    /// Stops at the first invalid index and returns the triangles before it along with the error, unknown buffers give no triangles.
+    fn assemble_triangles(&self, topology: PrimitiveTopology, pos_id: PositionBufferId, ind_id: IndexBufferId, color: Vector3d<f32>,
+                          depth_write: bool) -> (Vec<ScreenTriangle>, Result<(), RasterizerError>) {
+        let (vertices, ind) = match (self.vertex_payloads(pos_id, color), self.indices(ind_id)) {
+            (Ok(vertices), Ok(ind)) => (vertices, ind),
+            (Err(e), _) | (_, Err(e)) => return (Vec::new(), Err(e)),
+        };
+
+        let transforms = Transforms::new(self.model, self.view, self.projection);
+        let state = self.fragment_state(depth_write);
+        let primitives = topology.assemble(ind);
+        let mut triangles = Vec::with_capacity(primitives.len());
+        for indices in primitives.iter() {
+            let mut outputs = Vec::with_capacity(3);
+
+            for index in indices.iter() {
+                match vertex_at(&vertices, *index) {
+                    Err(e) => return (triangles, Err(e)),
+                    Ok(payload) => outputs.push(self.vertex_shader.shade(payload, &transforms)),
+                }
+            }
+
+            let polygon = clip_triangle(&[outputs[0], outputs[1], outputs[2]], self.depth_range);
+            let screen: Vec<Vector3d<f32>> = polygon.iter().map(|v| self.to_screen(v.position)).collect();
+            // The clipped polygon is convex, split it into a fan around its first vertex
+            for k in 1..polygon.len().saturating_sub(1) {
+                triangles.push(ScreenTriangle {
+                    v: [screen[0], screen[k], screen[k + 1]],
+                    inv_w: [1.0_f32 / polygon[0].position.w, 1.0_f32 / polygon[k].position.w, 1.0_f32 / polygon[k + 1].position.w],
+                    varyings: [polygon[0].varyings, polygon[k].varyings, polygon[k + 1].varyings],
+                    state: state.clone(),
+                });
+            }
+        }
+        (triangles, Ok(()))
+    }
+}
+
+/// The flat index list of a triangle list
+fn flatten_indices(indices: &[Vector3d<i32>]) -> Vec<i32> {
+    indices.iter().flat_map(|v| [v.x, v.y, v.z]).collect()
+}
+
+/// The attributes of the vertex at index, or an error when the index is out of range
+fn vertex_at(vertices: &[VertexPayload], index: i32) -> Result<&VertexPayload, RasterizerError> {
+    usize::try_from(index).ok().and_then(|i| vertices.get(i)).ok_or_else(|| RasterizerError {
+        err_code: 5004,
+        message: format!("index: {}, length: {}", index, vertices.len()),
+    })
+}
+
+/// The attribute buffers bound to a position buffer, one of each kind
+#[derive(Debug, Clone, Copy, Default)]
+struct VertexLayout {
+    colors:     Option<AttributeBufferId>,
+    normals:    Option<AttributeBufferId>,
+    tex_coords: Option<AttributeBufferId>,
+}
+
+/// The error for a handle without a buffer, err_code tells which kind of buffer
+fn unknown_buffer(err_code: usize, id: impl std::fmt::Debug) -> RasterizerError {
+    RasterizerError {
+        err_code,
+        message: format!("{:?}", id),
+    }
+}
+
+/// The fragment shader, blend state, depth writes and culling a triangle is filled with
+struct FragmentState {
+    shader:      Arc<dyn FragmentShader>,
+    blend:       BlendState,
+    depth_write: bool,
+    depth_only:  bool,
+    front_face:  FrontFace,
+    cull_mode:   CullMode,
+}
+
+impl FragmentState {
+    /// Whether a triangle whose screen space vertices span the signed area is discarded by the cull mode,
+    /// the area is positive for counter-clockwise triangles as the y axis of the screen points upwards
+    fn is_culled(&self, area: f32) -> bool {
+        let front_facing = match self.front_face {
+            FrontFace::CounterClockwise => area > 0.0_f32,
+            FrontFace::Clockwise => area < 0.0_f32,
+        };
+        match self.cull_mode {
+            CullMode::None => false,
+            CullMode::Front => front_facing,
+            CullMode::Back => !front_facing,
+        }
+    }
+}
+
+/// A triangle after the viewport transform, inv_w holds 1 / w of its clip space vertices
+#[derive(Clone)]
+struct ScreenTriangle {
+    v:        [Vector3d<f32>; 3],
+    inv_w:    [f32; 3],
+    varyings: [Varyings; 3],
+    state:    Arc<FragmentState>,
+}
+
+impl ScreenTriangle {
+    /// Twice the signed area, positive for counter-clockwise triangles
+    fn area(&self) -> f32 {
+        edge_function(self.v[0].head2(), self.v[1].head2(), self.v[2].head2())
+    }
+
+    /// Mean screen space depth of the vertices, the sort key of the transparent pass
+    fn depth(&self) -> f32 {
+        (self.v[0].z + self.v[1].z + self.v[2].z) / 3.0_f32
+    }
+
+    /// Degenerate triangles and triangles with vertices at infinity cover nothing
+    fn is_fillable(&self) -> bool {
+        let area = self.area();
+        area != 0.0_f32 && area.is_finite()
+    }
+}
+
+/// The frame, depth and sample buffers of the width x height pixels starting at (x0, y0), stored bottom row last like the buffers of the rasterizer
+struct Tile {
+    x0:               usize,
+    y0:               usize,
+    width:            usize,
+    height:           usize,
+    frame_buf:        Vec<Vector3d<f32>>,
+    alpha_buf:        Vec<f32>,
+    depth_buf:        Vec<f32>,
+    sample_frame_buf: Vec<Vector4d<f32>>,
+    sample_depth_buf: Vec<f32>,
+}
+
+impl Tile {
+    /// Copy a rectangle of the buffers of the rasterizer
+    fn copy_from(raster: &Rasterizer, x0: usize, y0: usize, width: usize, height: usize) -> Tile {
+        let n = raster.anti_aliasing.sample_count();
+        let mut tile = Tile {
+            x0,
+            y0,
+            width,
+            height,
+            frame_buf: Vec::with_capacity(width * height),
+            alpha_buf: Vec::with_capacity(width * height),
+            depth_buf: Vec::with_capacity(width * height),
+            sample_frame_buf: Vec::with_capacity(width * height * n),
+            sample_depth_buf: Vec::with_capacity(width * height * n),
+        };
+        for y in (y0..y0 + height).rev() {
+            let row = raster.get_index(x0, y);
+            tile.frame_buf.extend_from_slice(&raster.frame_buf[row..row + width]);
+            tile.alpha_buf.extend_from_slice(&raster.alpha_buf[row..row + width]);
+            tile.depth_buf.extend_from_slice(&raster.depth_buf[row..row + width]);
+            tile.sample_frame_buf.extend_from_slice(&raster.sample_frame_buf[row * n..(row + width) * n]);
+            tile.sample_depth_buf.extend_from_slice(&raster.sample_depth_buf[row * n..(row + width) * n]);
+        }
+        tile
+    }
+
+    /// Write the tile back into the buffers of the rasterizer
+    fn copy_to(&self, raster: &mut Rasterizer) {
+        let n = raster.anti_aliasing.sample_count();
+        for y in self.y0..self.y0 + self.height {
+            let (row, local) = (raster.get_index(self.x0, y), self.get_index(self.x0, y));
+            let width = self.width;
+            raster.frame_buf[row..row + width].copy_from_slice(&self.frame_buf[local..local + width]);
+            raster.alpha_buf[row..row + width].copy_from_slice(&self.alpha_buf[local..local + width]);
+            raster.depth_buf[row..row + width].copy_from_slice(&self.depth_buf[local..local + width]);
+            raster.sample_frame_buf[row * n..(row + width) * n].copy_from_slice(&self.sample_frame_buf[local * n..(local + width) * n]);
+            raster.sample_depth_buf[row * n..(row + width) * n].copy_from_slice(&self.sample_depth_buf[local * n..(local + width) * n]);
+        }
+    }
+
+    /// Index of the pixel (x, y) of the screen in the buffers of the tile
+    fn get_index(&self, x: usize, y: usize) -> usize {
+        (self.height - 1 - (y - self.y0)) * self.width + x - self.x0
+    }
+
+    /// Average the n sample colors of the pixel ind into frame_buf and alpha_buf and keep the closest sample depth in depth_buf
+    fn resolve(&mut self, ind: usize, n: usize) {
+        if n == 1 {
+            self.frame_buf[ind] = self.sample_frame_buf[ind].head3();
+            self.alpha_buf[ind] = self.sample_frame_buf[ind].w;
+            self.depth_buf[ind] = self.sample_depth_buf[ind];
+            return;
+        }
+        let colors = &self.sample_frame_buf[ind * n..(ind + 1) * n];
+        let average = Vector4d::div_item(colors.iter().fold(Vector4d::fill(0.0_f32), |acc, c| acc.add(*c)), n as f32);
+        self.frame_buf[ind] = average.head3();
+        self.alpha_buf[ind] = average.w;
+        self.depth_buf[ind] = self.sample_depth_buf[ind * n..(ind + 1) * n].iter().fold(f32::INFINITY, |acc, d| acc.min(*d));
+    }
+}
+
+/// Run the fragment shader of the triangle at a sample given as (position, alpha, beta, gamma, depth), returns RGBA in [0, 1]
+fn shade(triangle: &ScreenTriangle, sample: (Vector2d<f32>, f32, f32, f32, f32)) -> Vector4d<f32> {
+    let (point, alpha, beta, gamma, z) = sample;
+    let inv_w = triangle.inv_w;
+    let (pa, pb, pc) = (alpha * inv_w[0], beta * inv_w[1], gamma * inv_w[2]);
+    let sum = pa + pb + pc;
+    let fragment = FragmentPayload {
+        screen_pos: Vector3d::new(point.x, point.y, z),
+        varyings: Varyings::interpolate(&triangle.varyings, pa / sum, pb / sum, pc / sum),
+    };
+    triangle.state.shader.shade_rgba(&fragment)
+}
+
+/// Blend the RGBA fragment src in [0, 1] into the destination dst in [0, 255], returns the new destination in [0, 255]
+fn blend_255(blend: &BlendState, src: Vector4d<f32>, dst: Vector4d<f32>) -> Vector4d<f32> {
+    Vector4d::mul_item(blend.blend(src, Vector4d::div_item(dst, 255.0_f32)), 255.0_f32)
+}
+
+/// The pixels (x_min, x_max, y_min, y_max) overlapped by the bounding box of the triangle v, inclusive and clamped to the width x height pixels at (x0, y0)
+fn bounding_box(v: &[Vector3d<f32>; 3], x0: usize, y0: usize, width: usize, height: usize) -> Option<(usize, usize, usize, usize)> {
+    let x_min = f32::min(v[0].x, f32::min(v[1].x, v[2].x)).floor().max(x0 as f32);
+    let x_max = f32::max(v[0].x, f32::max(v[1].x, v[2].x)).ceil().min((x0 + width) as f32 - 1.0_f32);
+    let y_min = f32::min(v[0].y, f32::min(v[1].y, v[2].y)).floor().max(y0 as f32);
+    let y_max = f32::max(v[0].y, f32::max(v[1].y, v[2].y)).ceil().min((y0 + height) as f32 - 1.0_f32);
+    if x_min > x_max || y_min > y_max {
+        return None;
+    }
+    Some((x_min as usize, x_max as usize, y_min as usize, y_max as usize))
+}
+
+/// Divide the clip space position by w, then scale x and y to a viewport of width x height pixels
+/// and z to [SCREEN_DEPTH_NEAR, SCREEN_DEPTH_FAR]
+pub(crate) fn viewport_transform(clip: Vector4d<f32>, width: usize, height: usize, depth_range: DepthRange) -> Vector3d<f32> {
+    let (f1, f2) = match depth_range {
+        DepthRange::NegativeOneToOne => ((SCREEN_DEPTH_FAR - SCREEN_DEPTH_NEAR) / 2.0_f32, (SCREEN_DEPTH_FAR + SCREEN_DEPTH_NEAR) / 2.0_f32),
+        DepthRange::ZeroToOne => (SCREEN_DEPTH_FAR - SCREEN_DEPTH_NEAR, SCREEN_DEPTH_NEAR),
+    };
+
+    let v = Vector4d::div_item(clip, clip.w);
+    Vector3d::new(
+        0.5_f32 * width as f32 * (v.x + 1.0_f32),
+        0.5_f32 * height as f32 * (v.y + 1.0_f32),
+        v.z * f1 + f2
+    )
+}
+
+/// Twice the signed area of the triangle (a, b, p), positive when p lies on the left of the edge a -> b
+#[inline]
+fn edge_function(a: Vector2d<f32>, b: Vector2d<f32>, p: Vector2d<f32>) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Whether the edge a -> b of a counter-clockwise triangle is a top or a left edge
+#[inline]
+fn is_top_left(a: Vector2d<f32>, b: Vector2d<f32>) -> bool {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    dy < 0.0_f32 || (dy == 0.0_f32 && dx < 0.0_f32)
+}
+
+/// Barycentric coordinates of p in the triangle v, None if p is not covered by the triangle.
+/// area is the signed value of edge_function(v0, v1, v2), triangles of both windings are accepted.
+fn compute_barycentric_2d(p: Vector2d<f32>, v: &[Vector3d<f32>; 3], area: f32) -> Option<(f32, f32, f32)> {
+    let (a, b, c) = (v[0].head2(), v[1].head2(), v[2].head2());
+    // Flip clockwise triangles so that the covered region is always on the left of every edge
+    let sign = if area < 0.0_f32 { -1.0_f32 } else { 1.0_f32 };
+    let w0 = edge_function(b, c, p) * sign;
+    let w1 = edge_function(c, a, p) * sign;
+    let w2 = edge_function(a, b, p) * sign;
+
+    let covered = |w: f32, from: Vector2d<f32>, to: Vector2d<f32>| {
+        if sign > 0.0_f32 {
+            w > 0.0_f32 || (w == 0.0_f32 && is_top_left(from, to))
+        } else {
+            w > 0.0_f32 || (w == 0.0_f32 && is_top_left(to, from))
+        }
+    };
+    if !covered(w0, b, c) || !covered(w1, c, a) || !covered(w2, a, b) {
+        return None;
+    }
+
+    let area = area * sign;
+    Some((w0 / area, w1 / area, w2 / area))
 }
 
 impl BasicRasterizer for Rasterizer {
     fn view_matrix(eye_pos: Vector3d<f32>) -> Matrix4d<f32> {
-        Matrix4d::new(
-            Vector4d::new(1.0_f32, 0.0_f32, 0.0_f32, eye_pos.x * -1.0_f32),
-            Vector4d::new(0.0_f32, 1.0_f32, 0.0_f32, eye_pos.y * -1.0_f32),
-            Vector4d::new(0.0_f32, 0.0_f32, 1.0_f32, eye_pos.z * -1.0_f32),
-            Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32),
-        ) * Matrix4d::identity(1.0_f32)
+        transform::translate(Vector3d::mul_item(eye_pos, -1.0_f32))
     }
 
     fn model_matrix(norm: Vector3d<f32>, angle: f32) -> Matrix4d<f32> {
-        let r_angle = angle / 180.0_f32 * PI;
-        let r_model = Matrix3d::new(
-            Vector3d::new(0.0_f32, -1.0_f32 * norm.z, norm.y),
-            Vector3d::new(norm.z, 0.0_f32, -1.0_f32 * norm.x),
-            Vector3d::new(-1.0_f32 * norm.y, norm.x, 0.0_f32),
-        );
-        let rod = r_model.mul_item((1.0_f32 - f32::cos(r_angle)) * norm.dot(norm) + f32::sin(r_angle))
-            .add(Matrix3d::identity(1.0_f32).mul_item(f32::cos(r_angle)));
-
-        Matrix4d::new(
-            rod.items[0].to_vector4d(0.0_f32),
-            rod.items[1].to_vector4d(0.0_f32),
-            rod.items[2].to_vector4d(0.0_f32),
-            Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32)
-        )
+        transform::rotate(norm, angle)
     }
 
     fn projection_matrix(eye_fov: f32, aspect_ratio: f32, z_near: f32, z_far: f32) -> Matrix4d<f32> {
-        let t = f32::tan(eye_fov / 180.0_f32 * f32::acos(-1.0_f32) / 2.0_f32) * z_near;
-        let b = -t;
-        let r = aspect_ratio * t;
-        let l = -r;
-
-        let persp = Matrix4d::new(
-            Vector4d::new(z_near, 0.0_f32, 0.0_f32, 0.0_f32),
-            Vector4d::new(0.0_f32, z_near, 0.0_f32, 0.0_f32),
-            Vector4d::new(0.0_f32, 0.0_f32, z_near + z_far, -1.0_f32 * z_near * z_far),
-            Vector4d::new(0.0_f32, 0.0_f32, 1.0_f32, 0.0_f32),
-        );
-
-        let trans = Matrix4d::new(
-            Vector4d::new(2.0_f32 / (r - l), 0.0_f32, 0.0_f32, 0.0_f32),
-            Vector4d::new(0.0_f32, 2.0_f32 / (t - b), 0.0_f32, 0.0_f32),
-            Vector4d::new(0.0_f32, 0.0_f32, 2.0_f32/(z_near-z_far), 0.0_f32),
-            Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32),
-        );
-
-        let scale = Matrix4d::new(
-            Vector4d::new(1.0_f32, 0.0_f32, 0.0_f32, -1.0_f32 * (r + l) / 2.0_f32),
-            Vector4d::new(0.0_f32, 1.0_f32, 0.0_f32, -1.0_f32 * (t + b) / 2.0_f32),
-            Vector4d::new(0.0_f32, 0.0_f32, 1.0_f32, -1.0_f32 * (z_near + z_far) / 2.0_f32),
-            Vector4d::new(0.0_f32, 0.0_f32, 0.0_f32, 1.0_f32),
-        );
-
-        trans * scale * persp
+        transform::perspective(eye_fov, aspect_ratio, z_near, z_far, DepthRange::NegativeOneToOne)
     }
 }
\ No newline at end of file
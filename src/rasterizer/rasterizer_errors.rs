@@ -0,0 +1,29 @@
+use std::{error, fmt};
+
+/// Error raised by the buffers and draw calls of the rasterizer, message holds the handle or index at fault
+#[derive(Debug)]
+pub struct RasterizerError {
+    pub err_code: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RasterizerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let err_msg = match self.err_code {
+            5001 => "Unknown position buffer",
+            5002 => "Unknown index buffer",
+            5003 => "Unknown attribute buffer",
+            5004 => "Index out of range",
+            5005 => "Attribute buffer shorter than the position buffer",
+            5006 => "Failed to write the file",
+            5007 => "Unsupported image format",
+            5008 => "Color count differs from the image size",
+            5009 => "Image sizes differ",
+            _ => "Sorry, something is wrong! Please Try Again!",
+        };
+
+        write!(f, "{}: {}", err_msg, self.message)
+    }
+}
+
+impl error::Error for RasterizerError {}
@@ -0,0 +1,206 @@
+use crate::matrix::matrix3d::Matrix3d;
+use crate::matrix::matrix4d::Matrix4d;
+use crate::matrix::vector2d::Vector2d;
+use crate::matrix::vector3d::Vector3d;
+use crate::matrix::vector4d::Vector4d;
+
+/// Attributes of a single vertex read from the rasterizer buffers, the input of a vertex shader
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VertexPayload {
+    pub position:   Vector3d<f32>,
+    pub normal:     Vector3d<f32>,
+    pub tex_coords: Vector2d<f32>,
+    /// RGB in [0, 1]
+    pub color:      Vector3d<f32>,
+}
+
+/// The model, view and projection matrices of the rasterizer, shared by every vertex of a draw call
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transforms {
+    pub model:      Matrix4d<f32>,
+    pub view:       Matrix4d<f32>,
+    pub projection: Matrix4d<f32>,
+    /// Inverse-transpose of the upper 3x3 of model, keeps normals perpendicular to the surface under non-uniform scaling
+    pub normal:     Matrix3d<f32>,
+}
+
+impl Transforms {
+    /// Bundle the matrices of a draw call, the normal matrix is derived once here rather than for every vertex
+    pub fn new(model: Matrix4d<f32>, view: Matrix4d<f32>, projection: Matrix4d<f32>) -> Transforms {
+        Transforms { model, view, projection, normal: normal_matrix(&model) }
+    }
+}
+
+/// A singular model flattens the geometry and has no inverse, its upper 3x3 is used as is
+fn normal_matrix(model: &Matrix4d<f32>) -> Matrix3d<f32> {
+    let m = &model.items;
+    let upper = Matrix3d::new(m[0].head3(), m[1].head3(), m[2].head3());
+    match upper.inverse() {
+        Ok(mut normal) => {
+            normal.transpose();
+            normal
+        }
+        Err(_) => upper,
+    }
+}
+
+/// Values written by the vertex shader and interpolated across the triangle for every fragment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Varyings {
+    /// RGB in [0, 1]
+    pub color:      Vector3d<f32>,
+    pub normal:     Vector3d<f32>,
+    pub tex_coords: Vector2d<f32>,
+    pub world_pos:  Vector3d<f32>,
+}
+
+impl Varyings {
+    /// Blend the varyings of the three vertices of a triangle with the weights (alpha, beta, gamma)
+    #[inline]
+    pub fn interpolate(v: &[Varyings; 3], alpha: f32, beta: f32, gamma: f32) -> Varyings {
+        Varyings {
+            color: Vector3d::mul_item(v[0].color, alpha)
+                .add(Vector3d::mul_item(v[1].color, beta))
+                .add(Vector3d::mul_item(v[2].color, gamma)),
+            normal: Vector3d::mul_item(v[0].normal, alpha)
+                .add(Vector3d::mul_item(v[1].normal, beta))
+                .add(Vector3d::mul_item(v[2].normal, gamma)),
+            tex_coords: Vector2d::mul_item(v[0].tex_coords, alpha)
+                .add(Vector2d::mul_item(v[1].tex_coords, beta))
+                .add(Vector2d::mul_item(v[2].tex_coords, gamma)),
+            world_pos: Vector3d::mul_item(v[0].world_pos, alpha)
+                .add(Vector3d::mul_item(v[1].world_pos, beta))
+                .add(Vector3d::mul_item(v[2].world_pos, gamma)),
+        }
+    }
+
+    /// Blend the varyings of the two ends of an edge, t = 0 gives a and t = 1 gives b
+    #[inline]
+    pub fn lerp(a: &Varyings, b: &Varyings, t: f32) -> Varyings {
+        Varyings::interpolate(&[*a, *b, *b], 1.0_f32 - t, t, 0.0_f32)
+    }
+}
+
+/// The output of a vertex shader
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VertexOutput {
+    /// Homogeneous clip space position, the rasterizer divides it by w
+    pub position: Vector4d<f32>,
+    pub varyings: Varyings,
+}
+
+/// A fragment produced by the rasterizer, the input of a fragment shader
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FragmentPayload {
+    /// Pixel center in screen space, z is the interpolated depth
+    pub screen_pos: Vector3d<f32>,
+    pub varyings:   Varyings,
+}
+
+/** Programmable vertex stage, shared by the threads of a tiled rasterizer like FragmentShader, Example
+```rust
+use QRender::matrix::vector3d::Vector3d;
+use QRender::rasterizer::shader::{Transforms, VertexOutput, VertexPayload, VertexShader, Varyings};
+
+/// Move every vertex along its normal before projecting it
+struct InflateShader {
+    amount: f32,
+}
+
+impl VertexShader for InflateShader {
+    fn shade(&self, vertex: &VertexPayload, transforms: &Transforms) -> VertexOutput {
+        let position = vertex.position.add(Vector3d::mul_item(vertex.normal, self.amount));
+        let world_pos = transforms.model.product_with_vector4d(position.to_vector4d(1.0));
+        VertexOutput {
+            position: (transforms.projection * transforms.view).product_with_vector4d(world_pos),
+            varyings: Varyings {
+                color: vertex.color,
+                normal: vertex.normal,
+                tex_coords: vertex.tex_coords,
+                world_pos: world_pos.head3(),
+            },
+        }
+    }
+}
+```
+ */
+pub trait VertexShader: Send + Sync {
+    fn shade(&self, vertex: &VertexPayload, transforms: &Transforms) -> VertexOutput;
+}
+
+/// Programmable fragment stage, returns the RGB color of the fragment in [0, 1].
+/// Shaders are shared by the threads of a tiled rasterizer, hence Send + Sync
+pub trait FragmentShader: Send + Sync {
+    fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32>;
+
+    /// RGBA color of the fragment in [0, 1] used by the rasterizer, override it to return a transparent color, opaque by default
+    fn shade_rgba(&self, fragment: &FragmentPayload) -> Vector4d<f32> {
+        self.shade(fragment).to_vector4d(1.0_f32)
+    }
+}
+
+/// Transform positions with projection * view * model and pass the attributes through,
+/// normals are brought into world space with the normal matrix of the transforms
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultVertexShader;
+
+impl VertexShader for DefaultVertexShader {
+    fn shade(&self, vertex: &VertexPayload, transforms: &Transforms) -> VertexOutput {
+        let world_pos = transforms.model.product_with_vector4d(vertex.position.to_vector4d(1.0_f32));
+        let normal = transforms.normal.product_with_vector3d(vertex.normal);
+        VertexOutput {
+            position: (transforms.projection * transforms.view).product_with_vector4d(world_pos),
+            varyings: Varyings {
+                color: vertex.color,
+                normal,
+                tex_coords: vertex.tex_coords,
+                world_pos: world_pos.head3(),
+            },
+        }
+    }
+}
+
+/// Output the interpolated vertex color
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ColorFragmentShader;
+
+impl FragmentShader for ColorFragmentShader {
+    fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+        fragment.varyings.color
+    }
+}
+
+/// Visualize the interpolated normal, each component is mapped from [-1, 1] to [0, 1]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalFragmentShader;
+
+impl FragmentShader for NormalFragmentShader {
+    fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+        let n = fragment.varyings.normal.normalize();
+        Vector3d::mul_item(Vector3d::add_item(n, 1.0_f32), 0.5_f32)
+    }
+}
+
+/// Scale the alpha of another fragment shader by opacity, draw it with a blend state like BlendState::alpha
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpacityFragmentShader<S: FragmentShader> {
+    pub shader:  S,
+    pub opacity: f32,
+}
+
+impl<S: FragmentShader> OpacityFragmentShader<S> {
+    pub fn new(shader: S, opacity: f32) -> OpacityFragmentShader<S> {
+        OpacityFragmentShader { shader, opacity }
+    }
+}
+
+impl<S: FragmentShader> FragmentShader for OpacityFragmentShader<S> {
+    fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+        self.shader.shade(fragment)
+    }
+
+    fn shade_rgba(&self, fragment: &FragmentPayload) -> Vector4d<f32> {
+        let color = self.shader.shade_rgba(fragment);
+        Vector4d::new(color.x, color.y, color.z, color.w * self.opacity)
+    }
+}
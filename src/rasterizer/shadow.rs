@@ -0,0 +1,164 @@
+use crate::matrix::matrix4d::Matrix4d;
+use crate::matrix::transform::{self, DepthRange};
+use crate::matrix::vector3d::Vector3d;
+use crate::rasterizer::light::Light;
+use crate::rasterizer::rasterizer::{viewport_transform, Rasterizer};
+
+/** The depth of the scene seen from a light, rendered by a depth only pass of a Rasterizer
+  A world space point is in shadow when it lies behind the depth stored at its position in the map.
+  bias plus slope_bias * tan of the angle between the surface normal and the light is subtracted from the depth of the point before the comparison,
+  in the screen depth units [0.1, 100], to avoid self shadowing of lit surfaces, which grows with the slope of the surface seen from the light,
+  and pcf_radius is the radius in texels of the square of comparisons that are averaged to soften the edges of shadows. Example
+```rust
+use QRender::matrix::vector3d::Vector3d;
+use QRender::rasterizer::light::Light;
+use QRender::rasterizer::shadow::ShadowMap;
+
+let sun = Light::directional(Vector3d::new(0.0, -1.0, 0.0), Vector3d::fill(1.0));
+// A rasterizer looking down from the sun at a scene of radius 5 around the origin
+let mut pass = ShadowMap::depth_pass(&sun, Vector3d::fill(0.0), 5.0, 64).unwrap();
+// A square roof at y = 1 over the floor
+let pos_id = pass.load_positions(vec![
+    Vector3d::new(-1.0, 1.0, -1.0),
+    Vector3d::new(1.0, 1.0, -1.0),
+    Vector3d::new(1.0, 1.0, 1.0),
+    Vector3d::new(-1.0, 1.0, 1.0),
+]);
+let ind_id = pass.load_indices(vec![Vector3d::new(0, 1, 2), Vector3d::new(0, 2, 3)]);
+pass.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+
+let shadow = ShadowMap::from_depth_pass(&pass);
+// The floor faces the light, n.l = 1
+let under = shadow.visibility(Vector3d::new(0.0, 0.0, 0.0), 1.0);
+let beside = shadow.visibility(Vector3d::new(3.0, 0.0, 0.0), 1.0);
+```
+
+output under and beside:
+```text
+0
+1
+```
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowMap {
+    pub width:           usize,
+    pub height:          usize,
+    /// Depths stored like Rasterizer::depth_buf, infinite where nothing was drawn
+    pub depth:           Vec<f32>,
+    /// projection * view of the light
+    pub view_projection: Matrix4d<f32>,
+    pub depth_range:     DepthRange,
+    pub bias:            f32,
+    pub slope_bias:      f32,
+    pub pcf_radius:      usize,
+}
+
+impl ShadowMap {
+    /** The view and projection matrices of a light covering the sphere of radius around center
+      Directional lights use an orthographic projection along their direction and spot lights a perspective projection spanning their cone.
+      Point lights shine in every direction and return None.
+     */
+    pub fn light_transforms(light: &Light, center: Vector3d<f32>, radius: f32, depth: DepthRange) -> Option<(Matrix4d<f32>, Matrix4d<f32>)> {
+        match *light {
+            Light::Directional { direction, .. } => {
+                let d = direction.normalize();
+                let eye = center.sub(Vector3d::mul_item(d, 2.0_f32 * radius));
+                let view = transform::look_at(eye, center, up_vector(d));
+                let projection = transform::ortho(-radius, radius, -radius, radius, radius, 3.0_f32 * radius, depth);
+                Some((view, projection))
+            }
+            Light::Spot { position, direction, outer_angle, .. } => {
+                let d = direction.normalize();
+                let view = transform::look_at(position, position.add(d), up_vector(d));
+                let distance = position.sub(center).norm();
+                let far = distance + radius;
+                // Keep the near plane as far as possible, the precision of the depth falls off quickly behind it
+                let near = (distance - radius).max(far * 0.01_f32);
+                let fov = (2.0_f32 * outer_angle).min(170.0_f32);
+                let projection = transform::perspective(fov, 1.0_f32, near, far, depth);
+                Some((view, projection))
+            }
+            Light::Point { .. } => None,
+        }
+    }
+
+    /// A depth only rasterizer of size x size pixels looking from the light at the sphere of radius around center, None for point lights.
+    /// Load and draw the shadow casters into it, then build the shadow map with from_depth_pass.
+    pub fn depth_pass(light: &Light, center: Vector3d<f32>, radius: f32, size: usize) -> Option<Rasterizer> {
+        let (view, projection) = ShadowMap::light_transforms(light, center, radius, DepthRange::NegativeOneToOne)?;
+        let mut raster = Rasterizer::new(size, size);
+        raster.set_model(Matrix4d::identity(1.0_f32));
+        raster.set_view(view);
+        raster.set_projection(projection);
+        raster.set_depth_only(true);
+        Some(raster)
+    }
+
+    /// Take the depth buffer and the view and projection matrices of a rasterizer which rendered the scene from a light
+    pub fn from_depth_pass(raster: &Rasterizer) -> ShadowMap {
+        ShadowMap {
+            width: raster.width,
+            height: raster.height,
+            depth: raster.depth_buf.clone(),
+            view_projection: raster.projection * raster.view,
+            depth_range: raster.depth_range(),
+            bias: 0.2_f32,
+            slope_bias: 1.0_f32,
+            pcf_radius: 1,
+        }
+    }
+
+    pub fn set_bias(&mut self, bias: f32, slope_bias: f32) {
+        self.bias = bias;
+        self.slope_bias = slope_bias;
+    }
+
+    pub fn set_pcf_radius(&mut self, pcf_radius: usize) {
+        self.pcf_radius = pcf_radius;
+    }
+
+    /// The stored depth of texel (x, y), y points upwards like the screen space of the rasterizer
+    pub fn depth_at(&self, x: usize, y: usize) -> f32 {
+        self.depth[(self.height - 1 - y) * self.width + x]
+    }
+
+    /// The fraction of the light reaching a world space point, 0 in full shadow and 1 when lit.
+    /// n_dot_l is the cosine of the angle between the surface normal and the direction towards the light. Points outside of the map are lit.
+    pub fn visibility(&self, world_pos: Vector3d<f32>, n_dot_l: f32) -> f32 {
+        let clip = self.view_projection.product_with_vector4d(world_pos.to_vector4d(1.0_f32));
+        if clip.w <= 0.0_f32 {
+            return 1.0_f32;
+        }
+        let p = viewport_transform(clip, self.width, self.height, self.depth_range);
+        if p.x < 0.0_f32 || p.y < 0.0_f32 || p.x >= self.width as f32 || p.y >= self.height as f32 {
+            return 1.0_f32;
+        }
+
+        let cos = n_dot_l.clamp(1e-3_f32, 1.0_f32);
+        let tan = ((1.0_f32 - cos * cos).sqrt() / cos).min(10.0_f32);
+        let depth = p.z - self.bias - self.slope_bias * tan;
+
+        let (x, y) = (p.x as isize, p.y as isize);
+        let r = self.pcf_radius as isize;
+        let mut lit = 0;
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let tx = (x + dx).clamp(0, self.width as isize - 1) as usize;
+                let ty = (y + dy).clamp(0, self.height as isize - 1) as usize;
+                if depth <= self.depth_at(tx, ty) {
+                    lit += 1;
+                }
+            }
+        }
+        lit as f32 / ((2 * r + 1) * (2 * r + 1)) as f32
+    }
+}
+
+/// An up vector for look_at which is not parallel to the viewing direction d
+fn up_vector(d: Vector3d<f32>) -> Vector3d<f32> {
+    if d.y.abs() > 0.99_f32 {
+        Vector3d::new(0.0_f32, 0.0_f32, 1.0_f32)
+    } else {
+        Vector3d::new(0.0_f32, 1.0_f32, 0.0_f32)
+    }
+}
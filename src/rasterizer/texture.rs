@@ -0,0 +1,174 @@
+use std::path::Path;
+use image::error::{ParameterError, ParameterErrorKind};
+use image::{DynamicImage, ImageError};
+use crate::matrix::vector2d::Vector2d;
+use crate::matrix::vector3d::Vector3d;
+use crate::matrix::vector4d::Vector4d;
+use crate::rasterizer::shader::{FragmentPayload, FragmentShader};
+
+/// How a texture is read between texel centers
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FilterMode {
+    /// The texel containing the sample point
+    Nearest,
+    /// Blend the four closest texels by their distance to the sample point
+    Bilinear,
+}
+
+/// How texture coordinates outside [0, 1] are mapped back onto the texture
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum WrapMode {
+    /// Tile the texture
+    Repeat,
+    /// Repeat the texels of the border
+    ClampToEdge,
+    /// Tile the texture, flipping every other tile
+    MirroredRepeat,
+}
+
+/** An RGBA image sampled with normalized texture coordinates
+  u grows to the right and v grows upwards, (0, 0) is the bottom left corner of the image and (1, 1) the top right one.
+  Colors are in [0, 1].
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Texture {
+    pub width:  usize,
+    pub height: usize,
+    pub filter: FilterMode,
+    pub wrap:   WrapMode,
+    /// Row by row from the top of the image
+    texels:     Vec<Vector4d<f32>>,
+}
+
+impl Texture {
+    /// Convert an image of any color type, the texture defaults to bilinear filtering and repeat wrapping.
+    /// An image without pixels has nothing to sample and returns a dimension error
+    pub fn from_image(image: &DynamicImage) -> Result<Texture, ImageError> {
+        if image.width() == 0 || image.height() == 0 {
+            return Err(ImageError::Parameter(ParameterError::from_kind(ParameterErrorKind::DimensionMismatch)));
+        }
+        let rgba = image.to_rgba32f();
+        let texels = rgba.pixels().map(|p| Vector4d::new(p[0], p[1], p[2], p[3])).collect();
+        Ok(Texture {
+            width: rgba.width() as usize,
+            height: rgba.height() as usize,
+            filter: FilterMode::Bilinear,
+            wrap: WrapMode::Repeat,
+            texels,
+        })
+    }
+
+    /// Read an image file, any format supported by the image crate can be used
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Texture, ImageError> {
+        Texture::from_image(&image::open(path)?)
+    }
+
+    pub fn set_filter(&mut self, filter: FilterMode) {
+        self.filter = filter;
+    }
+
+    pub fn set_wrap(&mut self, wrap: WrapMode) {
+        self.wrap = wrap;
+    }
+
+    /// The texel in column x and row y, row 0 is the top of the image
+    pub fn texel(&self, x: usize, y: usize) -> Vector4d<f32> {
+        self.texels[y * self.width + x]
+    }
+
+    /// RGB color at uv
+    pub fn sample(&self, uv: Vector2d<f32>) -> Vector3d<f32> {
+        self.sample_rgba(uv).head3()
+    }
+
+    /** RGBA color at uv, a NaN or infinite coordinate returns transparent black, Example
+    ```rust
+    use image::{DynamicImage, Rgb, RgbImage};
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::rasterizer::texture::{FilterMode, Texture};
+
+    // Black on the left, white on the right
+    let image = RgbImage::from_fn(2, 1, |x, _| if x == 0 { Rgb([0, 0, 0]) } else { Rgb([255, 255, 255]) });
+    let mut texture = Texture::from_image(&DynamicImage::ImageRgb8(image)).unwrap();
+    let smooth = texture.sample_rgba(Vector2d::new(0.5, 0.5));
+    texture.set_filter(FilterMode::Nearest);
+    let sharp = texture.sample_rgba(Vector2d::new(0.5, 0.5));
+    ```
+
+    output smooth and sharp:
+    ```text
+    [0.5 0.5 0.5 1.0]
+    [1.0 1.0 1.0 1.0]
+    ```
+     */
+    pub fn sample_rgba(&self, uv: Vector2d<f32>) -> Vector4d<f32> {
+        if !uv.x.is_finite() || !uv.y.is_finite() {
+            return Vector4d::fill(0.0_f32);
+        }
+        // Continuous texel coordinates, texel centers lie at half integers
+        let x = uv.x * self.width as f32;
+        let y = (1.0_f32 - uv.y) * self.height as f32;
+
+        match self.filter {
+            FilterMode::Nearest => {
+                let col = wrap(x.floor() as i64, self.width, self.wrap);
+                let row = wrap(y.floor() as i64, self.height, self.wrap);
+                self.texel(col, row)
+            }
+            FilterMode::Bilinear => {
+                let x = x - 0.5_f32;
+                let y = y - 0.5_f32;
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+                // Coordinates too large for i64 saturate, the neighbor must not overflow
+                let (c0, c1) = (wrap(x0, self.width, self.wrap), wrap(x0.saturating_add(1), self.width, self.wrap));
+                let (r0, r1) = (wrap(y0, self.height, self.wrap), wrap(y0.saturating_add(1), self.height, self.wrap));
+
+                let top = lerp(self.texel(c0, r0), self.texel(c1, r0), tx);
+                let bottom = lerp(self.texel(c0, r1), self.texel(c1, r1), tx);
+                lerp(top, bottom, ty)
+            }
+        }
+    }
+}
+
+fn lerp(a: Vector4d<f32>, b: Vector4d<f32>, t: f32) -> Vector4d<f32> {
+    Vector4d::mul_item(a, 1.0_f32 - t).add(Vector4d::mul_item(b, t))
+}
+
+/// Map the texel index i onto [0, size)
+fn wrap(i: i64, size: usize, mode: WrapMode) -> usize {
+    let n = size as i64;
+    let i = match mode {
+        WrapMode::Repeat => i.rem_euclid(n),
+        WrapMode::ClampToEdge => i.clamp(0, n - 1),
+        WrapMode::MirroredRepeat => {
+            let m = i.rem_euclid(2 * n);
+            if m < n { m } else { 2 * n - 1 - m }
+        }
+    };
+    i as usize
+}
+
+/// Output the texture color and alpha at the interpolated texture coordinates
+#[derive(Debug, Clone)]
+pub struct TextureFragmentShader {
+    pub texture: Texture,
+}
+
+impl TextureFragmentShader {
+    pub fn new(texture: Texture) -> TextureFragmentShader {
+        TextureFragmentShader { texture }
+    }
+}
+
+impl FragmentShader for TextureFragmentShader {
+    fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+        self.texture.sample(fragment.varyings.tex_coords)
+    }
+
+    fn shade_rgba(&self, fragment: &FragmentPayload) -> Vector4d<f32> {
+        self.texture.sample_rgba(fragment.varyings.tex_coords)
+    }
+}
@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use QRender::geometry::triangle::Triangle;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::{AntiAliasing, Rasterizer};
+    use QRender::rasterizer::shader::{FragmentPayload, FragmentShader};
+
+    fn screen_triangle(a: (f32, f32), b: (f32, f32), c: (f32, f32), z: f32, color: (f32, f32, f32)) -> Triangle {
+        let mut t = Triangle::from_vertex([
+            Vector3d::new(a.0, a.1, z),
+            Vector3d::new(b.0, b.1, z),
+            Vector3d::new(c.0, c.1, z),
+        ]);
+        for i in 0..3 {
+            t.set_color(i, color.0, color.1, color.2).unwrap();
+        }
+        t
+    }
+
+    /// Count the fragment shader invocations
+    struct CountingShader {
+        count: Arc<AtomicUsize>,
+    }
+
+    impl FragmentShader for CountingShader {
+        fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+            self.count.fetch_add(1, Ordering::Relaxed);
+            fragment.varyings.color
+        }
+    }
+
+    fn is_partial(c: Vector3d<f32>) -> bool {
+        c.x > 0.0 && c.x < 255.0
+    }
+
+    #[test]
+    fn antialiasing_sample_patterns() {
+        let modes = [
+            (AntiAliasing::None, 1), (AntiAliasing::Msaa2, 2), (AntiAliasing::Msaa4, 4), (AntiAliasing::Msaa8, 8),
+            (AntiAliasing::Ssaa2, 2), (AntiAliasing::Ssaa4, 4), (AntiAliasing::Ssaa8, 8),
+        ];
+        for (mode, n) in modes.iter() {
+            assert_eq!(mode.sample_count(), *n);
+            for (dx, dy) in mode.sample_offsets().iter() {
+                assert!(dx.abs() < 0.5 && dy.abs() < 0.5);
+            }
+        }
+        assert!(AntiAliasing::Ssaa4.is_supersampling());
+        assert!(!AntiAliasing::Msaa4.is_supersampling());
+    }
+
+    #[test]
+    fn antialiasing_smooths_edges() {
+        let t = screen_triangle((0.0, 0.0), (16.0, 0.0), (0.0, 13.0), 1.0, (255.0, 0.0, 0.0));
+
+        let mut aliased = Rasterizer::new(16, 16);
+        aliased.rasterize_triangle(&t);
+        assert!(!aliased.frame_buf.iter().any(|c| is_partial(*c)));
+
+        for mode in [AntiAliasing::Msaa4, AntiAliasing::Msaa8, AntiAliasing::Ssaa4].iter() {
+            let mut raster = Rasterizer::new(16, 16);
+            raster.set_anti_aliasing(*mode);
+            raster.rasterize_triangle(&t);
+            let partial = raster.frame_buf.iter().filter(|c| is_partial(**c)).count();
+            assert!(partial >= 10, "{:?}: {} partial pixels", mode, partial);
+            // Pixels far from the edge are fully covered
+            assert_eq!(raster.frame_buf[raster.get_index(2, 2)], Vector3d::new(255.0, 0.0, 0.0));
+            assert_eq!(raster.frame_buf[raster.get_index(15, 15)], Vector3d::fill(0.0));
+            // The total intensity matches the area of the triangle
+            let total: f32 = raster.frame_buf.iter().map(|c| c.x / 255.0).sum();
+            assert!((total - 16.0 * 13.0 / 2.0).abs() < 6.0, "{:?}: {}", mode, total);
+        }
+    }
+
+    #[test]
+    fn antialiasing_shared_edge_has_no_seam() {
+        for mode in [AntiAliasing::Msaa2, AntiAliasing::Msaa4, AntiAliasing::Msaa8].iter() {
+            let mut raster = Rasterizer::new(8, 8);
+            raster.set_anti_aliasing(*mode);
+            raster.rasterize_triangle(&screen_triangle((0.0, 0.0), (8.0, 0.0), (8.0, 5.0), 1.0, (255.0, 0.0, 0.0)));
+            raster.rasterize_triangle(&screen_triangle((0.0, 0.0), (8.0, 5.0), (0.0, 8.0), 1.0, (255.0, 0.0, 0.0)));
+            raster.rasterize_triangle(&screen_triangle((0.0, 8.0), (8.0, 5.0), (8.0, 8.0), 1.0, (255.0, 0.0, 0.0)));
+            assert!(raster.frame_buf.iter().all(|c| (c.x - 255.0).abs() < 1e-3 && c.y == 0.0), "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn antialiasing_shading_rate() {
+        let t = screen_triangle((0.0, 0.0), (16.0, 0.0), (0.0, 16.0), 1.0, (255.0, 255.0, 255.0));
+        let invocations = |mode: AntiAliasing| {
+            let count = Arc::new(AtomicUsize::new(0));
+            let mut raster = Rasterizer::new(16, 16);
+            raster.set_anti_aliasing(mode);
+            raster.set_fragment_shader(CountingShader { count: count.clone() });
+            raster.rasterize_triangle(&t);
+            let touched = raster.depth_buf.iter().filter(|d| d.is_finite()).count();
+            (count.load(Ordering::Relaxed), touched)
+        };
+
+        let (msaa, touched) = invocations(AntiAliasing::Msaa4);
+        assert_eq!(msaa, touched);
+        let (ssaa, touched) = invocations(AntiAliasing::Ssaa4);
+        assert!(ssaa > touched * 3, "{} invocations for {} pixels", ssaa, touched);
+    }
+
+    #[test]
+    fn antialiasing_per_sample_depth() {
+        let mut raster = Rasterizer::new(16, 16);
+        raster.set_anti_aliasing(AntiAliasing::Msaa4);
+        // A far blue square and a near red triangle covering half of it
+        raster.rasterize_triangle(&screen_triangle((0.0, 0.0), (16.0, 0.0), (16.0, 16.0), 5.0, (0.0, 0.0, 255.0)));
+        raster.rasterize_triangle(&screen_triangle((0.0, 0.0), (16.0, 16.0), (0.0, 16.0), 5.0, (0.0, 0.0, 255.0)));
+        raster.rasterize_triangle(&screen_triangle((0.0, 0.0), (16.0, 0.0), (0.0, 16.0), 1.0, (255.0, 0.0, 0.0)));
+        // Drawing the far square again changes nothing
+        raster.rasterize_triangle(&screen_triangle((0.0, 0.0), (16.0, 0.0), (16.0, 16.0), 5.0, (0.0, 0.0, 255.0)));
+
+        assert_eq!(raster.frame_buf[raster.get_index(2, 2)], Vector3d::new(255.0, 0.0, 0.0));
+        assert_eq!(raster.frame_buf[raster.get_index(14, 14)], Vector3d::new(0.0, 0.0, 255.0));
+        // Pixels on the edge of the red triangle mix both colors and keep the nearest depth
+        let edge = raster.frame_buf[raster.get_index(8, 7)];
+        assert!(is_partial(edge) && edge.z > 0.0 && (edge.x + edge.z - 255.0).abs() < 1e-3, "{}", edge);
+        assert_eq!(raster.depth_buf[raster.get_index(8, 7)], 1.0);
+    }
+
+    #[test]
+    fn antialiasing_switch_and_clear() {
+        let mut raster = Rasterizer::new(8, 8);
+        raster.rasterize_triangle(&screen_triangle((0.0, 0.0), (8.0, 0.0), (0.0, 8.0), 1.0, (255.0, 0.0, 0.0)));
+        let before = raster.frame_buf.clone();
+        raster.set_anti_aliasing(AntiAliasing::Msaa8);
+        assert_eq!(raster.frame_buf, before);
+
+        // The samples carry the previous content, so drawing behind it leaves the covered pixels untouched
+        // and only fills the samples of uncovered pixels along the edge
+        raster.rasterize_triangle(&screen_triangle((0.0, 0.0), (8.0, 0.0), (0.0, 8.0), 5.0, (0.0, 255.0, 0.0)));
+        for (after, before) in raster.frame_buf.iter().zip(before.iter()) {
+            if before.x > 0.0 {
+                assert_eq!(after, before);
+            }
+        }
+
+        raster.clear_buf();
+        assert!(raster.frame_buf.iter().all(|c| *c == Vector3d::fill(0.0)));
+        raster.rasterize_triangle(&screen_triangle((0.0, 0.0), (8.0, 0.0), (0.0, 8.0), 5.0, (0.0, 255.0, 0.0)));
+        assert_eq!(raster.frame_buf[raster.get_index(1, 1)], Vector3d::new(0.0, 255.0, 0.0));
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod tests {
+    use QRender::geometry::obj::ObjModel;
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::{IndexBufferId, PositionBufferId, Rasterizer};
+    use QRender::rasterizer::shader::{FragmentPayload, FragmentShader, NormalFragmentShader};
+
+    /// Show the interpolated texture coordinates in the red and green channels
+    struct TexCoordShader;
+
+    impl FragmentShader for TexCoordShader {
+        fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+            fragment.varyings.tex_coords.to_vector3d(0.0)
+        }
+    }
+
+    fn ndc_rasterizer(size: usize) -> Rasterizer {
+        let mut raster = Rasterizer::new(size, size);
+        raster.set_model(Matrix4d::identity(1.0));
+        raster.set_view(Matrix4d::identity(1.0));
+        raster.set_projection(Matrix4d::identity(1.0));
+        raster
+    }
+
+    /// A quad covering the whole screen, vertex i at the corner (i & 1, i >> 1)
+    fn quad(raster: &mut Rasterizer) -> (PositionBufferId, IndexBufferId) {
+        let pos_id = raster.load_positions(vec![
+            Vector3d::new(-1.0, -1.0, 0.0), Vector3d::new(1.0, -1.0, 0.0), Vector3d::new(-1.0, 1.0, 0.0), Vector3d::new(1.0, 1.0, 0.0),
+        ]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2), Vector3d::new(2, 1, 3)]);
+        (pos_id, ind_id)
+    }
+
+    fn assert_close(a: Vector3d<f32>, b: Vector3d<f32>) {
+        assert!(a.sub(b).norm() < 1e-3, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn attribute_uniform_color() {
+        // Without a color buffer every vertex takes the whole color
+        let mut raster = ndc_rasterizer(8);
+        let (pos_id, ind_id) = quad(&mut raster);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::new(10.0, 20.0, 30.0)).unwrap();
+        assert!(raster.frame_buf.iter().all(|c| c.sub(Vector3d::new(10.0, 20.0, 30.0)).norm() < 1e-3));
+    }
+
+    #[test]
+    fn attribute_colors() {
+        let mut raster = ndc_rasterizer(8);
+        let (pos_id, ind_id) = quad(&mut raster);
+        let color_id = raster.load_colors(vec![
+            Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(255.0, 0.0, 0.0), Vector3d::new(0.0, 255.0, 0.0), Vector3d::new(255.0, 255.0, 0.0),
+        ]);
+        raster.bind_attributes(pos_id, color_id).unwrap();
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+        // The colors follow the corners, red grows to the right and green upwards
+        assert_close(raster.frame_buf[raster.get_index(0, 0)], Vector3d::new(255.0 / 16.0, 255.0 / 16.0, 0.0));
+        assert_close(raster.frame_buf[raster.get_index(7, 0)], Vector3d::new(255.0 * 15.0 / 16.0, 255.0 / 16.0, 0.0));
+        assert_close(raster.frame_buf[raster.get_index(0, 7)], Vector3d::new(255.0 / 16.0, 255.0 * 15.0 / 16.0, 0.0));
+
+        // Unbinding goes back to the color argument
+        raster.unbind_attributes(pos_id).unwrap();
+        raster.clear_buf();
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+        assert!(raster.frame_buf.iter().all(|c| *c == Vector3d::fill(255.0)));
+    }
+
+    #[test]
+    fn attribute_normals_and_texcoords() {
+        let mut raster = ndc_rasterizer(8);
+        let (pos_id, ind_id) = quad(&mut raster);
+        let normal_id = raster.load_normals(vec![Vector3d::new(0.0, 0.0, 1.0); 4]);
+        let tex_id = raster.load_texcoords(vec![Vector2d::new(0.0, 0.0), Vector2d::new(1.0, 0.0), Vector2d::new(0.0, 1.0), Vector2d::new(1.0, 1.0)]);
+        raster.bind_attributes(pos_id, normal_id).unwrap();
+        raster.bind_attributes(pos_id, tex_id).unwrap();
+
+        raster.set_fragment_shader(NormalFragmentShader);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+        assert!(raster.frame_buf.iter().all(|c| c.sub(Vector3d::new(127.5, 127.5, 255.0)).norm() < 1e-3));
+
+        raster.clear_buf();
+        raster.set_fragment_shader(TexCoordShader);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+        assert_close(raster.frame_buf[raster.get_index(7, 7)], Vector3d::new(255.0 * 15.0 / 16.0, 255.0 * 15.0 / 16.0, 0.0));
+
+        // The triangles carry the bound attributes
+        let triangles = raster.triangles(pos_id, ind_id, Vector3d::new(255.0, 0.0, 0.0)).unwrap();
+        assert_eq!(triangles.len(), 2);
+        assert_eq!(triangles[1].vertex[2], Vector3d::new(1.0, 1.0, 0.0));
+        assert_eq!(triangles[1].tex_coords[2], Vector2d::new(1.0, 1.0));
+        assert_eq!(triangles[1].normal[0], Vector3d::new(0.0, 0.0, 1.0));
+        assert_eq!(triangles[1].color[0], Vector3d::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn attribute_errors() {
+        let mut raster = ndc_rasterizer(8);
+        let (pos_id, ind_id) = quad(&mut raster);
+        let short_id = raster.load_colors(vec![Vector3d::fill(255.0); 3]);
+        raster.bind_attributes(pos_id, short_id).unwrap();
+        assert_eq!(raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap_err().err_code, 5005);
+        assert!(raster.depth_buf.iter().all(|d| d.is_infinite()));
+
+        // Binding another buffer of the same kind replaces the short one
+        let color_id = raster.load_colors(vec![Vector3d::fill(255.0); 4]);
+        raster.bind_attributes(pos_id, color_id).unwrap();
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+
+        raster.remove_attributes(color_id).unwrap();
+        assert_eq!(raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap_err().err_code, 5003);
+        assert_eq!(raster.bind_attributes(pos_id, color_id).unwrap_err().err_code, 5003);
+        raster.remove_positions(pos_id).unwrap();
+        assert_eq!(raster.bind_attributes(pos_id, short_id).unwrap_err().err_code, 5001);
+        assert_eq!(raster.triangles(pos_id, ind_id, Vector3d::fill(0.0)).err().unwrap().err_code, 5001);
+    }
+
+    #[test]
+    fn attribute_obj_mesh() {
+        let model = ObjModel::parse("v -1 -1 0\nv 1 -1 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 0.5 1\nvn 0 0 1\nf 1/1/1 2/2/1 3/3/1\n").unwrap();
+        let mut raster = ndc_rasterizer(8);
+        let (pos_id, ind_id) = raster.load_obj_mesh(&model.meshes[0]);
+        let triangles = raster.triangles(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+        assert_eq!(triangles[0].normal, [Vector3d::new(0.0, 0.0, 1.0); 3]);
+        assert_eq!(triangles[0].tex_coords[2], Vector2d::new(0.5, 1.0));
+        assert_eq!(raster.buffer_count(), 4);
+    }
+}
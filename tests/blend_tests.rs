@@ -0,0 +1,227 @@
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, Rgba, RgbaImage};
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::matrix::vector4d::Vector4d;
+    use QRender::rasterizer::blend::{BlendFactor, BlendOp, BlendState};
+    use QRender::rasterizer::rasterizer::{AntiAliasing, CullMode, IndexBufferId, PositionBufferId, Rasterizer};
+    use QRender::rasterizer::shader::{ColorFragmentShader, FragmentPayload, FragmentShader, OpacityFragmentShader};
+    use QRender::rasterizer::texture::{FilterMode, Texture, TextureFragmentShader};
+
+    /// A single RGBA color for every fragment
+    struct ConstantShader(Vector4d<f32>);
+
+    impl FragmentShader for ConstantShader {
+        fn shade(&self, _fragment: &FragmentPayload) -> Vector3d<f32> {
+            self.0.head3()
+        }
+
+        fn shade_rgba(&self, _fragment: &FragmentPayload) -> Vector4d<f32> {
+            self.0
+        }
+    }
+
+    fn assert_close(a: Vector4d<f32>, b: Vector4d<f32>) {
+        assert!(a.sub(b).norm() < 1e-4, "{} != {}", a, b);
+    }
+
+    /// A rasterizer drawing directly in normalized device coordinates
+    fn ndc_rasterizer(size: usize) -> Rasterizer {
+        let mut raster = Rasterizer::new(size, size);
+        raster.set_model(Matrix4d::identity(1.0));
+        raster.set_view(Matrix4d::identity(1.0));
+        raster.set_projection(Matrix4d::identity(1.0));
+        raster
+    }
+
+    /// A triangle covering the whole screen at the depth z of normalized device coordinates
+    fn pane(raster: &mut Rasterizer, z: f32) -> (PositionBufferId, IndexBufferId) {
+        let pos_id = raster.load_positions(vec![Vector3d::new(-4.0, -4.0, z), Vector3d::new(4.0, -4.0, z), Vector3d::new(0.0, 4.0, z)]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+        (pos_id, ind_id)
+    }
+
+    #[test]
+    fn blend_presets() {
+        let src = Vector4d::new(1.0, 0.5, 0.0, 0.25);
+        let dst = Vector4d::new(0.0, 0.5, 1.0, 1.0);
+        assert_eq!(BlendState::replace().blend(src, dst), src);
+        assert_eq!(BlendState::default(), BlendState::replace());
+        assert_close(BlendState::alpha().blend(src, dst), Vector4d::new(0.25, 0.5, 0.75, 1.0));
+        assert_close(BlendState::additive().blend(src, dst), Vector4d::new(0.25, 0.625, 1.0, 1.0));
+        assert_close(BlendState::multiply().blend(src, dst), Vector4d::new(0.0, 0.25, 0.0, 1.0));
+
+        // Premultiplying a straight color gives the same result as straight alpha blending
+        let premultiplied = BlendState::premultiplied_alpha().with_premultiply(true);
+        assert_close(premultiplied.blend(src, dst), BlendState::alpha().blend(src, dst));
+        let src_pre = Vector4d::new(0.25, 0.125, 0.0, 0.25);
+        assert_close(BlendState::premultiplied_alpha().blend(src_pre, dst), BlendState::alpha().blend(src, dst));
+    }
+
+    #[test]
+    fn blend_ops() {
+        let src = Vector4d::new(0.75, 0.25, 0.5, 1.0);
+        let dst = Vector4d::new(0.5, 0.5, 0.5, 0.5);
+        let state = |op| BlendState::new(BlendFactor::One, BlendFactor::One, op);
+        assert_close(state(BlendOp::Add).blend(src, dst), Vector4d::new(1.0, 0.75, 1.0, 1.0));
+        assert_close(state(BlendOp::Subtract).blend(src, dst), Vector4d::new(0.25, 0.0, 0.0, 0.5));
+        assert_close(state(BlendOp::ReverseSubtract).blend(src, dst), Vector4d::new(0.0, 0.25, 0.0, 0.0));
+        // Min and Max ignore the factors
+        let zero = |op| BlendState::new(BlendFactor::Zero, BlendFactor::Zero, op);
+        assert_close(zero(BlendOp::Min).blend(src, dst), Vector4d::new(0.5, 0.25, 0.5, 0.5));
+        assert_close(zero(BlendOp::Max).blend(src, dst), Vector4d::new(0.75, 0.5, 0.5, 1.0));
+
+        let separate = BlendState::separate(
+            BlendFactor::DstAlpha, BlendFactor::OneMinusDstAlpha, BlendOp::Add,
+            BlendFactor::Zero, BlendFactor::One, BlendOp::Add,
+        );
+        assert_close(separate.blend(src, dst), Vector4d::new(0.625, 0.375, 0.5, 0.5));
+    }
+
+    #[test]
+    fn blend_set_pixel() {
+        let mut raster = Rasterizer::new(2, 2);
+        let p = Vector3d::new(0.0, 0.0, 0.0);
+        raster.set_pixel(p, Vector3d::new(255.0, 0.0, 0.0));
+        assert_eq!(raster.alpha_buf[raster.get_index(0, 0)], 255.0);
+
+        raster.set_blend_state(BlendState::alpha());
+        raster.set_pixel_rgba(p, Vector4d::new(0.0, 0.0, 255.0, 51.0));
+        let color = raster.frame_buf[raster.get_index(0, 0)];
+        assert!(color.sub(Vector3d::new(204.0, 0.0, 51.0)).norm() < 1e-3, "{}", color);
+        assert_eq!(raster.alpha_buf[raster.get_index(0, 0)], 255.0);
+
+        raster.clear_frame_buf();
+        assert!(raster.alpha_buf.iter().all(|a| *a == 0.0));
+    }
+
+    #[test]
+    fn blend_transparent_pass_sorts_back_to_front() {
+        let expected = Vector3d::new(127.5, 63.75, 0.0);
+        for near_first in [true, false].iter() {
+            let mut raster = ndc_rasterizer(4);
+            raster.set_blend_state(BlendState::alpha());
+            let (far_pos, far_ind) = pane(&mut raster, 0.5);
+            let (near_pos, near_ind) = pane(&mut raster, -0.5);
+            let draw_near = |r: &mut Rasterizer| {
+                r.set_fragment_shader(ConstantShader(Vector4d::new(1.0, 0.0, 0.0, 0.5)));
+                r.draw_transparent(near_pos, near_ind, Vector3d::fill(255.0)).unwrap();
+            };
+            let draw_far = |r: &mut Rasterizer| {
+                r.set_fragment_shader(ConstantShader(Vector4d::new(0.0, 1.0, 0.0, 0.5)));
+                r.draw_transparent(far_pos, far_ind, Vector3d::fill(255.0)).unwrap();
+            };
+            if *near_first {
+                draw_near(&mut raster);
+                draw_far(&mut raster);
+            } else {
+                draw_far(&mut raster);
+                draw_near(&mut raster);
+            }
+            // Nothing is drawn before the flush
+            assert!(raster.frame_buf.iter().all(|c| *c == Vector3d::fill(0.0)));
+            raster.flush_transparent();
+
+            // The near red pane covers half of the green pane behind it
+            for c in raster.frame_buf.iter() {
+                assert!(c.sub(expected).norm() < 1e-3, "{}", c);
+            }
+            assert!(raster.alpha_buf.iter().all(|a| (*a - 191.25).abs() < 1e-3));
+            // The transparent panes do not write depth
+            assert!(raster.depth_buf.iter().all(|d| d.is_infinite()));
+
+            // The queue is empty after a flush
+            raster.flush_transparent();
+            assert!(raster.frame_buf.iter().all(|c| c.sub(expected).norm() < 1e-3));
+        }
+    }
+
+    #[test]
+    fn blend_transparent_behind_opaque() {
+        let mut raster = ndc_rasterizer(4);
+        let (opaque_pos, opaque_ind) = pane(&mut raster, 0.0);
+        raster.set_fragment_shader(ConstantShader(Vector4d::new(0.0, 0.0, 1.0, 1.0)));
+        raster.draw_triangle(opaque_pos, opaque_ind, Vector3d::fill(255.0)).unwrap();
+
+        raster.set_blend_state(BlendState::alpha());
+        let (behind_pos, behind_ind) = pane(&mut raster, 0.5);
+        let (front_pos, front_ind) = pane(&mut raster, -0.5);
+        raster.set_fragment_shader(ConstantShader(Vector4d::new(1.0, 0.0, 0.0, 0.5)));
+        raster.draw_transparent(behind_pos, behind_ind, Vector3d::fill(255.0)).unwrap();
+        // The opacity shader scales the alpha of the shader it wraps
+        raster.set_fragment_shader(OpacityFragmentShader::new(ConstantShader(Vector4d::new(0.0, 1.0, 0.0, 1.0)), 0.5));
+        raster.draw_transparent(front_pos, front_ind, Vector3d::fill(255.0)).unwrap();
+        raster.flush_transparent();
+
+        // Only the pane in front of the opaque one shows
+        for c in raster.frame_buf.iter() {
+            assert!(c.sub(Vector3d::new(0.0, 127.5, 127.5)).norm() < 1e-3, "{}", c);
+        }
+    }
+
+    #[test]
+    fn blend_transparent_keeps_queued_state() {
+        // The pane is counter-clockwise on screen, so it is front facing
+        let mut raster = ndc_rasterizer(4);
+        let (pos_id, ind_id) = pane(&mut raster, 0.0);
+        raster.set_blend_state(BlendState::alpha());
+        raster.set_fragment_shader(ConstantShader(Vector4d::new(1.0, 0.0, 0.0, 0.5)));
+        raster.set_cull_mode(CullMode::Back);
+        raster.draw_transparent(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+        raster.set_cull_mode(CullMode::Front);
+        raster.draw_transparent(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+
+        // Changing the state before the flush affects neither queued pane
+        raster.set_cull_mode(CullMode::None);
+        raster.set_depth_only(true);
+        raster.flush_transparent();
+        for c in raster.frame_buf.iter() {
+            assert!(c.sub(Vector3d::new(127.5, 0.0, 0.0)).norm() < 1e-3, "{}", c);
+        }
+    }
+
+    #[test]
+    fn blend_transparent_tiled_and_multisampled() {
+        let render = |threads: usize| {
+            let mut raster = ndc_rasterizer(40);
+            raster.set_threads(threads, 8);
+            raster.set_anti_aliasing(AntiAliasing::Msaa4);
+            raster.set_blend_state(BlendState::alpha());
+            raster.set_fragment_shader(OpacityFragmentShader::new(ColorFragmentShader, 0.4));
+            for i in 0..6 {
+                let shift = i as f32 * 0.2 - 0.5;
+                let pos_id = raster.load_positions(vec![
+                    Vector3d::new(-0.8 + shift, -0.7, shift),
+                    Vector3d::new(0.6 + shift, -0.9, shift),
+                    Vector3d::new(shift, 0.8, shift),
+                ]);
+                let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+                raster.draw_transparent(pos_id, ind_id, Vector3d::new(255.0, 40.0 * i as f32, 0.0)).unwrap();
+            }
+            raster.flush_transparent();
+            raster
+        };
+        let (single, tiled) = (render(1), render(4));
+        assert!(single.frame_buf == tiled.frame_buf && single.alpha_buf == tiled.alpha_buf);
+        assert!(single.alpha_buf.iter().any(|a| *a > 0.0 && *a < 255.0));
+    }
+
+    #[test]
+    fn blend_texture_alpha() {
+        let mut image = RgbaImage::new(2, 1);
+        image.put_pixel(0, 0, Rgba([255, 255, 255, 0]));
+        image.put_pixel(1, 0, Rgba([255, 255, 255, 255]));
+        let mut texture = Texture::from_image(&DynamicImage::ImageRgba8(image)).unwrap();
+        texture.set_filter(FilterMode::Nearest);
+
+        let mut raster = ndc_rasterizer(4);
+        raster.set_blend_state(BlendState::alpha());
+        raster.set_fragment_shader(TextureFragmentShader::new(texture));
+        // Without texture coordinates every fragment samples the transparent left texel
+        let (pos_id, ind_id) = pane(&mut raster, 0.0);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+        assert!(raster.frame_buf.iter().all(|c| *c == Vector3d::fill(0.0)));
+        assert!(raster.alpha_buf.iter().all(|a| *a == 0.0));
+    }
+}
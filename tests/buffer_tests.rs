@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::{Rasterizer, VertexAttributes};
+
+    fn ndc_rasterizer(size: usize) -> Rasterizer {
+        let mut raster = Rasterizer::new(size, size);
+        raster.set_model(Matrix4d::identity(1.0));
+        raster.set_view(Matrix4d::identity(1.0));
+        raster.set_projection(Matrix4d::identity(1.0));
+        raster
+    }
+
+    fn covered(raster: &Rasterizer) -> usize {
+        raster.depth_buf.iter().filter(|d| d.is_finite()).count()
+    }
+
+    #[test]
+    fn buffer_update() {
+        let mut raster = ndc_rasterizer(8);
+        let pos_id = raster.load_positions(vec![Vector3d::new(-1.0, -1.0, 0.0), Vector3d::new(1.0, -1.0, 0.0), Vector3d::new(-1.0, 1.0, 0.0)]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+        let half = covered(&raster);
+        assert!(half > 0 && half < 64);
+
+        // The same handles draw the new content
+        raster.clear_buf();
+        raster.update_positions(pos_id, vec![
+            Vector3d::new(-1.0, -1.0, 0.0), Vector3d::new(1.0, -1.0, 0.0), Vector3d::new(-1.0, 1.0, 0.0), Vector3d::new(1.0, 1.0, 0.0),
+        ]).unwrap();
+        raster.update_indices(ind_id, vec![Vector3d::new(0, 1, 2), Vector3d::new(2, 1, 3)]).unwrap();
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+        assert_eq!(covered(&raster), 64);
+        assert_eq!(raster.indices(ind_id).unwrap(), &vec![0, 1, 2, 2, 1, 3]);
+
+        let attr_id = raster.load_attributes(VertexAttributes::TexCoords(vec![Vector2d::new(0.0, 0.0)]));
+        raster.update_attributes(attr_id, VertexAttributes::Colors(vec![Vector3d::fill(255.0)])).unwrap();
+        assert_eq!(raster.attributes(attr_id).unwrap(), &VertexAttributes::Colors(vec![Vector3d::fill(255.0)]));
+    }
+
+    #[test]
+    fn buffer_remove() {
+        let mut raster = ndc_rasterizer(8);
+        assert_eq!(raster.buffer_count(), 0);
+        // A long running renderer loading and freeing a mesh every frame does not grow
+        for _ in 0..100 {
+            let pos_id = raster.load_positions(vec![Vector3d::fill(0.0); 3]);
+            let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+            let attr_id = raster.load_attributes(VertexAttributes::Normals(vec![Vector3d::new(0.0, 0.0, 1.0); 3]));
+            raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+            assert_eq!(raster.buffer_count(), 3);
+            assert_eq!(raster.remove_positions(pos_id).unwrap().len(), 3);
+            assert_eq!(raster.remove_indices(ind_id).unwrap(), vec![0, 1, 2]);
+            raster.remove_attributes(attr_id).unwrap();
+            assert_eq!(raster.buffer_count(), 0);
+        }
+
+        raster.load_positions(Vec::new());
+        raster.load_indices(Vec::new());
+        raster.clear_buffers();
+        assert_eq!(raster.buffer_count(), 0);
+    }
+
+    #[test]
+    fn buffer_unknown_handles() {
+        let mut raster = ndc_rasterizer(8);
+        let pos_id = raster.load_positions(vec![Vector3d::new(-1.0, -1.0, 0.0), Vector3d::new(1.0, -1.0, 0.0), Vector3d::new(-1.0, 1.0, 0.0)]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+        let attr_id = raster.load_attributes(VertexAttributes::Colors(Vec::new()));
+        raster.remove_positions(pos_id).unwrap();
+        raster.remove_attributes(attr_id).unwrap();
+
+        let err = raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap_err();
+        assert_eq!(err.err_code, 5001);
+        assert!(err.to_string().starts_with("Unknown position buffer"), "{}", err);
+        assert_eq!(covered(&raster), 0);
+        assert_eq!(raster.remove_positions(pos_id).unwrap_err().err_code, 5001);
+        assert_eq!(raster.update_positions(pos_id, Vec::new()).unwrap_err().err_code, 5001);
+        assert_eq!(raster.update_attributes(attr_id, VertexAttributes::Colors(Vec::new())).unwrap_err().err_code, 5003);
+
+        // Ids are not reused, the removed handle stays invalid after loading new buffers
+        let new_pos_id = raster.load_positions(vec![Vector3d::fill(0.0); 3]);
+        assert_ne!(new_pos_id, pos_id);
+        assert_eq!(raster.positions(pos_id).unwrap_err().err_code, 5001);
+
+        raster.remove_indices(ind_id).unwrap();
+        assert_eq!(raster.draw_transparent(new_pos_id, ind_id, Vector3d::fill(255.0)).unwrap_err().err_code, 5002);
+        assert_eq!(raster.draw_wireframe(new_pos_id, ind_id, Vector3d::fill(255.0)).unwrap_err().err_code, 5002);
+
+        let bad_id = raster.load_indices(vec![Vector3d::new(0, 1, 3)]);
+        let err = raster.draw_triangle(new_pos_id, bad_id, Vector3d::fill(255.0)).unwrap_err();
+        assert_eq!((err.err_code, err.message.as_str()), (5004, "index: 3, length: 3"));
+    }
+}
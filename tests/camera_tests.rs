@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::transform::{self, DepthRange};
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::camera::{Camera, CameraController, FlyController, OrbitController, Projection, TurntableController};
+    use QRender::rasterizer::rasterizer::{BasicRasterizer, Rasterizer};
+
+    fn assert_matrix_close(a: Matrix4d<f32>, b: Matrix4d<f32>) {
+        for (x, y) in a.items.iter().zip(b.items.iter()) {
+            assert!(x.sub(*y).norm() < 1e-4, "\n{}\n!=\n{}", a, b);
+        }
+    }
+
+    fn assert_close(a: Vector3d<f32>, b: Vector3d<f32>) {
+        assert!(a.sub(b).norm() < 1e-4, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn camera_matrices() {
+        // A camera moved without rotation matches the old view and projection shortcuts
+        let mut camera = Camera::perspective(45.0, 1.5, 0.1, 50.0);
+        camera.position = Vector3d::new(1.0, 2.0, 5.0);
+        assert_matrix_close(camera.view_matrix(), Rasterizer::view_matrix(camera.position));
+        assert_matrix_close(camera.projection_matrix(DepthRange::NegativeOneToOne), Rasterizer::projection_matrix(45.0, 1.5, 0.1, 50.0));
+
+        let target = Vector3d::new(-2.0, 0.5, 0.0);
+        camera.look_at(target, Vector3d::new(0.0, 1.0, 0.0));
+        assert_matrix_close(camera.view_matrix(), transform::look_at(camera.position, target, Vector3d::new(0.0, 1.0, 0.0)));
+        assert_close(camera.forward(), target.sub(camera.position).normalize());
+        assert!(camera.right().y.abs() < 1e-6 && camera.up().y > 0.0);
+
+        let ortho = Camera::orthographic(4.0, 2.0, 1.0, 10.0);
+        assert_eq!(ortho.projection, Projection::Orthographic { height: 4.0 });
+        assert_matrix_close(ortho.projection_matrix(DepthRange::ZeroToOne), transform::ortho(-4.0, 4.0, -2.0, 2.0, 1.0, 10.0, DepthRange::ZeroToOne));
+    }
+
+    #[test]
+    fn camera_look_at_vertical() {
+        // Looking straight down the up vector falls back to another up vector instead of producing NaNs
+        let mut camera = Camera::perspective(60.0, 1.0, 0.1, 10.0);
+        camera.position = Vector3d::new(0.0, 5.0, 0.0);
+        camera.look_at(Vector3d::fill(0.0), Vector3d::new(0.0, 1.0, 0.0));
+        assert_close(camera.forward(), Vector3d::new(0.0, -1.0, 0.0));
+        assert_close(camera.up(), Vector3d::new(0.0, 0.0, -1.0));
+        let origin = camera.view_matrix().product_with_vector4d(Vector3d::fill(0.0).to_vector4d(1.0));
+        assert_close(origin.head3(), Vector3d::new(0.0, 0.0, -5.0));
+
+        // A target at the position leaves the camera as it was
+        let before = camera;
+        camera.look_at(camera.position, Vector3d::new(0.0, 1.0, 0.0));
+        assert_eq!(camera, before);
+    }
+
+    #[test]
+    fn camera_set_camera() {
+        let mut camera = Camera::perspective(45.0, 1.0, 0.1, 50.0);
+        camera.position = Vector3d::new(0.0, 0.0, 3.0);
+        let mut raster = Rasterizer::new(8, 8);
+        raster.set_depth_range(DepthRange::ZeroToOne);
+        raster.set_camera(&camera);
+        assert_matrix_close(raster.view, camera.view_matrix());
+        // The projection follows the depth range of the rasterizer
+        assert_matrix_close(raster.projection, camera.projection_matrix(DepthRange::ZeroToOne));
+    }
+
+    #[test]
+    fn camera_orbit() {
+        let mut camera = Camera::perspective(45.0, 1.0, 0.1, 50.0);
+        let target = Vector3d::new(1.0, 0.0, 0.0);
+        let mut orbit = OrbitController::new(target, 4.0);
+        orbit.update(&mut camera);
+        assert_close(camera.position, Vector3d::new(1.0, 0.0, 4.0));
+        assert_close(camera.forward(), Vector3d::new(0.0, 0.0, -1.0));
+
+        // Up by 90 degrees stops short of the pole, the camera still looks at the target
+        orbit.rotate(-90.0, 90.0);
+        orbit.update(&mut camera);
+        assert_eq!(orbit.pitch, 89.0);
+        assert!((camera.position.sub(target).norm() - 4.0).abs() < 1e-4);
+        assert!(camera.position.y > 3.9 && camera.position.x < 1.0, "{}", camera.position);
+        assert_close(camera.forward(), target.sub(camera.position).normalize());
+
+        orbit.rotate(0.0, -89.0);
+        orbit.zoom(0.5);
+        orbit.update(&mut camera);
+        assert_close(camera.position, Vector3d::new(-1.0, 0.0, 0.0));
+        orbit.min_distance = 1.0;
+        orbit.zoom(0.01);
+        assert_eq!(orbit.distance, 1.0);
+
+        // Panning slides the target and the camera sideways
+        orbit.pan(0.5, 0.25);
+        orbit.update(&mut camera);
+        assert_close(orbit.target, Vector3d::new(1.0, 0.25, 0.5));
+        assert_close(camera.forward(), Vector3d::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn camera_fly() {
+        let mut camera = Camera::perspective(45.0, 1.0, 0.1, 50.0);
+        let mut fly = FlyController::new(Vector3d::new(0.0, 1.0, 0.0));
+        fly.translate(2.0, 1.0, 0.5);
+        assert_close(fly.position, Vector3d::new(1.0, 1.5, -2.0));
+
+        // A quarter turn to the left looks down -X, moving forward follows it
+        fly.rotate(90.0, 0.0);
+        fly.translate(1.0, 0.0, 0.0);
+        assert_close(fly.position, Vector3d::new(0.0, 1.5, -2.0));
+        fly.rotate(0.0, 45.0);
+        fly.update(&mut camera);
+        assert_close(camera.position, fly.position);
+        let s = 0.5_f32.sqrt();
+        assert_close(camera.forward(), Vector3d::new(-s, s, 0.0));
+        fly.rotate(0.0, 100.0);
+        assert_eq!(fly.pitch, 89.0);
+    }
+
+    #[test]
+    fn camera_turntable() {
+        let mut camera = Camera::perspective(45.0, 1.0, 0.1, 50.0);
+        let mut turntable = TurntableController::new(Vector3d::fill(0.0), 2.0, 30.0, 90.0);
+        turntable.update(&mut camera);
+        let start = camera.position;
+        assert_close(start, Vector3d::new(0.0, 1.0, 3.0_f32.sqrt()));
+
+        // A quarter turn per unit of time
+        turntable.advance(1.0);
+        turntable.update(&mut camera);
+        assert_close(camera.position, Vector3d::new(3.0_f32.sqrt(), 1.0, 0.0));
+        assert_close(camera.forward(), camera.position.normalize().mul(Vector3d::fill(-1.0)));
+
+        // A full turn comes back to the start
+        for _ in 0..6 {
+            turntable.advance(0.5);
+        }
+        turntable.update(&mut camera);
+        assert_close(camera.position, start);
+        assert!(turntable.angle.abs() < 1e-3);
+    }
+}
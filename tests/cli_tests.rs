@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::Command as Process;
+    use QRender::cli::args::{Command, Options, RenderMode};
+    use QRender::cli::render;
+    use QRender::matrix::vector3d::Vector3d;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("qrender_cli_tests_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A cube from -1 to 1 with a red material and no normals
+    fn cube(dir: &Path) -> PathBuf {
+        fs::write(dir.join("cube.mtl"), "newmtl red\nKd 0.8 0.1 0.1\n").unwrap();
+        fs::write(dir.join("cube.obj"), "mtllib cube.mtl\n\
+            v -1 -1 -1\nv 1 -1 -1\nv 1 1 -1\nv -1 1 -1\nv -1 -1 1\nv 1 -1 1\nv 1 1 1\nv -1 1 1\nusemtl red\n\
+            f 1 4 3 2\nf 5 6 7 8\nf 1 2 6 5\nf 3 4 8 7\nf 2 3 7 6\nf 1 5 8 4\n").unwrap();
+        dir.join("cube.obj")
+    }
+
+    fn parse(args: &[&str]) -> Result<Command, QRender::cli::cli_errors::CliError> {
+        Command::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn cli_parse() {
+        assert_eq!(parse(&["model.obj"]).unwrap(), Command::Render(Options::new("model.obj")));
+        let command = parse(&[
+            "-o", "thumb.ppm", "--size=64x48", "--eye", "-1,2.5,3", "--target=0,0,0", "--fov", "60", "--rotate", "0,90,0", "model.obj", "-m", "flat",
+        ]).unwrap();
+        let mut expected = Options::new("model.obj");
+        expected.output = PathBuf::from("thumb.ppm");
+        expected.width = 64;
+        expected.height = 48;
+        expected.eye = Some(Vector3d::new(-1.0, 2.5, 3.0));
+        expected.target = Some(Vector3d::fill(0.0));
+        expected.fov = 60.0;
+        expected.rotation = Vector3d::new(0.0, 90.0, 0.0);
+        expected.mode = RenderMode::Flat;
+        assert_eq!(command, Command::Render(expected));
+
+        // Arguments are read in order, help only skips the ones after it. -- lets a model name start with a dash
+        assert_eq!(parse(&["model.obj", "--bogus", "-h"]).unwrap_err().err_code, 6001);
+        assert_eq!(parse(&["-h", "--bogus"]).unwrap(), Command::Help);
+        assert_eq!(parse(&["--", "-model.obj"]).unwrap(), Command::Render(Options::new("-model.obj")));
+    }
+
+    #[test]
+    fn cli_parse_errors() {
+        let code = |args: &[&str]| {
+            let err = parse(args).unwrap_err();
+            (err.err_code, err.exit_code())
+        };
+        assert_eq!(code(&["model.obj", "--color", "red"]), (6001, 2));
+        assert_eq!(code(&["model.obj", "--fov"]), (6002, 2));
+        assert_eq!(code(&["model.obj", "--size", "64"]), (6003, 2));
+        assert_eq!(code(&["model.obj", "--size", "0x64"]), (6003, 2));
+        assert_eq!(code(&["model.obj", "--eye", "1,2"]), (6003, 2));
+        assert_eq!(code(&["model.obj", "--rotate", "1,2,x"]), (6003, 2));
+        assert_eq!(code(&["model.obj", "--fov", "180"]), (6003, 2));
+        assert_eq!(code(&["model.obj", "--mode", "solid"]), (6003, 2));
+        assert_eq!(code(&[]), (6004, 2));
+        assert_eq!(code(&["a.obj", "b.obj"]), (6005, 2));
+        assert_eq!(parse(&["model.obj", "--mode", "solid"]).unwrap_err().to_string(), "Invalid option value: --mode solid");
+    }
+
+    #[test]
+    fn cli_render_modes() {
+        let dir = temp_dir("modes");
+        let mut options = Options::new(cube(&dir));
+        options.width = 48;
+        options.height = 32;
+        options.rotation = Vector3d::new(30.0, 40.0, 0.0);
+
+        let mut lit = Vec::new();
+        let mut covered = Vec::new();
+        for mode in [RenderMode::Wireframe, RenderMode::Flat, RenderMode::Shaded].iter() {
+            options.mode = *mode;
+            let raster = render::render(&options).unwrap();
+            assert_eq!((raster.width, raster.height), (48, 32));
+            lit.push(raster.frame_buf.iter().filter(|c| **c != Vector3d::fill(0.0)).count());
+            covered.push(raster.depth_buf.iter().filter(|d| d.is_finite()).count());
+            if *mode != RenderMode::Wireframe {
+                // The framed cube stays inside the image and takes the red of its material
+                assert!(raster.frame_buf.iter().all(|c| c.x >= c.y && c.x >= c.z));
+                for x in 0..48 {
+                    assert_eq!(raster.frame_buf[raster.get_index(x, 0)], Vector3d::fill(0.0));
+                    assert_eq!(raster.frame_buf[raster.get_index(x, 31)], Vector3d::fill(0.0));
+                }
+            }
+        }
+        // The wireframe keeps the depth of its pre-pass, flat and smooth shading cover the same pixels
+        assert!(lit[0] > 0 && lit[0] < covered[0], "{:?} {:?}", lit, covered);
+        assert!(covered[0] == covered[1] && covered[1] == covered[2], "{:?}", covered);
+
+        // The camera looks at the cube from above
+        options.eye = Some(Vector3d::new(0.0, 6.0, 0.0));
+        options.target = Some(Vector3d::fill(0.0));
+        options.rotation = Vector3d::fill(0.0);
+        let raster = render::render(&options).unwrap();
+        assert!(raster.frame_buf[raster.get_index(24, 16)].x > 0.0);
+
+        options.eye = options.target;
+        assert_eq!(render::render(&options).err().unwrap().err_code, 6003);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cli_binary() {
+        let dir = temp_dir("binary");
+        let model = cube(&dir);
+        let output = dir.join("thumb.ppm");
+        let run = |args: &[&str]| Process::new(env!("CARGO_BIN_EXE_qrender")).args(args).output().unwrap();
+
+        let result = run(&[model.to_str().unwrap(), "-o", output.to_str().unwrap(), "-s", "16x8", "-m", "wireframe"]);
+        assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+        assert!(fs::read(&output).unwrap().starts_with(b"P6\n16 8\n255\n"));
+
+        let help = run(&["--help"]);
+        assert_eq!(help.status.code(), Some(0));
+        assert!(String::from_utf8_lossy(&help.stdout).starts_with("Usage: qrender"));
+
+        let result = run(&["--size", "big", model.to_str().unwrap()]);
+        assert_eq!(result.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&result.stderr).contains("--size big"));
+        assert_eq!(run(&[dir.join("missing.obj").to_str().unwrap()]).status.code(), Some(1));
+        assert_eq!(run(&[model.to_str().unwrap(), "-o", dir.join("thumb.gif").to_str().unwrap()]).status.code(), Some(1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use QRender::matrix::transform::{self, DepthRange};
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::matrix::vector4d::Vector4d;
+    use QRender::rasterizer::clip::{clip_triangle, ClipPlane};
+    use QRender::rasterizer::rasterizer::Rasterizer;
+    use QRender::rasterizer::shader::{Varyings, VertexOutput};
+
+    fn vertex(x: f32, y: f32, z: f32, w: f32, u: f32) -> VertexOutput {
+        VertexOutput {
+            position: Vector4d::new(x, y, z, w),
+            varyings: Varyings {
+                color: Vector3d::fill(u),
+                normal: Vector3d::fill(0.0),
+                tex_coords: Vector2d::new(u, 0.0),
+                world_pos: Vector3d::fill(0.0),
+            },
+        }
+    }
+
+    fn assert_inside(polygon: &[VertexOutput], depth: DepthRange) {
+        for v in polygon.iter() {
+            for plane in ClipPlane::ALL.iter() {
+                assert!(plane.distance(v.position, depth) >= -1e-5, "{:?} outside {:?}", v.position, plane);
+            }
+        }
+    }
+
+    #[test]
+    fn clip_inside_and_outside() {
+        let inside = [vertex(0.0, 0.0, 0.0, 1.0, 0.0), vertex(0.5, 0.0, 0.0, 1.0, 1.0), vertex(0.0, 0.5, 0.0, 1.0, 0.5)];
+        assert_eq!(clip_triangle(&inside, DepthRange::NegativeOneToOne), inside.to_vec());
+
+        let right = [vertex(2.0, 0.0, 0.0, 1.0, 0.0), vertex(3.0, 0.0, 0.0, 1.0, 0.0), vertex(2.0, 0.5, 0.0, 1.0, 0.0)];
+        assert!(clip_triangle(&right, DepthRange::NegativeOneToOne).is_empty());
+
+        // Behind the camera every vertex has a negative w
+        let behind = [vertex(0.0, 0.0, 1.0, -1.0, 0.0), vertex(0.5, 0.0, 1.0, -1.0, 0.0), vertex(0.0, 0.5, 1.0, -1.0, 0.0)];
+        assert!(clip_triangle(&behind, DepthRange::NegativeOneToOne).is_empty());
+    }
+
+    #[test]
+    fn clip_near_plane_interpolates_varyings() {
+        // The first vertex lies behind the near plane z = -w
+        let triangle = [vertex(0.0, 0.0, -3.0, 1.0, 0.0), vertex(0.0, 0.0, 1.0, 1.0, 1.0), vertex(0.5, 0.5, 1.0, 1.0, 1.0)];
+        let polygon = clip_triangle(&triangle, DepthRange::NegativeOneToOne);
+        assert_eq!(polygon.len(), 4);
+        assert_inside(&polygon, DepthRange::NegativeOneToOne);
+
+        // The new vertex on the edge v0 -> v1 lies half way along it
+        let cut = polygon.iter().find(|v| v.position.x == 0.0 && v.position.z == -1.0).unwrap();
+        assert!((cut.varyings.tex_coords.x - 0.5).abs() < 1e-6);
+        assert!((cut.varyings.color.y - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn clip_depth_ranges() {
+        let triangle = [vertex(0.0, 0.0, -0.5, 1.0, 0.0), vertex(0.5, 0.0, 0.5, 1.0, 1.0), vertex(0.0, 0.5, 0.5, 1.0, 1.0)];
+        assert_eq!(clip_triangle(&triangle, DepthRange::NegativeOneToOne).len(), 3);
+
+        let polygon = clip_triangle(&triangle, DepthRange::ZeroToOne);
+        assert_eq!(polygon.len(), 4);
+        assert_inside(&polygon, DepthRange::ZeroToOne);
+    }
+
+    #[test]
+    fn clip_every_plane() {
+        // A triangle much larger than the frustum is cut down to the square of the screen
+        let triangle = [vertex(-10.0, -10.0, 0.0, 1.0, 0.0), vertex(30.0, -10.0, 0.0, 1.0, 0.0), vertex(-10.0, 30.0, 0.0, 1.0, 0.0)];
+        let polygon = clip_triangle(&triangle, DepthRange::NegativeOneToOne);
+        assert_eq!(polygon.len(), 4);
+        assert_inside(&polygon, DepthRange::NegativeOneToOne);
+        for v in polygon.iter() {
+            assert!((v.position.x.abs() - 1.0).abs() < 1e-5 && (v.position.y.abs() - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn clip_camera_inside_scene() {
+        let mut raster = Rasterizer::new(40, 40);
+        // A floor passing under and far behind the camera
+        let pos_id = raster.load_positions(vec![
+            Vector3d::new(-100.0, 0.0, 20.0),
+            Vector3d::new(100.0, 0.0, 20.0),
+            Vector3d::new(0.0, 0.0, -30.0),
+        ]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+        raster.set_model(transform::scale(Vector3d::fill(1.0)));
+        raster.set_view(transform::look_at(Vector3d::new(0.0, 1.0, 0.0), Vector3d::new(0.0, 1.0, -1.0), Vector3d::new(0.0, 1.0, 0.0)));
+        raster.set_projection(transform::perspective(90.0, 1.0, 0.1, 50.0, DepthRange::NegativeOneToOne));
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+
+        // The floor covers the whole bottom half of the screen and nothing above the horizon
+        for x in 0..40 {
+            for y in 0..19 {
+                assert!(raster.depth_buf[raster.get_index(x, y)].is_finite(), "({}, {}) not covered", x, y);
+            }
+            for y in 20..40 {
+                assert!(raster.depth_buf[raster.get_index(x, y)].is_infinite(), "({}, {}) covered", x, y);
+            }
+        }
+        assert!(raster.depth_buf.iter().all(|d| d.is_infinite() || (*d >= 0.1 - 1e-4 && *d <= 100.0 + 1e-4)));
+    }
+
+    #[test]
+    fn clip_triangle_behind_camera_draws_nothing() {
+        let mut raster = Rasterizer::new(20, 20);
+        let pos_id = raster.load_positions(vec![
+            Vector3d::new(-1.0, -1.0, 5.0),
+            Vector3d::new(1.0, -1.0, 5.0),
+            Vector3d::new(0.0, 1.0, 5.0),
+        ]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+        raster.set_model(transform::scale(Vector3d::fill(1.0)));
+        raster.set_view(transform::look_at(Vector3d::fill(0.0), Vector3d::new(0.0, 0.0, -1.0), Vector3d::new(0.0, 1.0, 0.0)));
+        raster.set_projection(transform::perspective(60.0, 1.0, 0.1, 50.0, DepthRange::NegativeOneToOne));
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+
+        assert!(raster.depth_buf.iter().all(|d| d.is_infinite()));
+    }
+}
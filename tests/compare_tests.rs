@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use image::{Rgb, RgbImage};
+    use QRender::rasterizer::compare::{self, Tolerance};
+
+    /// A horizontal gradient with a bright square in the middle
+    fn pattern() -> RgbImage {
+        RgbImage::from_fn(32, 24, |x, y| {
+            if (10..20).contains(&x) && (8..16).contains(&y) {
+                Rgb([240, 240, 240])
+            } else {
+                Rgb([(x * 6) as u8, 40, 255 - (x * 6) as u8])
+            }
+        })
+    }
+
+    #[test]
+    fn compare_identical() {
+        let image = pattern();
+        let diff = compare::compare(&image, &image, &Tolerance::exact()).unwrap();
+        assert_eq!((diff.max_diff, diff.mismatched, diff.pixels), (0, 0, 32 * 24));
+        assert!(diff.psnr.is_infinite());
+        assert!((diff.ssim - 1.0).abs() < 1e-6);
+        assert!(diff.within(&Tolerance::exact()));
+    }
+
+    #[test]
+    fn compare_metrics() {
+        let expected = pattern();
+        // Rounding noise of one step everywhere stays within the default tolerance
+        let mut noisy = expected.clone();
+        for (i, p) in noisy.pixels_mut().enumerate() {
+            p.0[i % 3] = if p.0[i % 3] == 255 { 254 } else { p.0[i % 3] + 1 };
+        }
+        let diff = compare::compare(&noisy, &expected, &Tolerance::default()).unwrap();
+        assert_eq!((diff.max_diff, diff.mismatched), (1, 0));
+        // One channel in three off by 1 gives an MSE of 1 / 3
+        assert!((diff.psnr - 10.0 * (3.0_f32 * 255.0 * 255.0).log10()).abs() < 1e-3, "{}", diff.psnr);
+        assert!(diff.ssim > 0.99);
+        assert!(diff.within(&Tolerance::default()));
+        assert!(!diff.within(&Tolerance::exact()));
+
+        // Moving the square breaks the structure
+        let mut shifted = expected.clone();
+        for y in 8..16 {
+            for x in 0..32 {
+                shifted.put_pixel(x, y, *expected.get_pixel((x + 28) % 32, y));
+            }
+        }
+        let diff = compare::compare(&shifted, &expected, &Tolerance::default()).unwrap();
+        assert!(diff.mismatched > 8 * 4, "{}", diff.mismatched);
+        assert!(diff.psnr < 20.0, "{}", diff.psnr);
+        assert!(diff.ssim < 0.9, "{}", diff.ssim);
+        assert!(!diff.within(&Tolerance::default()));
+        assert_eq!(compare::psnr(&shifted, &expected).unwrap(), diff.psnr);
+        assert_eq!(compare::ssim(&shifted, &expected).unwrap(), diff.ssim);
+    }
+
+    #[test]
+    fn compare_diff_image() {
+        let expected = pattern();
+        let mut actual = expected.clone();
+        actual.put_pixel(3, 4, Rgb([0, 0, 0]));
+        actual.put_pixel(5, 4, Rgb([expected.get_pixel(5, 4).0[0] + 1, 40, 255 - 30]));
+        let diff = compare::diff_image(&actual, &expected, 1).unwrap();
+        assert_eq!(diff.dimensions(), (32, 24));
+        // The black pixel differs by 237 in blue
+        assert_eq!(*diff.get_pixel(3, 4), Rgb([128 + 237 / 2, 0, 0]));
+        // Within tolerance, a darkened gray of the reference
+        let gray = *diff.get_pixel(5, 4);
+        assert!(gray.0[0] == gray.0[1] && gray.0[1] == gray.0[2] && gray.0[0] < 80, "{:?}", gray);
+        assert_eq!(diff.pixels().filter(|p| p.0[1] == 0 && p.0[0] >= 128).count(), 1);
+    }
+
+    #[test]
+    fn compare_size_mismatch() {
+        let a = RgbImage::new(4, 4);
+        let b = RgbImage::new(4, 3);
+        let err = compare::compare(&a, &b, &Tolerance::default()).unwrap_err();
+        assert_eq!((err.err_code, err.to_string().as_str()), (5009, "Image sizes differ: 4x4 and 4x3"));
+        assert_eq!(compare::psnr(&a, &b).unwrap_err().err_code, 5009);
+        assert_eq!(compare::ssim(&a, &b).unwrap_err().err_code, 5009);
+        assert_eq!(compare::diff_image(&a, &b, 0).unwrap_err().err_code, 5009);
+        // Images smaller than a window still get a score
+        let tiny = RgbImage::from_pixel(2, 1, Rgb([9, 9, 9]));
+        assert!((compare::ssim(&tiny, &tiny).unwrap() - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use image::{Rgb, Rgba};
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::matrix::vector4d::Vector4d;
+    use QRender::rasterizer::export::{write_pfm, write_ppm};
+    use QRender::rasterizer::rasterizer::Rasterizer;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("qrender_export_tests_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A 3 x 2 frame with a red bottom left and a blue top right pixel
+    fn frame() -> Rasterizer {
+        let mut raster = Rasterizer::new(3, 2);
+        raster.set_pixel(Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(255.0, 0.0, 0.0));
+        raster.set_pixel_rgba(Vector3d::new(2.0, 1.0, 0.0), Vector4d::new(0.0, 0.0, 255.0, 128.0));
+        raster
+    }
+
+    #[test]
+    fn export_orientation() {
+        let raster = frame();
+        let image = raster.to_rgb_image();
+        assert_eq!(image.dimensions(), (3, 2));
+        // Screen y points up, image rows go down
+        assert_eq!(*image.get_pixel(0, 1), Rgb([255, 0, 0]));
+        assert_eq!(*image.get_pixel(2, 0), Rgb([0, 0, 255]));
+        assert_eq!(*image.get_pixel(0, 0), Rgb([0, 0, 0]));
+        // Every corner pixel is exported, including row 0 and column 0
+        for (x, y) in [(0, 0), (2, 0), (0, 1), (2, 1)].iter() {
+            let color = raster.frame_buf[raster.get_index(*x, *y)];
+            assert_eq!(image.get_pixel(*x as u32, 1 - *y as u32).0, [color.x as u8, color.y as u8, color.z as u8]);
+        }
+
+        let rgba = raster.to_rgba_image();
+        assert_eq!(*rgba.get_pixel(0, 1), Rgba([255, 0, 0, 255]));
+        assert_eq!(*rgba.get_pixel(2, 0), Rgba([0, 0, 255, 128]));
+        assert_eq!(*rgba.get_pixel(1, 0), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn export_clamps() {
+        let mut raster = Rasterizer::new(1, 1);
+        raster.frame_buf[0] = Vector3d::new(-3.0, 127.6, 300.0);
+        assert_eq!(*raster.to_rgb_image().get_pixel(0, 0), Rgb([0, 128, 255]));
+    }
+
+    #[test]
+    fn export_ppm_and_pfm() {
+        let dir = temp_dir("netpbm");
+        let raster = frame();
+        raster.save(dir.join("frame.ppm")).unwrap();
+        let ppm = fs::read(dir.join("frame.ppm")).unwrap();
+        let header = b"P6\n3 2\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        let pixels = &ppm[header.len()..];
+        assert_eq!(pixels.len(), 3 * 2 * 3);
+        // Top row first, the blue pixel ends it and the red pixel starts the bottom row
+        assert_eq!(&pixels[6..9], &[0, 0, 255]);
+        assert_eq!(&pixels[9..12], &[255, 0, 0]);
+
+        raster.save(dir.join("frame.PFM")).unwrap();
+        let pfm = fs::read(dir.join("frame.PFM")).unwrap();
+        let header = b"PF\n3 2\n-1.0\n";
+        assert_eq!(&pfm[..header.len()], header);
+        let floats: Vec<f32> = pfm[header.len()..].chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect();
+        assert_eq!(floats.len(), 3 * 2 * 3);
+        // PFM stores the bottom row first
+        assert_eq!(&floats[0..3], &[1.0, 0.0, 0.0]);
+        assert_eq!(&floats[15..18], &[0.0, 0.0, 1.0]);
+
+        // The writers also take buffers that do not belong to a rasterizer
+        write_ppm(dir.join("gray.ppm"), 1, 1, &[Vector3d::fill(64.0)]).unwrap();
+        assert_eq!(fs::read(dir.join("gray.ppm")).unwrap(), b"P6\n1 1\n255\n\x40\x40\x40".to_vec());
+        write_pfm(dir.join("hdr.pfm"), 1, 1, &[Vector3d::fill(510.0)]).unwrap();
+        assert_eq!(&fs::read(dir.join("hdr.pfm")).unwrap()[12..16], &2.0_f32.to_le_bytes());
+
+        // Too few or too many colors for the size are rejected without writing the file
+        let colors = vec![Vector3d::fill(64.0); 5];
+        assert_eq!(write_ppm(dir.join("short.ppm"), 3, 2, &colors).unwrap_err().err_code, 5008);
+        assert_eq!(write_pfm(dir.join("short.pfm"), 3, 2, &colors).unwrap_err().err_code, 5008);
+        assert_eq!(write_ppm(dir.join("long.ppm"), 2, 2, &colors).unwrap_err().err_code, 5008);
+        assert_eq!(write_pfm(dir.join("huge.pfm"), usize::MAX, 2, &colors).unwrap_err().err_code, 5008);
+        assert!(!dir.join("short.ppm").exists() && !dir.join("short.pfm").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn export_image_formats() {
+        let dir = temp_dir("formats");
+        let raster = frame();
+        for extension in ["png", "bmp", "tga"].iter() {
+            let path = dir.join(format!("frame.{}", extension));
+            raster.save(&path).unwrap();
+            let image = image::open(&path).unwrap().to_rgb8();
+            assert_eq!(image, raster.to_rgb_image(), "{}", extension);
+        }
+        // JPEG is lossy, only check the size
+        raster.save(dir.join("frame.jpg")).unwrap();
+        assert_eq!(image::open(dir.join("frame.jpg")).unwrap().to_rgb8().dimensions(), (3, 2));
+
+        assert_eq!(raster.save(dir.join("frame.gif2")).unwrap_err().err_code, 5007);
+        assert_eq!(raster.save(dir.join("frame")).unwrap_err().err_code, 5007);
+        assert_eq!(raster.save(dir.join("missing").join("frame.png")).unwrap_err().err_code, 5006);
+        assert_eq!(raster.save(dir.join("missing").join("frame.ppm")).unwrap_err().err_code, 5006);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
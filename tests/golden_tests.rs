@@ -0,0 +1,158 @@
+/** Reference image tests. Every scene is rendered and compared to tests/golden/<name>.png with compare::Tolerance::default().
+  On a mismatch the rendered image and a diff image are written to <target>/tmp/golden and the test fails with the metrics.
+  After an intended rendering change, regenerate the references with
+  ```text
+  QRENDER_BLESS=1 cargo test --test golden_tests
+  ```
+  and review the updated PNGs before committing them.
+ */
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use image::{DynamicImage, Rgba, RgbaImage};
+    use QRender::cli::args::{Options, RenderMode};
+    use QRender::cli::render;
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::transform::{self, DepthRange};
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::blend::BlendState;
+    use QRender::rasterizer::compare::{self, Tolerance};
+    use QRender::rasterizer::rasterizer::{LineMode, Rasterizer};
+    use QRender::rasterizer::shader::{ColorFragmentShader, OpacityFragmentShader};
+    use QRender::rasterizer::texture::{FilterMode, Texture, TextureFragmentShader};
+
+    fn assert_golden(name: &str, raster: &Rasterizer) {
+        let actual = raster.to_rgb_image();
+        let reference = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(format!("{}.png", name));
+        if std::env::var_os("QRENDER_BLESS").is_some() {
+            fs::create_dir_all(reference.parent().unwrap()).unwrap();
+            actual.save(&reference).unwrap();
+            return;
+        }
+        let expected = match image::open(&reference) {
+            Ok(image) => image.to_rgb8(),
+            Err(e) => panic!("{}: {}, run with QRENDER_BLESS=1 to create it", reference.display(), e),
+        };
+
+        let tolerance = Tolerance::default();
+        let diff = compare::compare(&actual, &expected, &tolerance).unwrap();
+        if !diff.within(&tolerance) {
+            let out = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden");
+            fs::create_dir_all(&out).unwrap();
+            actual.save(out.join(format!("{}.actual.png", name))).unwrap();
+            compare::diff_image(&actual, &expected, tolerance.pixel).unwrap().save(out.join(format!("{}.diff.png", name))).unwrap();
+            panic!(
+                "{} differs from its reference: {} of {} pixels mismatched, max diff {}, PSNR {:.2} dB, SSIM {:.4}, see {}",
+                name, diff.mismatched, diff.pixels, diff.max_diff, diff.psnr, diff.ssim, out.display()
+            );
+        }
+    }
+
+    /// A 64 x 64 rasterizer with a camera at (0, 0, 3) looking at the origin
+    fn scene() -> Rasterizer {
+        let mut raster = Rasterizer::new(64, 64);
+        raster.set_model(Matrix4d::identity(1.0));
+        raster.set_view(transform::look_at(Vector3d::new(0.0, 0.0, 3.0), Vector3d::fill(0.0), Vector3d::new(0.0, 1.0, 0.0)));
+        raster.set_projection(transform::perspective(45.0, 1.0, 0.1, 50.0, DepthRange::NegativeOneToOne));
+        raster
+    }
+
+    #[test]
+    fn golden_vertex_colors() {
+        let mut raster = scene();
+        raster.set_model(transform::rotate_z(15.0));
+        let pos_id = raster.load_positions(vec![Vector3d::new(-1.0, -0.8, 0.0), Vector3d::new(1.0, -0.8, 0.0), Vector3d::new(0.0, 0.9, 0.0)]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+        let color_id = raster.load_colors(vec![Vector3d::new(255.0, 0.0, 0.0), Vector3d::new(0.0, 255.0, 0.0), Vector3d::new(0.0, 0.0, 255.0)]);
+        raster.bind_attributes(pos_id, color_id).unwrap();
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+        assert_golden("vertex_colors", &raster);
+    }
+
+    #[test]
+    fn golden_depth_intersection() {
+        // Two triangles tilted in opposite directions cross in the middle
+        let mut raster = scene();
+        let pos_id = raster.load_positions(vec![
+            Vector3d::new(-1.0, -0.8, -0.5), Vector3d::new(1.0, -0.8, 0.5), Vector3d::new(0.0, 0.8, 0.0),
+            Vector3d::new(-1.0, 0.8, 0.5), Vector3d::new(0.0, -0.8, 0.0), Vector3d::new(1.0, 0.8, -0.5),
+        ]);
+        let first = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+        let second = raster.load_indices(vec![Vector3d::new(3, 4, 5)]);
+        raster.draw_triangle(pos_id, first, Vector3d::new(230.0, 120.0, 30.0)).unwrap();
+        raster.draw_triangle(pos_id, second, Vector3d::new(40.0, 140.0, 220.0)).unwrap();
+        assert_golden("depth_intersection", &raster);
+    }
+
+    #[test]
+    fn golden_lines() {
+        let mut raster = Rasterizer::new(64, 64);
+        let center = Vector3d::new(32.0, 32.0, 0.0);
+        for (i, mode) in [LineMode::Aliased, LineMode::AntiAliased, LineMode::Thick(3.0)].iter().enumerate() {
+            raster.set_line_mode(*mode);
+            for k in 0..4 {
+                let angle = (i * 4 + k) as f32 * 15.0_f32.to_radians();
+                let end = center.add(Vector3d::new(angle.cos() * 30.0, angle.sin() * 30.0, 0.0));
+                raster.draw_line(center, end, Vector3d::new(255.0, 255.0 - 60.0 * i as f32, 60.0 * k as f32));
+            }
+        }
+        assert_golden("lines", &raster);
+    }
+
+    #[test]
+    fn golden_alpha_blending() {
+        let mut raster = scene();
+        let pos_id = raster.load_positions(vec![
+            Vector3d::new(-1.0, -1.0, 0.0), Vector3d::new(0.6, -1.0, 0.0), Vector3d::new(-1.0, 0.6, 0.0),
+            Vector3d::new(1.0, 1.0, 0.3), Vector3d::new(-0.6, 1.0, 0.3), Vector3d::new(1.0, -0.6, 0.3),
+        ]);
+        let back = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+        let front = raster.load_indices(vec![Vector3d::new(3, 4, 5)]);
+        raster.draw_triangle(pos_id, back, Vector3d::new(255.0, 200.0, 0.0)).unwrap();
+        raster.set_fragment_shader(OpacityFragmentShader::new(ColorFragmentShader, 0.5));
+        raster.set_blend_state(BlendState::alpha());
+        raster.draw_transparent(pos_id, front, Vector3d::new(0.0, 80.0, 255.0)).unwrap();
+        raster.flush_transparent();
+        assert_golden("alpha_blending", &raster);
+    }
+
+    #[test]
+    fn golden_texture_perspective() {
+        // A checkerboard floor going into the distance
+        let checker = RgbaImage::from_fn(8, 8, |x, y| if (x + y) % 2 == 0 { Rgba([255, 255, 255, 255]) } else { Rgba([200, 30, 30, 255]) });
+        let mut texture = Texture::from_image(&DynamicImage::ImageRgba8(checker)).unwrap();
+        texture.set_filter(FilterMode::Nearest);
+        let mut raster = scene();
+        raster.set_fragment_shader(TextureFragmentShader::new(texture));
+        raster.set_model(transform::rotate_x(-70.0));
+        let pos_id = raster.load_positions(vec![
+            Vector3d::new(-1.5, -1.5, 0.0), Vector3d::new(1.5, -1.5, 0.0), Vector3d::new(-1.5, 1.5, 0.0), Vector3d::new(1.5, 1.5, 0.0),
+        ]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2), Vector3d::new(2, 1, 3)]);
+        let tex_id = raster.load_texcoords(vec![Vector2d::new(0.0, 0.0), Vector2d::new(1.0, 0.0), Vector2d::new(0.0, 1.0), Vector2d::new(1.0, 1.0)]);
+        raster.bind_attributes(pos_id, tex_id).unwrap();
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+        assert_golden("texture_perspective", &raster);
+    }
+
+    #[test]
+    fn golden_cli_modes() {
+        let dir = std::env::temp_dir().join(format!("qrender_golden_tests_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("cube.mtl"), "newmtl teal\nKa 0.05 0.1 0.1\nKd 0.2 0.7 0.7\nKs 0.4 0.4 0.4\nNs 24\n").unwrap();
+        fs::write(dir.join("cube.obj"), "mtllib cube.mtl\n\
+            v -1 -1 -1\nv 1 -1 -1\nv 1 1 -1\nv -1 1 -1\nv -1 -1 1\nv 1 -1 1\nv 1 1 1\nv -1 1 1\nusemtl teal\n\
+            f 1 4 3 2\nf 5 6 7 8\nf 1 2 6 5\nf 3 4 8 7\nf 2 3 7 6\nf 1 5 8 4\n").unwrap();
+        let mut options = Options::new(dir.join("cube.obj"));
+        options.width = 64;
+        options.height = 64;
+        options.rotation = Vector3d::new(25.0, 35.0, 0.0);
+        for (mode, name) in [(RenderMode::Wireframe, "cli_wireframe"), (RenderMode::Flat, "cli_flat"), (RenderMode::Shaded, "cli_shaded")].iter() {
+            options.mode = *mode;
+            assert_golden(name, &render::render(&options).unwrap());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod tests {
+    use QRender::geometry::obj::ObjMaterial;
+    use QRender::geometry::triangle::Triangle;
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::transform;
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::light::{Attenuation, BlinnPhongFragmentShader, Light, Material};
+    use QRender::rasterizer::rasterizer::Rasterizer;
+    use QRender::rasterizer::shader::{DefaultVertexShader, FragmentPayload, FragmentShader, Transforms, Varyings, VertexOutput, VertexPayload, VertexShader};
+
+    fn assert_vector_eq(a: Vector3d<f32>, b: Vector3d<f32>) {
+        assert!((a.x - b.x).abs() < 1e-5 && (a.y - b.y).abs() < 1e-5 && (a.z - b.z).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    fn fragment(world_pos: Vector3d<f32>, normal: Vector3d<f32>) -> FragmentPayload {
+        FragmentPayload {
+            screen_pos: Vector3d::fill(0.0),
+            varyings: Varyings {
+                color: Vector3d::fill(1.0),
+                normal,
+                tex_coords: Vector2d::fill(0.0),
+                world_pos,
+            },
+        }
+    }
+
+    /// Give every vertex the same object space normal, then transform it like DefaultVertexShader
+    struct FlatNormalShader(Vector3d<f32>);
+
+    impl VertexShader for FlatNormalShader {
+        fn shade(&self, vertex: &VertexPayload, transforms: &Transforms) -> VertexOutput {
+            DefaultVertexShader.shade(&VertexPayload { normal: self.0, ..*vertex }, transforms)
+        }
+    }
+
+    #[test]
+    fn light_attenuation() {
+        assert_eq!(Attenuation::none().factor(10.0), 1.0);
+        assert_eq!(Attenuation::inverse_square().factor(2.0), 0.25);
+        assert_eq!(Attenuation::new(1.0, 1.0, 0.0).factor(3.0), 0.25);
+    }
+
+    #[test]
+    fn light_point_and_directional() {
+        let origin = Vector3d::fill(0.0);
+        let point = Light::point(Vector3d::new(3.0, 0.0, 4.0), Vector3d::fill(1.0)).with_attenuation(Attenuation::new(0.0, 1.0, 0.0));
+        let (l, intensity) = point.illuminate(origin);
+        assert_vector_eq(l, Vector3d::new(0.6, 0.0, 0.8));
+        assert_vector_eq(intensity, Vector3d::fill(0.2));
+
+        let sun = Light::directional(Vector3d::new(0.0, -2.0, 0.0), Vector3d::new(1.0, 0.5, 0.0));
+        let (l, intensity) = sun.illuminate(Vector3d::new(100.0, 5.0, -7.0));
+        assert_vector_eq(l, Vector3d::new(0.0, 1.0, 0.0));
+        assert_vector_eq(intensity, Vector3d::new(1.0, 0.5, 0.0));
+        assert_eq!(sun.with_attenuation(Attenuation::inverse_square()), sun);
+    }
+
+    #[test]
+    fn light_spot_cone() {
+        let spot = Light::spot(Vector3d::new(0.0, 1.0, 0.0), Vector3d::new(0.0, -1.0, 0.0), Vector3d::fill(1.0), 20.0, 40.0);
+        let at_angle = |deg: f32| spot.illuminate(Vector3d::new(deg.to_radians().tan(), 0.0, 0.0)).1.x;
+
+        assert!((at_angle(0.0) - 1.0).abs() < 1e-5);
+        assert!((at_angle(15.0) - 1.0).abs() < 1e-5);
+        let middle = at_angle(30.0);
+        assert!(middle > 0.0 && middle < 1.0);
+        assert!(at_angle(25.0) > middle && middle > at_angle(35.0));
+        assert_eq!(at_angle(45.0), 0.0);
+    }
+
+    #[test]
+    fn blinn_phong_terms() {
+        let material = Material::new(Vector3d::fill(0.1), Vector3d::new(0.5, 0.0, 0.0), Vector3d::new(0.0, 0.0, 0.5), 8.0);
+        let light = Light::directional(Vector3d::new(0.0, 0.0, -1.0), Vector3d::fill(1.0));
+        let mut shader = BlinnPhongFragmentShader::new(material, vec![light], Vector3d::new(0.0, 0.0, 10.0));
+        shader.ambient_light = Vector3d::fill(0.5);
+
+        // Facing the light and the eye, every term is at its maximum
+        let color = shader.shade(&fragment(Vector3d::fill(0.0), Vector3d::new(0.0, 0.0, 2.0)));
+        assert_vector_eq(color, Vector3d::new(0.55, 0.05, 0.55));
+
+        // Tilted by 60 degrees, the diffuse term halves and the highlight, centered on the normal, nearly vanishes
+        let n = Vector3d::new(60.0_f32.to_radians().sin(), 0.0, 60.0_f32.to_radians().cos());
+        let color = shader.shade(&fragment(Vector3d::fill(0.0), n));
+        assert!((color.x - 0.3).abs() < 1e-5);
+        assert!(color.z > 0.05 && color.z < 0.06);
+
+        // Facing away from the light only keeps the ambient term
+        let color = shader.shade(&fragment(Vector3d::fill(0.0), Vector3d::new(0.0, 0.0, -1.0)));
+        assert_vector_eq(color, Vector3d::fill(0.05));
+    }
+
+    #[test]
+    fn blinn_phong_clamps_and_sums_lights() {
+        let lights = vec![
+            Light::directional(Vector3d::new(0.0, 0.0, -1.0), Vector3d::fill(1.0)),
+            Light::directional(Vector3d::new(0.0, 0.0, -1.0), Vector3d::fill(1.0)),
+        ];
+        let mut shader = BlinnPhongFragmentShader::new(Material::default(), lights, Vector3d::new(0.0, 0.0, 10.0));
+        let color = shader.shade(&fragment(Vector3d::fill(0.0), Vector3d::new(0.0, 0.0, 1.0)));
+        assert_vector_eq(color, Vector3d::fill(1.0));
+
+        shader.lights.clear();
+        let color = shader.shade(&fragment(Vector3d::fill(0.0), Vector3d::new(0.0, 0.0, 1.0)));
+        assert_vector_eq(color, Material::default().ambient);
+    }
+
+    #[test]
+    fn material_from_obj() {
+        let materials = ObjMaterial::parse("newmtl m\nKa 0.1 0.2 0.3\nKd 0.4 0.5 0.6\nKs 1 1 1\nNs 50").unwrap();
+        let m = Material::from(&materials["m"]);
+        assert_eq!(m.ambient, Vector3d::new(0.1, 0.2, 0.3));
+        assert_eq!(m.diffuse, Vector3d::new(0.4, 0.5, 0.6));
+        assert_eq!(m.shininess, 50.0);
+    }
+
+    #[test]
+    fn blinn_phong_rasterize() {
+        let mut raster = Rasterizer::new(20, 20);
+        let material = Material::new(Vector3d::fill(0.0), Vector3d::fill(1.0), Vector3d::fill(0.0), 1.0);
+        // A point light above the left edge of the screen
+        let light = Light::point(Vector3d::new(0.0, 10.0, 5.0), Vector3d::fill(1.0));
+        raster.set_fragment_shader(BlinnPhongFragmentShader::new(material, vec![light], Vector3d::new(10.0, 10.0, 10.0)));
+
+        let mut t = Triangle::from_vertex([
+            Vector3d::new(0.0, 0.0, 1.0),
+            Vector3d::new(20.0, 0.0, 1.0),
+            Vector3d::new(0.0, 20.0, 1.0),
+        ]);
+        for i in 0..3 {
+            t.set_normal(i, Vector3d::new(0.0, 0.0, 1.0)).unwrap();
+        }
+        raster.rasterize_triangle(&t);
+
+        let near = raster.frame_buf[raster.get_index(0, 10)];
+        let far = raster.frame_buf[raster.get_index(18, 0)];
+        assert!(near.x > far.x && far.x > 0.0);
+    }
+
+    #[test]
+    fn blinn_phong_non_uniform_scale() {
+        // The plane z = y squashed to half its height becomes z = 2y, whose normal is (0, -2, 1)
+        let mut raster = Rasterizer::new(16, 16);
+        raster.set_model(transform::scale(Vector3d::new(1.0, 0.5, 1.0)));
+        raster.set_view(Matrix4d::identity(1.0));
+        raster.set_projection(Matrix4d::identity(1.0));
+        raster.set_vertex_shader(FlatNormalShader(Vector3d::new(0.0, -1.0, 1.0).normalize()));
+        let material = Material::new(Vector3d::fill(0.0), Vector3d::fill(1.0), Vector3d::fill(0.0), 1.0);
+        // Light along the squashed normal lights the plane fully, the model matrix alone would tilt the normal to (0, -0.5, 1)
+        let light = Light::directional(Vector3d::new(0.0, 2.0, -1.0), Vector3d::fill(1.0));
+        raster.set_fragment_shader(BlinnPhongFragmentShader::new(material, vec![light], Vector3d::new(0.0, 0.0, 10.0)));
+
+        let pos_id = raster.load_positions(vec![Vector3d::new(-2.0, -2.0, -2.0), Vector3d::new(2.0, -2.0, -2.0), Vector3d::new(0.0, 2.0, 2.0)]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+
+        let center = raster.frame_buf[raster.get_index(8, 8)];
+        assert!((center.x - 255.0).abs() < 1e-2, "{}", center);
+    }
+}
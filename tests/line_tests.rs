@@ -0,0 +1,245 @@
+#[cfg(test)]
+mod tests {
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::transform::DepthRange;
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::matrix::vector4d::Vector4d;
+    use QRender::rasterizer::clip::clip_line;
+    use QRender::rasterizer::line::{bresenham, clip_segment, thick_clipped, wu, LinePixel};
+    use QRender::rasterizer::rasterizer::{LineMode, Rasterizer};
+    use QRender::rasterizer::shader::NormalFragmentShader;
+
+    /// A rasterizer drawing directly in normalized device coordinates
+    fn ndc_rasterizer(size: usize) -> Rasterizer {
+        let mut raster = Rasterizer::new(size, size);
+        raster.set_model(Matrix4d::identity(1.0));
+        raster.set_view(Matrix4d::identity(1.0));
+        raster.set_projection(Matrix4d::identity(1.0));
+        raster
+    }
+
+    fn lit(raster: &Rasterizer) -> usize {
+        raster.frame_buf.iter().filter(|c| **c != Vector3d::fill(0.0)).count()
+    }
+
+    #[test]
+    fn line_bresenham_octants() {
+        let center = Vector2d::new(10.5, 10.5);
+        let ends: [(i64, i64); 8] = [(7, 3), (3, 7), (-3, 7), (-7, 3), (-7, -3), (-3, -7), (3, -7), (7, -3)];
+        for (dx, dy) in ends.iter() {
+            let end = Vector2d::new(center.x + *dx as f32, center.y + *dy as f32);
+            let pixels = bresenham(center, end);
+            assert_eq!(pixels.len() as i64, dx.abs().max(dy.abs()) + 1, "({}, {})", dx, dy);
+            assert_eq!((pixels[0].x, pixels[0].y), (10, 10));
+            assert_eq!((pixels.last().unwrap().x, pixels.last().unwrap().y), (10 + dx, 10 + dy));
+            assert_eq!(pixels[0].t, 0.0);
+            assert_eq!(pixels.last().unwrap().t, 1.0);
+
+            for pair in pixels.windows(2) {
+                // Unit steps along the major axis, at most one along the minor axis
+                let (sx, sy) = (pair[1].x - pair[0].x, pair[1].y - pair[0].y);
+                assert!(sx.abs() <= 1 && sy.abs() <= 1 && (sx != 0 || sy != 0), "({}, {})", dx, dy);
+                assert!(pair[1].t > pair[0].t);
+            }
+            for p in pixels.iter() {
+                // Every pixel lies within half a pixel of the ideal line along the minor axis
+                let (px, py) = ((p.x - 10) as f32, (p.y - 10) as f32);
+                let deviation = if dx.abs() >= dy.abs() { py - px * *dy as f32 / *dx as f32 } else { px - py * *dx as f32 / *dy as f32 };
+                assert!(deviation.abs() <= 0.5, "({}, {}) at ({}, {})", dx, dy, p.x, p.y);
+            }
+
+            // Drawing the line backwards touches the same pixels
+            let mut forward: Vec<(i64, i64)> = pixels.iter().map(|p| (p.x, p.y)).collect();
+            let mut backward: Vec<(i64, i64)> = bresenham(end, center).iter().map(|p| (p.x, p.y)).collect();
+            forward.sort();
+            backward.sort();
+            assert_eq!(forward, backward, "({}, {})", dx, dy);
+        }
+    }
+
+    #[test]
+    fn line_bresenham_axis_aligned() {
+        let horizontal: Vec<(i64, i64)> = bresenham(Vector2d::new(5.5, 2.5), Vector2d::new(1.5, 2.5)).iter().map(|p| (p.x, p.y)).collect();
+        assert_eq!(horizontal, vec![(5, 2), (4, 2), (3, 2), (2, 2), (1, 2)]);
+        let vertical: Vec<(i64, i64)> = bresenham(Vector2d::new(0.5, 0.5), Vector2d::new(0.5, 3.5)).iter().map(|p| (p.x, p.y)).collect();
+        assert_eq!(vertical, vec![(0, 0), (0, 1), (0, 2), (0, 3)]);
+        let diagonal: Vec<(i64, i64)> = bresenham(Vector2d::new(3.5, 0.5), Vector2d::new(0.5, 3.5)).iter().map(|p| (p.x, p.y)).collect();
+        assert_eq!(diagonal.len(), 4);
+        assert!(diagonal.iter().all(|(x, y)| x + y == 3));
+        let point = bresenham(Vector2d::new(2.2, 3.7), Vector2d::new(2.9, 3.1));
+        assert_eq!(point.len(), 1);
+        assert_eq!((point[0].x, point[0].y, point[0].t), (2, 3, 0.0));
+    }
+
+    #[test]
+    fn line_wu_coverage() {
+        // A horizontal line through pixel centers covers one row fully
+        let pixels = wu(Vector2d::new(1.5, 2.5), Vector2d::new(6.5, 2.5));
+        let full: Vec<&_> = pixels.iter().filter(|p| p.coverage > 0.0).collect();
+        assert!(full.iter().all(|p| p.y == 2));
+        assert!(full.iter().filter(|p| p.x > 1 && p.x < 6).all(|p| (p.coverage - 1.0).abs() < 1e-5));
+
+        // Between two rows the coverage is split evenly, and every column sums to one away from the ends
+        let pixels = wu(Vector2d::new(0.5, 3.0), Vector2d::new(10.5, 3.0));
+        for x in 1..10 {
+            let column: Vec<_> = pixels.iter().filter(|p| p.x == x).collect();
+            assert_eq!(column.len(), 2);
+            assert!(column.iter().all(|p| (p.coverage - 0.5).abs() < 1e-5));
+        }
+
+        // Steep lines in every direction
+        for (dx, dy) in [(2.0, 9.0), (-3.0, 8.0), (-2.5, -9.0), (9.0, -4.0)].iter() {
+            let begin = Vector2d::new(12.5, 12.5);
+            let end = Vector2d::new(12.5 + dx, 12.5 + dy);
+            let pixels = wu(begin, end);
+            let steep = f32::abs(*dy) > f32::abs(*dx);
+            let major = |p: &LinePixel| if steep { p.y } else { p.x };
+            let (lo, hi) = (pixels.iter().map(major).min().unwrap(), pixels.iter().map(major).max().unwrap());
+            for a in lo + 1..hi {
+                let sum: f32 = pixels.iter().filter(|p| major(p) == a).map(|p| p.coverage).sum();
+                assert!((sum - 1.0).abs() < 1e-4, "({}, {}) {}", dx, dy, sum);
+            }
+            assert!(pixels.iter().all(|p| p.coverage > 0.0 && p.coverage <= 1.0 && p.t >= 0.0 && p.t <= 1.0));
+        }
+    }
+
+    #[test]
+    fn line_draw_modes() {
+        let white = Vector3d::fill(255.0);
+        let (begin, end) = (Vector3d::new(2.5, 3.5, 0.0), Vector3d::new(27.5, 17.5, 0.0));
+
+        let mut aliased = Rasterizer::new(32, 32);
+        aliased.draw_line(begin, end, white);
+        assert_eq!(lit(&aliased), 26);
+        assert_eq!(aliased.frame_buf[aliased.get_index(2, 3)], white);
+        assert_eq!(aliased.frame_buf[aliased.get_index(27, 17)], white);
+
+        let mut smooth = Rasterizer::new(32, 32);
+        smooth.set_line_mode(LineMode::AntiAliased);
+        smooth.draw_line(begin, end, white);
+        assert!(lit(&smooth) > 26);
+        assert!(smooth.frame_buf.iter().any(|c| c.x > 0.0 && c.x < 255.0));
+        assert!(smooth.frame_buf.iter().zip(smooth.alpha_buf.iter()).all(|(c, a)| (c.x - a).abs() < 1e-3));
+
+        let mut thick = Rasterizer::new(32, 32);
+        thick.set_line_mode(LineMode::Thick(4.0));
+        thick.draw_line(begin, end, white);
+        assert!(lit(&thick) > 3 * 26, "{}", lit(&thick));
+        assert_eq!(thick.frame_buf[thick.get_index(15, 10)], white);
+        assert_eq!(thick.frame_buf[thick.get_index(16, 13)], white);
+        assert_eq!(thick.frame_buf[thick.get_index(15, 16)], Vector3d::fill(0.0));
+        // Screen space lines neither test nor write depth
+        assert!(thick.depth_buf.iter().all(|d| d.is_infinite()));
+
+        // Lines leaving the screen are cut at the border
+        let mut outside = Rasterizer::new(8, 8);
+        outside.draw_line(Vector3d::new(-10.5, 4.5, 0.0), Vector3d::new(20.5, 4.5, 0.0), white);
+        assert_eq!(lit(&outside), 8);
+    }
+
+    #[test]
+    fn line_far_off_screen() {
+        let white = Vector3d::fill(255.0);
+        for mode in [LineMode::Aliased, LineMode::AntiAliased, LineMode::Thick(3.0)].iter() {
+            // An end a billion pixels away is clipped before any pixel is generated, the visible part matches a short line
+            let mut far = Rasterizer::new(16, 16);
+            far.set_line_mode(*mode);
+            far.draw_line(Vector3d::new(4.5, 8.5, 0.0), Vector3d::new(1e9, 8.5, 0.0), white);
+            let mut near = Rasterizer::new(16, 16);
+            near.set_line_mode(*mode);
+            near.draw_line(Vector3d::new(4.5, 8.5, 0.0), Vector3d::new(40.5, 8.5, 0.0), white);
+            assert!(far.frame_buf == near.frame_buf, "{:?}", mode);
+            assert!(lit(&far) >= 12, "{:?}", mode);
+
+            // Lines entirely outside or with non-finite ends draw nothing
+            let mut raster = Rasterizer::new(16, 16);
+            raster.set_line_mode(*mode);
+            raster.draw_line(Vector3d::new(-1e9, -1e9, 0.0), Vector3d::new(1e9, -1e9, 0.0), white);
+            raster.draw_line(Vector3d::new(4.5, 4.5, 0.0), Vector3d::new(f32::INFINITY, 4.5, 0.0), white);
+            raster.draw_line(Vector3d::new(f32::NAN, 4.5, 0.0), Vector3d::new(8.5, 8.5, 0.0), white);
+            assert_eq!(lit(&raster), 0, "{:?}", mode);
+        }
+
+        assert_eq!(clip_segment(Vector2d::new(-10.0, 5.0), Vector2d::new(30.0, 5.0), Vector2d::fill(0.0), Vector2d::fill(10.0)), Some((0.25, 0.5)));
+        assert_eq!(clip_segment(Vector2d::new(-10.0, 5.0), Vector2d::new(-1.0, 50.0), Vector2d::fill(0.0), Vector2d::fill(10.0)), None);
+        assert_eq!(clip_segment(Vector2d::new(1.0, 1.0), Vector2d::new(2.0, 3.0), Vector2d::fill(0.0), Vector2d::fill(10.0)), Some((0.0, 1.0)));
+        // A huge thick line only scans the viewport
+        assert_eq!(thick_clipped(Vector2d::fill(0.0), Vector2d::fill(1e9), 1e9, 4, 3).len(), 12);
+    }
+
+    #[test]
+    fn line_3d_depth_test() {
+        let white = Vector3d::fill(255.0);
+        for mode in [LineMode::Aliased, LineMode::AntiAliased, LineMode::Thick(3.0)].iter() {
+            let mut raster = ndc_rasterizer(32);
+            let pos_id = raster.load_positions(vec![Vector3d::new(-4.0, -4.0, 0.0), Vector3d::new(4.0, -4.0, 0.0), Vector3d::new(0.0, 4.0, 0.0)]);
+            let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+            raster.set_fragment_shader(NormalFragmentShader);
+            raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+            let (depth, frame) = (raster.depth_buf.clone(), raster.frame_buf.clone());
+            raster.set_line_mode(*mode);
+
+            // Behind the triangle
+            raster.draw_line_3d(Vector3d::new(-0.8, -0.5, 0.5), Vector3d::new(0.8, 0.5, 0.5), white);
+            assert!(raster.frame_buf == frame, "{:?}", mode);
+            // In front of the triangle
+            raster.draw_line_3d(Vector3d::new(-0.8, 0.5, -0.5), Vector3d::new(0.8, -0.5, -0.5), white);
+            let changed = raster.frame_buf.iter().zip(frame.iter()).filter(|(a, b)| a != b).count();
+            assert!(changed >= 25, "{:?} {}", mode, changed);
+            // Lines do not write depth
+            assert_eq!(raster.depth_buf, depth);
+        }
+    }
+
+    #[test]
+    fn line_3d_clipped() {
+        let mut raster = ndc_rasterizer(16);
+        // Entirely behind the near plane
+        raster.draw_line_3d(Vector3d::new(-0.5, 0.0, -2.0), Vector3d::new(0.5, 0.0, -3.0), Vector3d::fill(255.0));
+        assert_eq!(lit(&raster), 0);
+        // Crossing the left border and the far plane
+        raster.draw_line_3d(Vector3d::new(-3.0, 0.1, 0.0), Vector3d::new(0.5, 0.1, 1.5), Vector3d::fill(255.0));
+        let count = lit(&raster);
+        assert!(count > 0 && count <= 16, "{}", count);
+
+        let a = Vector4d::new(-2.0, 0.0, 0.0, 1.0);
+        let b = Vector4d::new(2.0, 0.0, 0.0, 1.0);
+        let (t0, t1) = clip_line(a, b, DepthRange::NegativeOneToOne).unwrap();
+        assert!((t0 - 0.25).abs() < 1e-6 && (t1 - 0.75).abs() < 1e-6);
+        assert_eq!(clip_line(a, Vector4d::new(-3.0, 0.0, 0.0, 1.0), DepthRange::NegativeOneToOne), None);
+        // z = -0.5 is in front of the near plane of the [0, 1] depth range
+        let near = Vector4d::new(0.0, 0.0, -0.5, 1.0);
+        assert_eq!(clip_line(near, Vector4d::new(0.0, 0.0, 0.5, 1.0), DepthRange::NegativeOneToOne), Some((0.0, 1.0)));
+        let (t0, _) = clip_line(near, Vector4d::new(0.0, 0.0, 0.5, 1.0), DepthRange::ZeroToOne).unwrap();
+        assert!((t0 - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn line_wireframe_over_mesh() {
+        let mut raster = ndc_rasterizer(32);
+        let pos_id = raster.load_positions(vec![
+            Vector3d::new(-0.8, -0.8, 0.2), Vector3d::new(0.8, -0.8, 0.2), Vector3d::new(0.8, 0.8, -0.2), Vector3d::new(-0.8, 0.8, -0.2),
+        ]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2), Vector3d::new(0, 2, 3)]);
+        raster.set_fragment_shader(NormalFragmentShader);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+
+        // Edges lying on the mesh stay visible, the shared diagonal is drawn once
+        let red = Vector3d::new(255.0, 0.0, 0.0);
+        raster.draw_wireframe(pos_id, ind_id, red).unwrap();
+        let red_pixels = raster.frame_buf.iter().filter(|c| **c == red).count();
+        assert!(red_pixels >= 4 * 24 + 20, "{}", red_pixels);
+        let center = raster.get_index(16, 16);
+        assert_eq!(raster.frame_buf[center], red);
+
+        // A negative bias pushes the edges behind their own triangles
+        raster.clear_frame_buf();
+        raster.set_line_depth_bias(-5.0);
+        raster.draw_wireframe(pos_id, ind_id, red).unwrap();
+        assert_eq!(raster.frame_buf.iter().filter(|c| **c == red).count(), 0);
+
+        let bad = raster.load_indices(vec![Vector3d::new(0, 1, 9)]);
+        assert_eq!(raster.draw_wireframe(pos_id, bad, red).unwrap_err().err_code, 5004);
+    }
+}
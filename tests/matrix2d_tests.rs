@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use QRender::matrix::matrix_errors::MatrixErrorType;
     use QRender::matrix::matrix2d::Matrix2d;
     use QRender::matrix::vector2d::Vector2d;
 
@@ -112,4 +113,45 @@ mod tests {
         assert_eq!(m.items[1].x, 6);
         assert_eq!(m.items[1].y, 6);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn matrix2d_determinant() {
+        let m = Matrix2d::new(Vector2d::new(3, 8), Vector2d::new(4, 6));
+        assert_eq!(m.determinant(), -14);
+        assert_eq!(Matrix2d::identity(1).determinant(), 1);
+    }
+
+    #[test]
+    fn matrix2d_inverse() {
+        let m = Matrix2d::new(Vector2d::new(4.0, 7.0), Vector2d::new(2.0, 6.0));
+        let inv = m.inverse().unwrap();
+        assert!((inv.items[0].x - 0.6_f64).abs() < 1e-12);
+        assert!((inv.items[0].y + 0.7_f64).abs() < 1e-12);
+        assert!((inv.items[1].x + 0.2_f64).abs() < 1e-12);
+        assert!((inv.items[1].y - 0.4_f64).abs() < 1e-12);
+
+        let id = m * inv;
+        assert!((id.items[0].x - 1.0).abs() < 1e-12 && id.items[0].y.abs() < 1e-12);
+        assert!(id.items[1].x.abs() < 1e-12 && (id.items[1].y - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn matrix2d_inverse_singular() {
+        let m = Matrix2d::new(Vector2d::new(1.0, 2.0), Vector2d::new(2.0, 4.0));
+        let err = m.inverse().unwrap_err();
+        assert!(matches!(err.err_type, MatrixErrorType::SingularMatrixError));
+    }
+
+    #[test]
+    fn matrix2d_inverse_nearly_singular() {
+        // The second row is twice the first up to a rounding error, the exact determinant is not zero
+        let m = Matrix2d::new(Vector2d::new(1.0, 2.0), Vector2d::new(2.0, 4.0 + 1e-15_f64));
+        assert!(m.determinant() != 0.0);
+        let err = m.inverse().unwrap_err();
+        assert!(matches!(err.err_type, MatrixErrorType::SingularMatrixError));
+
+        // A small but well conditioned matrix is still inverted
+        let inv = Matrix2d::new(Vector2d::new(1e-6_f32, 0.0), Vector2d::new(0.0, 2e-6)).inverse().unwrap();
+        assert!((inv.items[0].x - 1e6).abs() < 1.0 && (inv.items[1].y - 5e5).abs() < 1.0);
+    }
+}
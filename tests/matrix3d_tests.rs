@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use QRender::matrix::matrix_errors::MatrixErrorType;
     use QRender::matrix::matrix3d::Matrix3d;
     use QRender::matrix::vector3d::Vector3d;
 
@@ -197,4 +198,69 @@ mod tests {
         assert_eq!(m.items[1], m1.items[1].cross(v));
         assert_eq!(m.items[2], m1.items[2].cross(v));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn matrix3d_determinant() {
+        let m = Matrix3d::new(
+            Vector3d::new(2, 0, 1),
+            Vector3d::new(1, 3, 2),
+            Vector3d::new(1, 1, 2)
+        );
+        assert_eq!(m.determinant(), 6);
+        let m = Matrix3d::new(
+            Vector3d::new(1, 2, 3),
+            Vector3d::new(4, 5, 6),
+            Vector3d::new(7, 8, 9)
+        );
+        assert_eq!(m.determinant(), 0);
+    }
+
+    #[test]
+    fn matrix3d_inverse() {
+        let m = Matrix3d::new(
+            Vector3d::new(2.0_f64, 0.0, 1.0),
+            Vector3d::new(1.0, 3.0, 2.0),
+            Vector3d::new(1.0, 1.0, 2.0)
+        );
+        let inv = m.inverse().unwrap();
+        let expect = Matrix3d::new(
+            Vector3d::new(2.0 / 3.0, 1.0 / 6.0, -0.5),
+            Vector3d::new(0.0, 0.5, -0.5),
+            Vector3d::new(-1.0 / 3.0, -1.0 / 3.0, 1.0)
+        );
+        let id = m * inv;
+        for i in 0..3 {
+            let (a, b) = (inv.items[i], expect.items[i]);
+            assert!((a.x - b.x).abs() < 1e-12 && (a.y - b.y).abs() < 1e-12 && (a.z - b.z).abs() < 1e-12);
+            let (a, b) = (id.items[i], Matrix3d::identity(1.0_f64).items[i]);
+            assert!((a.x - b.x).abs() < 1e-12 && (a.y - b.y).abs() < 1e-12 && (a.z - b.z).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn matrix3d_inverse_singular() {
+        let m = Matrix3d::new(
+            Vector3d::new(1.0, 2.0, 3.0),
+            Vector3d::new(4.0, 5.0, 6.0),
+            Vector3d::new(7.0, 8.0, 9.0)
+        );
+        let err = m.inverse().unwrap_err();
+        assert!(matches!(err.err_type, MatrixErrorType::SingularMatrixError));
+    }
+
+    #[test]
+    fn matrix3d_inverse_nearly_singular() {
+        let m = Matrix3d::new(
+            Vector3d::new(1.0, 2.0, 3.0),
+            Vector3d::new(4.0, 5.0, 6.0),
+            Vector3d::new(7.0, 8.0, 9.0 + 1e-14_f64)
+        );
+        assert!(m.determinant() != 0.0);
+        let err = m.inverse().unwrap_err();
+        assert!(matches!(err.err_type, MatrixErrorType::SingularMatrixError));
+
+        // A small but well conditioned matrix is still inverted
+        let inv = Matrix3d::identity(1e-3_f32).inverse().unwrap();
+        assert!((inv.items[2].z - 1e3).abs() < 1e-2 && inv.items[0].y == 0.0);
+    }
+}
@@ -4,6 +4,7 @@ mod tests {
     extern crate test;
     use test::Bencher;
 
+    use QRender::matrix::matrix_errors::MatrixErrorType;
     use QRender::matrix::matrix4d::Matrix4d;
     use QRender::matrix::vector4d::Vector4d;
 
@@ -318,4 +319,76 @@ mod tests {
         );
         assert_eq!(m, m2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn matrix4d_determinant() {
+        let m = Matrix4d::new(
+            Vector4d::new(3, 0, 4, 9),
+            Vector4d::new(7, 1, 3, 8),
+            Vector4d::new(9, 2, 5, 4),
+            Vector4d::new(4, 7, 6, 0)
+        );
+        assert_eq!(m.determinant(), 938);
+        assert_eq!(Matrix4d::identity(2).determinant(), 16);
+        let m = Matrix4d::new(
+            Vector4d::new(2, 1, 0, 3),
+            Vector4d::new(3, 4, 5, 2),
+            Vector4d::new(6, 7, 8, 9),
+            Vector4d::new(4, 3, 2, 1)
+        );
+        assert_eq!(m.determinant(), 0);
+    }
+
+    #[test]
+    fn matrix4d_inverse() {
+        let m = Matrix4d::new(
+            Vector4d::new(3.0, 0.0, 4.0, 9.0),
+            Vector4d::new(7.0, 1.0, 3.0, 8.0),
+            Vector4d::new(9.0, 2.0, 5.0, 4.0),
+            Vector4d::new(4.0, 7.0, 6.0, 0.0)
+        );
+        let inv = m.inverse().unwrap();
+        assert!((inv.items[0].x + 62.0 / 469.0_f64).abs() < 1e-12);
+        assert!((inv.items[1].w - 82.0 / 469.0_f64).abs() < 1e-12);
+        assert!((inv.items[2].y + 411.0 / 938.0_f64).abs() < 1e-12);
+        assert!((inv.items[3].z + 135.0 / 938.0_f64).abs() < 1e-12);
+
+        let id = m * inv;
+        let expect = Matrix4d::identity(1.0_f64);
+        for i in 0..4 {
+            let (a, b) = (id.items[i], expect.items[i]);
+            assert!((a.x - b.x).abs() < 1e-12 && (a.y - b.y).abs() < 1e-12);
+            assert!((a.z - b.z).abs() < 1e-12 && (a.w - b.w).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn matrix4d_inverse_singular() {
+        let m = Matrix4d::new(
+            Vector4d::new(2.0, 1.0, 0.0, 3.0),
+            Vector4d::new(3.0, 4.0, 5.0, 2.0),
+            Vector4d::new(6.0, 7.0, 8.0, 9.0),
+            Vector4d::new(4.0, 3.0, 2.0, 1.0)
+        );
+        let err = m.inverse().unwrap_err();
+        assert!(matches!(err.err_type, MatrixErrorType::SingularMatrixError));
+    }
+
+    #[test]
+    fn matrix4d_inverse_nearly_singular() {
+        // The last row is the sum of the first two plus a rounding error
+        let m = Matrix4d::new(
+            Vector4d::new(2.0, 1.0, 0.0, 3.0),
+            Vector4d::new(3.0, 4.0, 5.0, 2.0),
+            Vector4d::new(6.0, 7.0, 8.0, 9.0),
+            Vector4d::new(5.0, 5.0, 5.0, 5.0 + 1e-6_f32)
+        );
+        assert!(m.determinant() != 0.0);
+        let err = m.inverse().unwrap_err();
+        assert!(matches!(err.err_type, MatrixErrorType::SingularMatrixError));
+
+        // A small but well conditioned matrix is still inverted
+        let inv = Matrix4d::identity(1e-3_f32).inverse().unwrap();
+        assert!((inv.items[3].w - 1e3).abs() < 1e-2 && inv.items[0].w == 0.0);
+    }
+}
@@ -0,0 +1,191 @@
+#[cfg(test)]
+mod tests {
+    use QRender::geometry::mesh::{Aabb, Mesh};
+    use QRender::geometry::obj::ObjModel;
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::matrix::vector4d::Vector4d;
+    use QRender::rasterizer::light::{BlinnPhongFragmentShader, Light, Material};
+    use QRender::rasterizer::rasterizer::Rasterizer;
+    use QRender::rasterizer::shader::NormalFragmentShader;
+
+    fn assert_close(a: Vector3d<f32>, b: Vector3d<f32>) {
+        assert!(a.sub(b).norm() < 1e-5, "{} != {}", a, b);
+    }
+
+    /// The corner of a cube: the quads x = 0, y = 0 and z = 0 meeting at the origin, vertex 0, with normals pointing inside
+    fn corner() -> Mesh {
+        Mesh::new(
+            vec![
+                Vector3d::new(0.0, 0.0, 0.0),
+                Vector3d::new(1.0, 0.0, 0.0), Vector3d::new(0.0, 1.0, 0.0), Vector3d::new(0.0, 0.0, 1.0),
+                Vector3d::new(1.0, 1.0, 0.0), Vector3d::new(0.0, 1.0, 1.0), Vector3d::new(1.0, 0.0, 1.0),
+            ],
+            vec![0, 1, 4, 0, 4, 2, 0, 2, 5, 0, 5, 3, 0, 3, 6, 0, 6, 1],
+        )
+    }
+
+    #[test]
+    fn mesh_smooth_normals() {
+        let mut mesh = corner();
+        mesh.compute_smooth_normals().unwrap();
+        // The three quads weigh the same at the shared corner
+        assert_close(mesh.normals[0], Vector3d::fill(1.0 / 3.0_f32.sqrt()));
+        // Vertex 1 lies on the z = 0 and y = 0 quads, with one triangle of each
+        assert_close(mesh.normals[1], Vector3d::new(0.0, 1.0, 1.0).normalize());
+        // Vertex 4 only belongs to the z = 0 quad
+        assert_close(mesh.normals[4], Vector3d::new(0.0, 0.0, 1.0));
+
+        // Larger faces pull harder
+        let mut wedge = Mesh::new(
+            vec![Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(3.0, 0.0, 0.0), Vector3d::new(0.0, 3.0, 0.0), Vector3d::new(0.0, 0.0, 1.0)],
+            vec![0, 1, 2, 0, 3, 1],
+        );
+        wedge.compute_smooth_normals().unwrap();
+        assert_close(wedge.normals[0], Vector3d::new(0.0, 1.0, 3.0).normalize());
+    }
+
+    #[test]
+    fn mesh_flat_normals() {
+        let mut mesh = corner();
+        mesh.colors = vec![Vector3d::new(1.0, 0.0, 0.0); 7];
+        mesh.colors[4] = Vector3d::new(0.0, 1.0, 0.0);
+        mesh.compute_flat_normals().unwrap();
+        assert_eq!((mesh.vertex_count(), mesh.face_count()), (18, 6));
+        assert_eq!(mesh.indices, (0..18).collect::<Vec<u32>>());
+        assert_close(mesh.normals[0], Vector3d::new(0.0, 0.0, 1.0));
+        assert_close(mesh.normals[17], Vector3d::new(0.0, 1.0, 0.0));
+        // The attributes follow their vertices
+        assert_eq!(mesh.positions[2], Vector3d::new(1.0, 1.0, 0.0));
+        assert_eq!(mesh.colors[2], Vector3d::new(0.0, 1.0, 0.0));
+        assert!(mesh.tex_coords.is_empty() && mesh.tangents.is_empty());
+        mesh.validate().unwrap();
+    }
+
+    #[test]
+    fn mesh_tangents() {
+        // A quad in the XY plane with u along X and v along Y, then the same quad with u mirrored
+        let positions = vec![Vector3d::new(-1.0, -1.0, 0.0), Vector3d::new(1.0, -1.0, 0.0), Vector3d::new(-1.0, 1.0, 0.0), Vector3d::new(1.0, 1.0, 0.0)];
+        let mut mesh = Mesh::new(positions, vec![0, 1, 2, 2, 1, 3]);
+        assert_eq!(mesh.compute_tangents().unwrap_err().err_code, 7004);
+        mesh.compute_smooth_normals().unwrap();
+        assert_eq!(mesh.compute_tangents().unwrap_err().err_code, 7004);
+        mesh.tex_coords = vec![Vector2d::new(0.0, 0.0), Vector2d::new(1.0, 0.0), Vector2d::new(0.0, 1.0), Vector2d::new(1.0, 1.0)];
+        mesh.compute_tangents().unwrap();
+        assert!(mesh.tangents.iter().all(|t| *t == Vector4d::new(1.0, 0.0, 0.0, 1.0)), "{:?}", mesh.tangents);
+
+        mesh.tex_coords = vec![Vector2d::new(1.0, 0.0), Vector2d::new(0.0, 0.0), Vector2d::new(1.0, 1.0), Vector2d::new(0.0, 1.0)];
+        mesh.compute_tangents().unwrap();
+        assert!(mesh.tangents.iter().all(|t| *t == Vector4d::new(-1.0, 0.0, 0.0, -1.0)), "{:?}", mesh.tangents);
+
+        // Tilted normals give tangents orthogonal to them, degenerate coordinates still give a unit tangent
+        mesh.normals = vec![Vector3d::new(1.0, 0.0, 1.0).normalize(); 4];
+        mesh.tex_coords = vec![Vector2d::fill(0.5); 4];
+        mesh.compute_tangents().unwrap();
+        for (t, n) in mesh.tangents.iter().zip(mesh.normals.iter()) {
+            let t3 = Vector3d::new(t.x, t.y, t.z);
+            assert!((t3.norm() - 1.0).abs() < 1e-5 && t3.dot(*n).abs() < 1e-5, "{:?}", t);
+        }
+    }
+
+    #[test]
+    fn mesh_aabb() {
+        let mesh = corner();
+        let aabb = mesh.aabb().unwrap();
+        assert_eq!(aabb, Aabb::new(Vector3d::fill(0.0), Vector3d::fill(1.0)));
+        assert_eq!(aabb.center(), Vector3d::fill(0.5));
+        assert_eq!(aabb.size(), Vector3d::fill(1.0));
+        assert!(aabb.contains(Vector3d::new(1.0, 0.5, 0.0)) && !aabb.contains(Vector3d::new(1.1, 0.5, 0.0)));
+        let other = Aabb::new(Vector3d::new(-2.0, 0.5, 0.5), Vector3d::new(-1.0, 3.0, 0.5));
+        assert_eq!(aabb.union(&other), Aabb::new(Vector3d::new(-2.0, 0.0, 0.0), Vector3d::new(1.0, 3.0, 1.0)));
+        assert!(Mesh::default().aabb().is_none());
+    }
+
+    #[test]
+    fn mesh_faces_and_errors() {
+        let mut mesh = corner();
+        mesh.tex_coords = vec![Vector2d::new(0.25, 0.75); 7];
+        let faces: Vec<_> = mesh.faces().unwrap().collect();
+        assert_eq!(faces.len(), 6);
+        assert_eq!(faces[5].vertex, [Vector3d::fill(0.0), Vector3d::new(1.0, 0.0, 1.0), Vector3d::new(1.0, 0.0, 0.0)]);
+        assert_eq!(faces[5].tex_coords[1], Vector2d::new(0.25, 0.75));
+        assert_eq!(faces[5].normal[1], Vector3d::fill(0.0));
+
+        mesh.indices.push(0);
+        assert_eq!(mesh.validate().unwrap_err().err_code, 7001);
+        assert_eq!(mesh.faces().err().unwrap().err_code, 7001);
+        mesh.indices.extend([1, 7]);
+        let err = mesh.compute_smooth_normals().unwrap_err();
+        assert_eq!((err.err_code, err.to_string().as_str()), (7002, "Index out of range: index: 7, length: 7"));
+        mesh.indices.truncate(18);
+        mesh.colors = vec![Vector3d::fill(1.0); 6];
+        assert_eq!(mesh.compute_flat_normals().unwrap_err().err_code, 7003);
+    }
+
+    #[test]
+    fn mesh_obj_attributes() {
+        // Texture coordinates at (0, 0) and zeroed normals given by the file are kept
+        let model = ObjModel::parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvn 0 0 0\nf 1/1/1 2/1/1 3/1/1\n").unwrap();
+        assert!(model.meshes[0].has_normals && model.meshes[0].has_tex_coords);
+        let mesh = Mesh::from(&model.meshes[0]);
+        assert_eq!(mesh.tex_coords, vec![Vector2d::fill(0.0); 3]);
+        assert_eq!(mesh.normals, vec![Vector3d::fill(0.0); 3]);
+
+        // An attribute missing from some corners counts as missing for the whole mesh
+        let model = ObjModel::parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0.5 0.5\nvn 0 0 1\nf 1/1/1 2//1 3/1\n").unwrap();
+        assert!(!model.meshes[0].has_normals && !model.meshes[0].has_tex_coords);
+        let mesh = Mesh::from(&model.meshes[0]);
+        assert!(mesh.normals.is_empty() && mesh.tex_coords.is_empty());
+    }
+
+    #[test]
+    fn mesh_obj_and_rasterizer() {
+        // The OBJ loader and the rasterizer share the mesh
+        let model = ObjModel::parse("v -1 -1 0\nv 1 -1 0\nv -1 1 0\nv 1 1 0\nf 1 2 4 3\n").unwrap();
+        let mut mesh = Mesh::from(&model.meshes[0]);
+        // The loader numbers the vertices in the order the faces use them
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+        // The file has no normals or texture coordinates
+        assert!(mesh.normals.is_empty() && mesh.tex_coords.is_empty());
+        mesh.compute_smooth_normals().unwrap();
+
+        let mut raster = Rasterizer::new(8, 8);
+        raster.set_model(Matrix4d::identity(1.0));
+        raster.set_view(Matrix4d::identity(1.0));
+        raster.set_projection(Matrix4d::identity(1.0));
+        raster.set_fragment_shader(NormalFragmentShader);
+        let (pos_id, ind_id) = raster.load_mesh(&mesh);
+        assert_eq!(raster.buffer_count(), 3);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+        assert!(raster.frame_buf.iter().all(|c| c.sub(Vector3d::new(127.5, 127.5, 255.0)).norm() < 1e-3));
+
+        // Colors in [0, 1] come back from the rasterizer scaled to [0, 1] again
+        mesh.colors = vec![Vector3d::new(0.5, 0.25, 1.0); 4];
+        let (pos_id, ind_id) = raster.load_mesh(&mesh);
+        let triangles = raster.triangles(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+        assert_eq!(triangles[1].color[2], Vector3d::new(0.5, 0.25, 1.0));
+        assert_eq!(triangles[1].normal[2], Vector3d::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn mesh_obj_without_normals_is_lit() {
+        // A counter-clockwise triangle in the plane z = 0 without vn statements, lit head on along -z
+        let model = ObjModel::parse("v -2 -2 0\nv 2 -2 0\nv 0 2 0\nf 1 2 3\n").unwrap();
+        assert!(!model.meshes[0].has_normals);
+
+        let mut raster = Rasterizer::new(8, 8);
+        raster.set_model(Matrix4d::identity(1.0));
+        raster.set_view(Matrix4d::identity(1.0));
+        raster.set_projection(Matrix4d::identity(1.0));
+        let material = Material::new(Vector3d::fill(0.0), Vector3d::fill(1.0), Vector3d::fill(0.0), 1.0);
+        let light = Light::directional(Vector3d::new(0.0, 0.0, -1.0), Vector3d::fill(1.0));
+        raster.set_fragment_shader(BlinnPhongFragmentShader::new(material, vec![light], Vector3d::new(0.0, 0.0, 10.0)));
+        let (pos_id, ind_id) = raster.load_obj_mesh(&model.meshes[0]);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+
+        // The computed normal faces the light, so the diffuse term is at its maximum
+        let center = raster.frame_buf[raster.get_index(4, 4)];
+        assert!((center.x - 255.0).abs() < 1e-2, "{}", center);
+    }
+}
@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use QRender::geometry::obj::{ObjMaterial, ObjModel};
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::Rasterizer;
+
+    const CUBE_FACE: &str = "
+# one face of a cube
+v -1 -1 1
+v 1 -1 1
+v 1 1 1
+v -1 1 1
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+f 1/1/1 2/2/1 3/3/1 4/4/1
+";
+
+    #[test]
+    fn obj_parse_polygon() {
+        let model = ObjModel::parse(CUBE_FACE).unwrap();
+        assert_eq!(model.meshes.len(), 1);
+        let mesh = &model.meshes[0];
+        assert_eq!(mesh.name, "default");
+        assert_eq!(mesh.material, None);
+        assert_eq!(mesh.indices, vec![Vector3d::new(0, 1, 2), Vector3d::new(0, 2, 3)]);
+        assert_eq!(mesh.positions[2], Vector3d::new(1.0, 1.0, 1.0));
+        assert_eq!(mesh.tex_coords[2], Vector2d::new(1.0, 1.0));
+        assert!(mesh.normals.iter().all(|n| *n == Vector3d::new(0.0, 0.0, 1.0)));
+        assert!(mesh.has_normals && mesh.has_tex_coords);
+    }
+
+    #[test]
+    fn obj_unify_vertices() {
+        // The shared corner 1 has two different normals, so it becomes two vertices
+        let model = ObjModel::parse("
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 0 0 1
+vn 0 0 -1
+vn 0 -1 0
+f 1//1 3//1 2//1
+f 1//2 2//2 4//2
+f 2//2 1//2 4//2
+").unwrap();
+        let mesh = &model.meshes[0];
+        assert_eq!(mesh.positions.len(), 6);
+        assert_eq!(mesh.indices[1], Vector3d::new(3, 4, 5));
+        assert_eq!(mesh.indices[2], Vector3d::new(4, 3, 5));
+        assert_eq!(mesh.normals[0], Vector3d::new(0.0, 0.0, -1.0));
+        assert_eq!(mesh.normals[3], Vector3d::new(0.0, -1.0, 0.0));
+        // Missing texture coordinates default to zero
+        assert_eq!(mesh.tex_coords[0], Vector2d::fill(0.0));
+        assert!(mesh.has_normals && !mesh.has_tex_coords);
+    }
+
+    #[test]
+    fn obj_negative_indices() {
+        let model = ObjModel::parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\nv 5 5 5\nf -4 -3 -1").unwrap();
+        let mesh = &model.meshes[0];
+        assert_eq!(mesh.indices, vec![Vector3d::new(0, 1, 2), Vector3d::new(0, 1, 3)]);
+        assert_eq!(mesh.positions[3], Vector3d::fill(5.0));
+    }
+
+    #[test]
+    fn obj_groups_and_materials() {
+        let model = ObjModel::parse("
+mtllib scene.mtl
+v 0 0 0
+v 1 0 0
+v 0 1 0
+g floor
+usemtl stone
+f 1 2 3
+usemtl wood
+f 1 2 3
+g wall
+f 3 2 1
+g empty
+").unwrap();
+        assert_eq!(model.material_libs, vec!["scene.mtl".to_string()]);
+        let meshes: Vec<(&str, Option<&str>)> = model.meshes.iter()
+            .map(|m| (m.name.as_str(), m.material.as_deref()))
+            .collect();
+        assert_eq!(meshes, vec![("floor", Some("stone")), ("floor", Some("wood")), ("wall", Some("wood"))]);
+        assert!(model.meshes.iter().all(|m| m.positions.len() == 3 && m.indices.len() == 1));
+    }
+
+    #[test]
+    fn obj_parse_errors() {
+        assert_eq!(ObjModel::parse("v 0 0 0\nf 1 2 3").unwrap_err().err_code, 4003);
+        assert_eq!(ObjModel::parse("v 0 0 0\nf 0 1 1").unwrap_err().err_code, 4003);
+        assert_eq!(ObjModel::parse("v 0 0 0\nf 1 1").unwrap_err().err_code, 4004);
+        assert_eq!(ObjModel::parse("v 0 zero 0").unwrap_err().err_code, 4002);
+        assert_eq!(ObjModel::parse("v 0 0").unwrap_err().err_code, 4005);
+
+        let err = ObjModel::parse("v 0 0 0\n\nf 1 1 x").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid number: line 3, x");
+        assert_eq!(ObjModel::load("no/such/file.obj").unwrap_err().err_code, 4001);
+
+        // Load failures propagate with ? like the other error types
+        let load = || -> Result<ObjModel, Box<dyn std::error::Error>> { Ok(ObjModel::load("no/such/file.obj")?) };
+        assert!(load().unwrap_err().to_string().starts_with("Failed to read the file"));
+    }
+
+    #[test]
+    fn obj_parse_mtl() {
+        let materials = ObjMaterial::parse("
+Kd 0 0 0
+newmtl red plastic
+Ka 0.1 0.1 0.1
+Kd 1 0 0
+Ks 0.5 0.5 0.5
+Ns 64
+d 0.5
+map_Kd textures/red.png
+newmtl glass
+Tr 0.75
+").unwrap();
+        assert_eq!(materials.len(), 2);
+        let red = &materials["red plastic"];
+        assert_eq!(red.diffuse, Vector3d::new(1.0, 0.0, 0.0));
+        assert_eq!(red.specular, Vector3d::fill(0.5));
+        assert_eq!(red.shininess, 64.0);
+        assert_eq!(red.dissolve, 0.5);
+        assert_eq!(red.diffuse_map.as_deref(), Some("textures/red.png"));
+        assert_eq!(materials["glass"].dissolve, 0.25);
+        assert_eq!(materials["glass"].diffuse, Vector3d::fill(1.0));
+    }
+
+    #[test]
+    fn obj_load_into_rasterizer() {
+        let dir = std::env::temp_dir().join(format!("qrender_obj_tests_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("quad.mtl"), "newmtl green\nKd 0 1 0\n").unwrap();
+        fs::write(dir.join("quad.obj"), format!("mtllib quad.mtl\nusemtl green\n{}", CUBE_FACE)).unwrap();
+        let model = ObjModel::load(dir.join("quad.obj")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(model.materials["green"].diffuse, Vector3d::new(0.0, 1.0, 0.0));
+        let mesh = &model.meshes[0];
+        assert_eq!(mesh.material.as_deref(), Some("green"));
+
+        let mut raster = Rasterizer::new(10, 10);
+        let (pos_id, ind_id) = raster.load_obj_mesh(mesh);
+        assert_eq!(raster.positions(pos_id).unwrap(), &mesh.positions);
+        assert_eq!(raster.indices(ind_id).unwrap().len(), 3 * mesh.indices.len());
+    }
+}
@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::transform::{self, DepthRange};
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::Rasterizer;
+    use QRender::rasterizer::shader::{DefaultVertexShader, FragmentPayload, FragmentShader, Transforms, VertexOutput, VertexPayload, VertexShader};
+
+    const SIZE: usize = 64;
+    const FOV: f32 = 90.0;
+
+    /// Use the world x and z coordinates as texture coordinates
+    struct FloorShader;
+
+    impl VertexShader for FloorShader {
+        fn shade(&self, vertex: &VertexPayload, transforms: &Transforms) -> VertexOutput {
+            let mut output = DefaultVertexShader.shade(vertex, transforms);
+            output.varyings.tex_coords = Vector2d::new(output.varyings.world_pos.x, output.varyings.world_pos.z);
+            output
+        }
+    }
+
+    /// Black and white squares of one unit
+    struct CheckerShader;
+
+    impl FragmentShader for CheckerShader {
+        fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+            Vector3d::fill(checker(fragment.varyings.tex_coords.x, fragment.varyings.tex_coords.y))
+        }
+    }
+
+    /// Write the world position into the frame buffer
+    struct WorldPosShader;
+
+    impl FragmentShader for WorldPosShader {
+        fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+            fragment.varyings.world_pos
+        }
+    }
+
+    fn checker(u: f32, v: f32) -> f32 {
+        ((u.floor() + v.floor()) as i32).rem_euclid(2) as f32
+    }
+
+    /// A floor at y = -1 stretching away from a camera at the origin which looks down the negative z axis
+    fn floor_scene(fragment_shader: impl FragmentShader + 'static) -> Rasterizer {
+        let mut raster = Rasterizer::new(SIZE, SIZE);
+        let pos_id = raster.load_positions(vec![
+            Vector3d::new(-8.0, -1.0, -1.0),
+            Vector3d::new(8.0, -1.0, -1.0),
+            Vector3d::new(8.0, -1.0, -30.0),
+            Vector3d::new(-8.0, -1.0, -30.0),
+        ]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2), Vector3d::new(0, 2, 3)]);
+        raster.set_model(Matrix4d::identity(1.0));
+        raster.set_view(Matrix4d::identity(1.0));
+        raster.set_projection(transform::perspective(FOV, 1.0, 0.1, 50.0, DepthRange::NegativeOneToOne));
+        raster.set_vertex_shader(FloorShader);
+        raster.set_fragment_shader(fragment_shader);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+        raster
+    }
+
+    /// Where the ray through the center of pixel (x, y) hits the floor
+    fn floor_hit(x: usize, y: usize) -> Vector3d<f32> {
+        let t = (FOV.to_radians() / 2.0).tan();
+        let ndc_x = (x as f32 + 0.5) / SIZE as f32 * 2.0 - 1.0;
+        let ndc_y = (y as f32 + 0.5) / SIZE as f32 * 2.0 - 1.0;
+        let d = Vector3d::new(ndc_x * t, ndc_y * t, -1.0);
+        Vector3d::mul_item(d, -1.0 / d.y)
+    }
+
+    #[test]
+    fn perspective_correct_world_position() {
+        let raster = floor_scene(WorldPosShader);
+        let mut covered = 0;
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let ind = raster.get_index(x, y);
+                if raster.depth_buf[ind].is_infinite() {
+                    continue;
+                }
+                covered += 1;
+                let world = Vector3d::div_item(raster.frame_buf[ind], 255.0);
+                let expected = floor_hit(x, y);
+                let error = world.sub(expected).norm();
+                assert!(error < 1e-3 * expected.norm(), "({}, {}): {} != {}", x, y, world, expected);
+            }
+        }
+        assert!(covered > SIZE * SIZE / 4);
+    }
+
+    #[test]
+    fn perspective_correct_checkerboard() {
+        let raster = floor_scene(CheckerShader);
+        let (mut covered, mut wrong, mut affine_wrong) = (0, 0, 0);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let ind = raster.get_index(x, y);
+                if raster.depth_buf[ind].is_infinite() {
+                    continue;
+                }
+                covered += 1;
+                let hit = floor_hit(x, y);
+                let expected = checker(hit.x, hit.z);
+                if raster.frame_buf[ind].x / 255.0 != expected {
+                    wrong += 1;
+                }
+
+                // The checker that screen space interpolation would produce along the depth of the floor,
+                // the near edge at z = -1 lies on the bottom of the screen and the far edge at z = -30 on row (1 - 1 / 30) * SIZE / 2
+                let s = (y as f32 + 0.5) / ((1.0 - 1.0 / 30.0) * SIZE as f32 / 2.0);
+                let affine_z = -1.0 - 29.0 * s;
+                if checker(hit.x, affine_z) != expected {
+                    affine_wrong += 1;
+                }
+            }
+        }
+        // Only pixels whose center lies within rounding error of a square border may differ
+        assert!(wrong * 50 < covered, "{} of {} pixels differ", wrong, covered);
+        // Interpolating in screen space would have warped a large part of the checkerboard
+        assert!(affine_wrong * 4 > covered, "{} of {} pixels differ", affine_wrong, covered);
+    }
+}
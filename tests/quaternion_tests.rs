@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests {
+    use QRender::matrix::quaternion::Quaternion;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::matrix::vector4d::Vector4d;
+
+    fn assert_vector_eq(a: Vector3d<f32>, b: Vector3d<f32>) {
+        assert!((a.x - b.x).abs() < 1e-5 && (a.y - b.y).abs() < 1e-5 && (a.z - b.z).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    fn assert_rotation_eq(a: Quaternion<f32>, b: Quaternion<f32>) {
+        // q and -q represent the same rotation
+        assert!((a.dot(b).abs() - 1.0).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn quaternion_product() {
+        let i = Quaternion::new(1, 0, 0, 0);
+        let j = Quaternion::new(0, 1, 0, 0);
+        let k = Quaternion::new(0, 0, 1, 0);
+        let minus_one = Quaternion::new(0, 0, 0, -1);
+        assert_eq!(i * j, k);
+        assert_eq!(j * k, i);
+        assert_eq!(k * i, j);
+        assert_eq!(j * i, Quaternion::new(0, 0, -1, 0));
+        assert_eq!(i * i, minus_one);
+        assert_eq!(i * j * k, minus_one);
+
+        let a = Quaternion::new(1, 2, 3, 4);
+        let b = Quaternion::new(5, 6, 7, 8);
+        assert_eq!(a.product(b), Quaternion::new(24, 48, 48, -6));
+    }
+
+    #[test]
+    fn quaternion_conjugate() {
+        let q = Quaternion::new(1, 2, 3, 4);
+        assert_eq!(q.conjugate(), Quaternion::new(-1, -2, -3, 4));
+        assert_eq!(q * q.conjugate(), Quaternion::new(0, 0, 0, 30));
+        assert_eq!(q.norm_squared(), 30);
+    }
+
+    #[test]
+    fn quaternion_inverse() {
+        let q = Quaternion::new(1.0_f32, 2.0, 3.0, 4.0);
+        let id = q * q.inverse().unwrap();
+        assert!((id.w - 1.0).abs() < 1e-6 && id.x.abs() < 1e-6 && id.y.abs() < 1e-6 && id.z.abs() < 1e-6);
+        assert!(Quaternion::new(0.0_f32, 0.0, 0.0, 0.0).inverse().is_none());
+    }
+
+    #[test]
+    fn quaternion_normalized() {
+        let q = Quaternion::new(0.0_f32, 3.0, 0.0, 4.0).normalized();
+        assert_eq!(q, Quaternion::new(0.0, 0.6, 0.0, 0.8));
+        assert!((q.norm() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn quaternion_axis_angle() {
+        let q = Quaternion::from_axis_angle(Vector3d::new(0.0_f32, 0.0, 2.0), 90.0);
+        assert_vector_eq(q.rotate_vector(Vector3d::new(1.0, 0.0, 0.0)), Vector3d::new(0.0, 1.0, 0.0));
+
+        let (axis, angle) = q.to_axis_angle();
+        assert_vector_eq(axis, Vector3d::new(0.0, 0.0, 1.0));
+        assert!((angle - 90.0).abs() < 1e-4);
+
+        let q = Quaternion::from_axis_angle(Vector3d::new(1.0_f32, 1.0, 1.0), 120.0);
+        assert_vector_eq(q.rotate_vector(Vector3d::new(1.0, 0.0, 0.0)), Vector3d::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn quaternion_euler() {
+        let q = Quaternion::from_euler(90.0_f32, 0.0, 0.0);
+        assert_rotation_eq(q, Quaternion::from_axis_angle(Vector3d::new(1.0, 0.0, 0.0), 90.0));
+
+        // X first, then Z
+        let q = Quaternion::from_euler(90.0_f32, 0.0, 90.0);
+        assert_vector_eq(q.rotate_vector(Vector3d::new(0.0, 1.0, 0.0)), Vector3d::new(0.0, 0.0, 1.0));
+        assert_vector_eq(q.rotate_vector(Vector3d::new(1.0, 0.0, 0.0)), Vector3d::new(0.0, 1.0, 0.0));
+
+        let angles = Quaternion::from_euler(10.0_f32, 20.0, 30.0).to_euler();
+        assert_vector_eq(angles, Vector3d::new(10.0, 20.0, 30.0));
+    }
+
+    #[test]
+    fn quaternion_matrix() {
+        let q = Quaternion::from_axis_angle(Vector3d::new(0.3_f32, -0.5, 0.8), 73.0);
+        let m = q.to_matrix3d();
+        let v = Vector3d::new(0.2, 1.5, -0.7);
+        assert_vector_eq(m.product_with_vector3d(v), q.rotate_vector(v));
+
+        let m4 = q.to_matrix4d();
+        assert_eq!(m4.items[3], Vector4d::new(0.0, 0.0, 0.0, 1.0));
+        assert_eq!(m4.product_with_vector4d(v.to_vector4d(1.0)).head3(), m.product_with_vector3d(v));
+
+        assert_rotation_eq(Quaternion::from_matrix3d(m), q);
+        assert_rotation_eq(Quaternion::from_matrix4d(m4), q);
+
+        // Rotations of nearly 180 degrees take the other branches of from_matrix3d
+        for axis in [Vector3d::new(1.0, 0.1, 0.0), Vector3d::new(0.1, 1.0, 0.0), Vector3d::new(0.0, 0.1, 1.0)] {
+            let q = Quaternion::from_axis_angle(axis, 179.0);
+            assert_rotation_eq(Quaternion::from_matrix3d(q.to_matrix3d()), q);
+        }
+    }
+
+    #[test]
+    fn quaternion_slerp() {
+        let a = Quaternion::identity(1.0_f32);
+        let b = Quaternion::from_axis_angle(Vector3d::new(0.0, 1.0, 0.0), 90.0);
+        assert_rotation_eq(a.slerp(b, 0.0), a);
+        assert_rotation_eq(a.slerp(b, 1.0), b);
+        for t in [0.25_f32, 0.5, 0.75] {
+            let q = a.slerp(b, t);
+            assert!((q.norm() - 1.0).abs() < 1e-5);
+            assert_rotation_eq(q, Quaternion::from_axis_angle(Vector3d::new(0.0, 1.0, 0.0), 90.0 * t));
+        }
+
+        // The shortest arc is taken even when the quaternions lie in opposite hemispheres
+        let c = Quaternion::mul_item(b, -1.0);
+        assert_rotation_eq(a.slerp(c, 0.5), Quaternion::from_axis_angle(Vector3d::new(0.0, 1.0, 0.0), 45.0));
+    }
+
+    #[test]
+    fn quaternion_f64() {
+        let q = Quaternion::from_axis_angle(Vector3d::new(0.0_f64, 0.0, 1.0), 90.0);
+        let v = q.rotate_vector(Vector3d::new(1.0, 0.0, 0.0));
+        assert!(v.x.abs() < 1e-12 && (v.y - 1.0).abs() < 1e-12 && v.z.abs() < 1e-12);
+        let half = Quaternion::identity(1.0_f64).slerp(q, 0.5);
+        assert!((half.to_axis_angle().1 - 45.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn quaternion_nlerp() {
+        let a = Quaternion::identity(1.0_f32);
+        let b = Quaternion::from_axis_angle(Vector3d::new(1.0, 0.0, 0.0), 60.0);
+        let q = a.nlerp(b, 0.5);
+        assert!((q.norm() - 1.0).abs() < 1e-6);
+        // The midpoint of nlerp coincides with slerp
+        assert_rotation_eq(q, a.slerp(b, 0.5));
+    }
+}
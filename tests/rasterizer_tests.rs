@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use QRender::geometry::triangle::Triangle;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::{BasicRasterizer, CullMode, FrontFace, Rasterizer};
+    use QRender::rasterizer::shader::{FragmentPayload, FragmentShader};
+
+    fn screen_triangle(a: (f32, f32, f32), b: (f32, f32, f32), c: (f32, f32, f32), color: (f32, f32, f32)) -> Triangle {
+        let mut t = Triangle::from_vertex([
+            Vector3d::new(a.0, a.1, a.2),
+            Vector3d::new(b.0, b.1, b.2),
+            Vector3d::new(c.0, c.1, c.2),
+        ]);
+        for i in 0..3 {
+            t.set_color(i, color.0, color.1, color.2).unwrap();
+        }
+        t
+    }
+
+    fn covered_pixels(raster: &Rasterizer) -> usize {
+        raster.depth_buf.iter().filter(|d| d.is_finite()).count()
+    }
+
+    #[test]
+    fn rasterizer_fill_triangle() {
+        let mut raster = Rasterizer::new(10, 10);
+        let t = screen_triangle((0.0, 0.0, 1.0), (10.0, 0.0, 1.0), (0.0, 10.0, 1.0), (255.0, 0.0, 0.0));
+        raster.rasterize_triangle(&t);
+
+        // 10 + 9 + ... + 1 pixel centers lie strictly inside the half square
+        assert_eq!(covered_pixels(&raster), 45);
+        assert_eq!(raster.frame_buf[raster.get_index(0, 0)], Vector3d::new(255.0, 0.0, 0.0));
+        assert_eq!(raster.frame_buf[raster.get_index(9, 9)], Vector3d::fill(0.0));
+        assert_eq!(raster.depth_buf[raster.get_index(2, 3)], 1.0);
+    }
+
+    #[test]
+    fn rasterizer_fill_clockwise_triangle() {
+        let mut ccw = Rasterizer::new(10, 10);
+        let mut cw = Rasterizer::new(10, 10);
+        ccw.rasterize_triangle(&screen_triangle((1.0, 1.0, 1.0), (9.0, 2.0, 1.0), (4.0, 8.0, 1.0), (0.0, 255.0, 0.0)));
+        cw.rasterize_triangle(&screen_triangle((1.0, 1.0, 1.0), (4.0, 8.0, 1.0), (9.0, 2.0, 1.0), (0.0, 255.0, 0.0)));
+        assert!(covered_pixels(&ccw) > 0);
+        assert_eq!(covered_pixels(&ccw), covered_pixels(&cw));
+        for (a, b) in ccw.depth_buf.iter().zip(cw.depth_buf.iter()) {
+            assert_eq!(a.is_finite(), b.is_finite());
+        }
+    }
+
+    #[test]
+    fn rasterizer_shared_edge_covered_once() {
+        let mut raster = Rasterizer::new(8, 8);
+        // Two triangles splitting the whole screen along the diagonal, which passes through pixel centers
+        raster.rasterize_triangle(&screen_triangle((0.0, 0.0, 1.0), (8.0, 0.0, 1.0), (8.0, 8.0, 1.0), (255.0, 0.0, 0.0)));
+        let first = covered_pixels(&raster);
+        raster.clear_depth_buf();
+        raster.rasterize_triangle(&screen_triangle((0.0, 0.0, 1.0), (8.0, 8.0, 1.0), (0.0, 8.0, 1.0), (255.0, 0.0, 0.0)));
+        let second = covered_pixels(&raster);
+        assert_eq!(first + second, 64);
+    }
+
+    #[test]
+    fn rasterizer_depth_test() {
+        let mut raster = Rasterizer::new(10, 10);
+        let near = screen_triangle((0.0, 0.0, 1.0), (10.0, 0.0, 1.0), (0.0, 10.0, 1.0), (255.0, 0.0, 0.0));
+        let far = screen_triangle((0.0, 0.0, 5.0), (10.0, 0.0, 5.0), (0.0, 10.0, 5.0), (0.0, 0.0, 255.0));
+
+        raster.rasterize_triangle(&near);
+        raster.rasterize_triangle(&far);
+        assert_eq!(raster.frame_buf[raster.get_index(1, 1)], Vector3d::new(255.0, 0.0, 0.0));
+
+        raster.clear_buf();
+        raster.rasterize_triangle(&far);
+        raster.rasterize_triangle(&near);
+        assert_eq!(raster.frame_buf[raster.get_index(1, 1)], Vector3d::new(255.0, 0.0, 0.0));
+        assert_eq!(raster.depth_buf[raster.get_index(1, 1)], 1.0);
+    }
+
+    #[test]
+    fn rasterizer_interpolate_color() {
+        let mut raster = Rasterizer::new(100, 100);
+        let mut t = Triangle::from_vertex([
+            Vector3d::new(0.0, 0.0, 1.0),
+            Vector3d::new(100.0, 0.0, 1.0),
+            Vector3d::new(0.0, 100.0, 1.0),
+        ]);
+        t.set_color(0, 255.0, 0.0, 0.0).unwrap();
+        t.set_color(1, 0.0, 255.0, 0.0).unwrap();
+        t.set_color(2, 0.0, 0.0, 255.0).unwrap();
+        raster.rasterize_triangle(&t);
+
+        let color = raster.frame_buf[raster.get_index(0, 0)];
+        assert!(color.x > 250.0 && color.y < 5.0 && color.z < 5.0);
+        let color = raster.frame_buf[raster.get_index(98, 0)];
+        assert!(color.y > 250.0);
+        let color = raster.frame_buf[raster.get_index(49, 49)];
+        assert!((color.x + color.y + color.z - 255.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn rasterizer_draw_triangle_fills() {
+        let mut raster = Rasterizer::new(100, 100);
+        let pos_id = raster.load_positions(vec![
+            Vector3d::new(2.0, 0.0, -2.0),
+            Vector3d::new(0.0, 2.0, -2.0),
+            Vector3d::new(-2.0, 0.0, -2.0),
+        ]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+        raster.set_model(Rasterizer::model_matrix(Vector3d::new(0.0, 0.0, 1.0), 0.0));
+        raster.set_view(Rasterizer::view_matrix(Vector3d::new(0.0, 0.0, 5.0)));
+        raster.set_projection(Rasterizer::projection_matrix(45.0, 1.0, 0.1, 50.0));
+        raster.draw_triangle(pos_id, ind_id, Vector3d::new(0.0, 255.0, 0.0)).unwrap();
+
+        assert!(covered_pixels(&raster) > 100);
+    }
+
+    /// Count the fragments that reach the fragment shader
+    struct CountingShader {
+        count: Arc<AtomicUsize>,
+    }
+
+    impl FragmentShader for CountingShader {
+        fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+            self.count.fetch_add(1, Ordering::Relaxed);
+            fragment.varyings.color
+        }
+    }
+
+    #[test]
+    fn rasterizer_cull_mode() {
+        let ccw = screen_triangle((1.0, 1.0, 1.0), (9.0, 2.0, 1.0), (4.0, 8.0, 1.0), (0.0, 255.0, 0.0));
+        let cw = screen_triangle((1.0, 1.0, 1.0), (4.0, 8.0, 1.0), (9.0, 2.0, 1.0), (0.0, 255.0, 0.0));
+        let covered = |front_face: FrontFace, cull_mode: CullMode, t: &Triangle| {
+            let mut raster = Rasterizer::new(10, 10);
+            raster.set_front_face(front_face);
+            raster.set_cull_mode(cull_mode);
+            raster.rasterize_triangle(t);
+            covered_pixels(&raster)
+        };
+
+        let full = covered(FrontFace::CounterClockwise, CullMode::None, &ccw);
+        assert!(full > 0);
+        assert_eq!(covered(FrontFace::CounterClockwise, CullMode::None, &cw), full);
+        assert_eq!(covered(FrontFace::CounterClockwise, CullMode::Back, &ccw), full);
+        assert_eq!(covered(FrontFace::CounterClockwise, CullMode::Back, &cw), 0);
+        assert_eq!(covered(FrontFace::CounterClockwise, CullMode::Front, &ccw), 0);
+        assert_eq!(covered(FrontFace::CounterClockwise, CullMode::Front, &cw), full);
+        assert_eq!(covered(FrontFace::Clockwise, CullMode::Back, &ccw), 0);
+        assert_eq!(covered(FrontFace::Clockwise, CullMode::Back, &cw), full);
+    }
+
+    #[test]
+    fn rasterizer_cull_closed_mesh() {
+        // A cube whose faces are counter-clockwise seen from outside
+        let positions = vec![
+            Vector3d::new(-1.0, -1.0, -1.0), Vector3d::new(1.0, -1.0, -1.0), Vector3d::new(1.0, 1.0, -1.0), Vector3d::new(-1.0, 1.0, -1.0),
+            Vector3d::new(-1.0, -1.0, 1.0), Vector3d::new(1.0, -1.0, 1.0), Vector3d::new(1.0, 1.0, 1.0), Vector3d::new(-1.0, 1.0, 1.0),
+        ];
+        let indices = vec![
+            Vector3d::new(4, 5, 6), Vector3d::new(4, 6, 7), Vector3d::new(1, 0, 3), Vector3d::new(1, 3, 2),
+            Vector3d::new(5, 1, 2), Vector3d::new(5, 2, 6), Vector3d::new(0, 4, 7), Vector3d::new(0, 7, 3),
+            Vector3d::new(7, 6, 2), Vector3d::new(7, 2, 3), Vector3d::new(0, 1, 5), Vector3d::new(0, 5, 4),
+        ];
+        let render = |cull_mode: CullMode| {
+            let count = Arc::new(AtomicUsize::new(0));
+            let mut raster = Rasterizer::new(64, 64);
+            let pos_id = raster.load_positions(positions.clone());
+            let ind_id = raster.load_indices(indices.clone());
+            raster.set_model(Rasterizer::model_matrix(Vector3d::new(1.0, 1.0, 0.0), 30.0));
+            raster.set_view(Rasterizer::view_matrix(Vector3d::new(0.0, 0.0, 6.0)));
+            raster.set_projection(Rasterizer::projection_matrix(45.0, 1.0, 0.1, 50.0));
+            raster.set_fragment_shader(CountingShader { count: count.clone() });
+            raster.set_cull_mode(cull_mode);
+            raster.draw_triangle(pos_id, ind_id, Vector3d::new(255.0, 255.0, 255.0)).unwrap();
+            (raster.frame_buf, count.load(Ordering::Relaxed))
+        };
+
+        let (all, all_fragments) = render(CullMode::None);
+        let (back, back_fragments) = render(CullMode::Back);
+        let (front, _) = render(CullMode::Front);
+        assert_eq!(all, back);
+        // Back faces drawn before the front faces covering them no longer reach the fragment shader
+        assert!(back_fragments < all_fragments, "{} of {} fragments", back_fragments, all_fragments);
+        assert_ne!(front, back);
+    }
+}
@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests {
+    use QRender::geometry::triangle::Triangle;
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::matrix::vector4d::Vector4d;
+    use QRender::rasterizer::rasterizer::{IndexBufferId, PositionBufferId, Rasterizer};
+    use QRender::rasterizer::shader::{FragmentPayload, FragmentShader, NormalFragmentShader, Transforms, Varyings, VertexOutput, VertexPayload, VertexShader};
+
+    /// Pass positions through untouched, so that the test can place vertices directly in clip space
+    struct ClipSpaceShader;
+
+    impl VertexShader for ClipSpaceShader {
+        fn shade(&self, vertex: &VertexPayload, _transforms: &Transforms) -> VertexOutput {
+            VertexOutput {
+                position: vertex.position.to_vector4d(1.0),
+                varyings: Varyings {
+                    color: vertex.color,
+                    normal: vertex.normal,
+                    tex_coords: Vector2d::new(vertex.position.x, vertex.position.y),
+                    world_pos: vertex.position,
+                },
+            }
+        }
+    }
+
+    /// A fragment shader with a uniform tint
+    struct TintShader {
+        tint: Vector3d<f32>,
+    }
+
+    impl FragmentShader for TintShader {
+        fn shade(&self, _fragment: &FragmentPayload) -> Vector3d<f32> {
+            self.tint
+        }
+    }
+
+    /// Write the interpolated texture coordinates into the red and green channels
+    struct TexCoordsShader;
+
+    impl FragmentShader for TexCoordsShader {
+        fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+            let uv = fragment.varyings.tex_coords;
+            Vector3d::new(uv.x * 0.5 + 0.5, uv.y * 0.5 + 0.5, 0.0)
+        }
+    }
+
+    fn full_screen(raster: &mut Rasterizer) -> (PositionBufferId, IndexBufferId) {
+        let pos_id = raster.load_positions(vec![
+            Vector3d::new(-1.0, -1.0, 0.0),
+            Vector3d::new(3.0, -1.0, 0.0),
+            Vector3d::new(-1.0, 3.0, 0.0),
+        ]);
+        let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+        (pos_id, ind_id)
+    }
+
+    #[test]
+    fn shader_fragment_uniform() {
+        let mut raster = Rasterizer::new(16, 16);
+        raster.set_vertex_shader(ClipSpaceShader);
+        raster.set_fragment_shader(TintShader { tint: Vector3d::new(1.0, 0.5, 0.0) });
+        let (pos_id, ind_id) = full_screen(&mut raster);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+
+        for color in raster.frame_buf.iter() {
+            assert_eq!(*color, Vector3d::new(255.0, 127.5, 0.0));
+        }
+    }
+
+    #[test]
+    fn shader_varyings_interpolated() {
+        let mut raster = Rasterizer::new(16, 16);
+        raster.set_vertex_shader(ClipSpaceShader);
+        raster.set_fragment_shader(TexCoordsShader);
+        let (pos_id, ind_id) = full_screen(&mut raster);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+
+        // The texture coordinates equal the normalized device coordinates of the pixel centers
+        for (x, y) in [(0, 0), (5, 9), (15, 15)] {
+            let color = raster.frame_buf[raster.get_index(x, y)];
+            assert!((color.x - (x as f32 + 0.5) / 16.0 * 255.0).abs() < 1e-2);
+            assert!((color.y - (y as f32 + 0.5) / 16.0 * 255.0).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn shader_default_vertex_shader_transforms() {
+        let mut raster = Rasterizer::new(16, 16);
+        // Shrink the full screen triangle to the lower left quarter with the model matrix
+        raster.set_model(Matrix4d::new(
+            Vector4d::new(0.5, 0.0, 0.0, -0.5),
+            Vector4d::new(0.0, 0.5, 0.0, -0.5),
+            Vector4d::new(0.0, 0.0, 1.0, 0.0),
+            Vector4d::new(0.0, 0.0, 0.0, 1.0),
+        ));
+        raster.set_view(Matrix4d::identity(1.0));
+        raster.set_projection(Matrix4d::identity(1.0));
+        raster.set_fragment_shader(TintShader { tint: Vector3d::fill(1.0) });
+        let (pos_id, ind_id) = full_screen(&mut raster);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(0.0)).unwrap();
+
+        assert_eq!(raster.frame_buf[raster.get_index(3, 3)], Vector3d::fill(255.0));
+        assert_eq!(raster.frame_buf[raster.get_index(7, 7)], Vector3d::fill(255.0));
+        assert_eq!(raster.frame_buf[raster.get_index(8, 8)], Vector3d::fill(0.0));
+        assert_eq!(raster.frame_buf[raster.get_index(12, 3)], Vector3d::fill(0.0));
+    }
+
+    #[test]
+    fn shader_normal_fragment_shader() {
+        let mut raster = Rasterizer::new(8, 8);
+        raster.set_fragment_shader(NormalFragmentShader);
+        let mut t = Triangle::from_vertex([
+            Vector3d::new(0.0, 0.0, 1.0),
+            Vector3d::new(16.0, 0.0, 1.0),
+            Vector3d::new(0.0, 16.0, 1.0),
+        ]);
+        for i in 0..3 {
+            t.set_normal(i, Vector3d::new(0.0, 0.0, 2.0)).unwrap();
+        }
+        raster.rasterize_triangle(&t);
+        assert_eq!(raster.frame_buf[raster.get_index(2, 2)], Vector3d::new(127.5, 127.5, 255.0));
+    }
+
+    #[test]
+    fn shader_varyings_interpolate() {
+        let v = Varyings {
+            color: Vector3d::fill(0.0),
+            normal: Vector3d::fill(0.0),
+            tex_coords: Vector2d::fill(0.0),
+            world_pos: Vector3d::fill(0.0),
+        };
+        let mut a = v;
+        a.color = Vector3d::new(1.0, 0.0, 0.0);
+        let mut b = v;
+        b.tex_coords = Vector2d::new(1.0, 1.0);
+        let mut c = v;
+        c.world_pos = Vector3d::new(0.0, 0.0, 4.0);
+
+        let res = Varyings::interpolate(&[a, b, c], 0.5, 0.25, 0.25);
+        assert_eq!(res.color, Vector3d::new(0.5, 0.0, 0.0));
+        assert_eq!(res.tex_coords, Vector2d::new(0.25, 0.25));
+        assert_eq!(res.world_pos, Vector3d::new(0.0, 0.0, 1.0));
+    }
+}
@@ -0,0 +1,172 @@
+#[cfg(test)]
+mod tests {
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::transform::{self, DepthRange};
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::light::{BlinnPhongFragmentShader, Light, Material};
+    use QRender::rasterizer::rasterizer::{AntiAliasing, Rasterizer};
+    use QRender::rasterizer::shader::{DefaultVertexShader, Transforms, VertexOutput, VertexPayload, VertexShader};
+    use QRender::rasterizer::shadow::ShadowMap;
+
+    /// Give every vertex of the floor a normal pointing upwards
+    struct FloorShader;
+
+    impl VertexShader for FloorShader {
+        fn shade(&self, vertex: &VertexPayload, transforms: &Transforms) -> VertexOutput {
+            let mut output = DefaultVertexShader.shade(vertex, transforms);
+            output.varyings.normal = Vector3d::new(0.0, 1.0, 0.0);
+            output
+        }
+    }
+
+    /// A floor of 8 x 8 units at y = 0 and a square roof of 2 x 2 units at y = 1 above its center
+    fn scene() -> (Vec<Vector3d<f32>>, Vec<Vector3d<i32>>) {
+        let positions = vec![
+            Vector3d::new(-4.0, 0.0, 4.0),
+            Vector3d::new(4.0, 0.0, 4.0),
+            Vector3d::new(4.0, 0.0, -4.0),
+            Vector3d::new(-4.0, 0.0, -4.0),
+            Vector3d::new(-1.0, 1.0, 1.0),
+            Vector3d::new(1.0, 1.0, 1.0),
+            Vector3d::new(1.0, 1.0, -1.0),
+            Vector3d::new(-1.0, 1.0, -1.0),
+        ];
+        let indices = vec![Vector3d::new(0, 1, 2), Vector3d::new(0, 2, 3), Vector3d::new(4, 5, 6), Vector3d::new(4, 6, 7)];
+        (positions, indices)
+    }
+
+    fn render_shadow_map(light: &Light, size: usize) -> ShadowMap {
+        let mut pass = ShadowMap::depth_pass(light, Vector3d::fill(0.0), 6.0, size).unwrap();
+        let (positions, indices) = scene();
+        let pos_id = pass.load_positions(positions);
+        let ind_id = pass.load_indices(indices);
+        pass.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+        ShadowMap::from_depth_pass(&pass)
+    }
+
+    #[test]
+    fn shadow_light_transforms() {
+        let center = Vector3d::new(1.0, 2.0, 3.0);
+        let sun = Light::directional(Vector3d::new(1.0, -1.0, 0.0), Vector3d::fill(1.0));
+        let spot = Light::spot(Vector3d::new(1.0, 8.0, 3.0), Vector3d::new(0.0, -1.0, 0.0), Vector3d::fill(1.0), 20.0, 30.0);
+        for light in [sun, spot].iter() {
+            let (view, projection) = ShadowMap::light_transforms(light, center, 2.0, DepthRange::NegativeOneToOne).unwrap();
+            let clip = (projection * view).product_with_vector4d(center.to_vector4d(1.0));
+            // The center of the scene lies in the middle of the shadow map, inside the depth range
+            assert!((clip.x / clip.w).abs() < 1e-4 && (clip.y / clip.w).abs() < 1e-4, "{:?}", clip);
+            assert!((clip.z / clip.w).abs() < 1.0);
+        }
+
+        let point = Light::point(Vector3d::fill(0.0), Vector3d::fill(1.0));
+        assert!(ShadowMap::light_transforms(&point, center, 2.0, DepthRange::NegativeOneToOne).is_none());
+        assert!(ShadowMap::depth_pass(&point, center, 2.0, 16).is_none());
+    }
+
+    #[test]
+    fn shadow_depth_pass_skips_shading() {
+        let sun = Light::directional(Vector3d::new(0.0, -1.0, 0.0), Vector3d::fill(1.0));
+        let mut pass = ShadowMap::depth_pass(&sun, Vector3d::fill(0.0), 6.0, 32).unwrap();
+        let (positions, indices) = scene();
+        let pos_id = pass.load_positions(positions);
+        let ind_id = pass.load_indices(indices);
+        pass.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+
+        assert!(pass.frame_buf.iter().all(|c| *c == Vector3d::fill(0.0)));
+        assert!(pass.depth_buf.iter().any(|d| d.is_finite()));
+    }
+
+    #[test]
+    fn shadow_directional_light() {
+        let sun = Light::directional(Vector3d::new(0.0, -1.0, 0.0), Vector3d::fill(1.0));
+        let mut shadow = render_shadow_map(&sun, 128);
+        shadow.set_pcf_radius(0);
+
+        assert_eq!(shadow.visibility(Vector3d::new(0.0, 0.0, 0.0), 1.0), 0.0);
+        assert_eq!(shadow.visibility(Vector3d::new(0.5, 0.0, -0.5), 1.0), 0.0);
+        // The roof itself and the floor around it are lit without shadow acne
+        assert_eq!(shadow.visibility(Vector3d::new(0.0, 1.0, 0.0), 1.0), 1.0);
+        for x in [-3.5_f32, -2.0, 1.5, 3.5].iter() {
+            for z in [-3.5_f32, -1.5, 2.0, 3.5].iter() {
+                assert_eq!(shadow.visibility(Vector3d::new(*x, 0.0, *z), 1.0), 1.0, "({}, {})", x, z);
+            }
+        }
+        // Far outside the map everything is lit
+        assert_eq!(shadow.visibility(Vector3d::new(50.0, 0.0, 0.0), 1.0), 1.0);
+    }
+
+    #[test]
+    fn shadow_slanted_directional_light_moves_shadow() {
+        // Light travelling towards +x shifts the shadow of the roof by one unit
+        let sun = Light::directional(Vector3d::new(1.0, -1.0, 0.0), Vector3d::fill(1.0));
+        let mut shadow = render_shadow_map(&sun, 128);
+        shadow.set_pcf_radius(0);
+
+        assert_eq!(shadow.visibility(Vector3d::new(1.5, 0.0, 0.0), 1.0), 0.0);
+        assert_eq!(shadow.visibility(Vector3d::new(-0.5, 0.0, 0.0), 1.0), 1.0);
+    }
+
+    #[test]
+    fn shadow_spot_light() {
+        let spot = Light::spot(Vector3d::new(0.0, 4.0, 0.0), Vector3d::new(0.0, -1.0, 0.0), Vector3d::fill(1.0), 50.0, 60.0);
+        let mut shadow = render_shadow_map(&spot, 128);
+        shadow.set_pcf_radius(0);
+
+        // The roof at a quarter of the height of the light casts a shadow 4 / 3 times its size
+        assert_eq!(shadow.visibility(Vector3d::new(1.2, 0.0, 0.0), 1.0), 0.0);
+        assert_eq!(shadow.visibility(Vector3d::new(0.0, 0.0, -1.2), 1.0), 0.0);
+        assert_eq!(shadow.visibility(Vector3d::new(1.5, 0.0, 0.0), 1.0), 1.0);
+        assert_eq!(shadow.visibility(Vector3d::new(-3.0, 0.0, 2.0), 1.0), 1.0);
+    }
+
+    #[test]
+    fn shadow_pcf_softens_edges() {
+        let sun = Light::directional(Vector3d::new(0.0, -1.0, 0.0), Vector3d::fill(1.0));
+        let mut shadow = render_shadow_map(&sun, 64);
+        shadow.set_pcf_radius(2);
+
+        assert_eq!(shadow.visibility(Vector3d::new(0.0, 0.0, 0.0), 1.0), 0.0);
+        assert_eq!(shadow.visibility(Vector3d::new(3.0, 0.0, 0.0), 1.0), 1.0);
+        let edge = shadow.visibility(Vector3d::new(1.0, 0.0, 0.0), 1.0);
+        assert!(edge > 0.0 && edge < 1.0, "{}", edge);
+
+        // Without a bias the floor under a slanted light shadows itself
+        let sun = Light::directional(Vector3d::new(1.0, -1.0, 0.0), Vector3d::fill(1.0));
+        let mut shadow = render_shadow_map(&sun, 64);
+        shadow.set_pcf_radius(0);
+        let floor: Vec<Vector3d<f32>> = (0..40).map(|i| Vector3d::new(-3.9 + i as f32 * 0.05, 0.0, 3.0)).collect();
+        let n_dot_l = 0.5_f32.sqrt();
+        shadow.set_bias(0.0, 0.0);
+        let acne = floor.iter().filter(|p| shadow.visibility(**p, n_dot_l) < 1.0).count();
+        shadow.set_bias(0.2, 1.0);
+        let lit = floor.iter().filter(|p| shadow.visibility(**p, n_dot_l) == 1.0).count();
+        assert!(acne > 0, "{}", acne);
+        assert_eq!(lit, floor.len());
+    }
+
+    #[test]
+    fn shadow_main_pass() {
+        let sun = Light::directional(Vector3d::new(0.0, -1.0, 0.0), Vector3d::fill(1.0));
+        let eye = Vector3d::new(0.0, 10.0, 0.01);
+        let mut shader = BlinnPhongFragmentShader::new(Material::default(), vec![sun], eye);
+        shader.set_shadow_map(0, render_shadow_map(&sun, 128));
+
+        // Look down at the floor from above the roof
+        let mut raster = Rasterizer::new(64, 64);
+        raster.set_anti_aliasing(AntiAliasing::Msaa4);
+        let (positions, indices) = scene();
+        let pos_id = raster.load_positions(positions[..4].to_vec());
+        let ind_id = raster.load_indices(indices[..2].to_vec());
+        raster.set_model(Matrix4d::identity(1.0));
+        raster.set_view(transform::look_at(eye, Vector3d::fill(0.0), Vector3d::new(0.0, 0.0, -1.0)));
+        raster.set_projection(transform::ortho(-4.0, 4.0, -4.0, 4.0, 0.1, 20.0, DepthRange::NegativeOneToOne));
+        raster.set_vertex_shader(FloorShader);
+        raster.set_fragment_shader(shader);
+        raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+
+        // Only the ambient light reaches the floor under the roof
+        let shadowed = raster.frame_buf[raster.get_index(32, 32)];
+        let lit = raster.frame_buf[raster.get_index(4, 4)];
+        assert!((shadowed.x - 0.1 * 255.0).abs() < 1e-3, "{}", shadowed);
+        assert!(lit.x > 200.0, "{}", lit);
+    }
+}
@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, Rgb, RgbImage, Rgba, RgbaImage};
+    use QRender::geometry::triangle::Triangle;
+    use QRender::matrix::vector2d::Vector2d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::matrix::vector4d::Vector4d;
+    use QRender::rasterizer::rasterizer::Rasterizer;
+    use QRender::rasterizer::texture::{FilterMode, Texture, TextureFragmentShader, WrapMode};
+
+    fn red() -> Vector3d<f32> {
+        Vector3d::new(1.0, 0.0, 0.0)
+    }
+
+    /// A 2x2 texture, red and green on the top row, blue and white on the bottom row
+    fn quad_texture() -> Texture {
+        let colors = [[255, 0, 0], [0, 255, 0], [0, 0, 255], [255, 255, 255]];
+        let image = RgbImage::from_fn(2, 2, |x, y| Rgb(colors[(y * 2 + x) as usize]));
+        Texture::from_image(&DynamicImage::ImageRgb8(image)).unwrap()
+    }
+
+    fn assert_color_eq(a: Vector3d<f32>, b: Vector3d<f32>) {
+        assert!((a.x - b.x).abs() < 1e-5 && (a.y - b.y).abs() < 1e-5 && (a.z - b.z).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn texture_from_image() {
+        let texture = quad_texture();
+        assert_eq!((texture.width, texture.height), (2, 2));
+        assert_eq!(texture.filter, FilterMode::Bilinear);
+        assert_eq!(texture.wrap, WrapMode::Repeat);
+        assert_eq!(texture.texel(1, 1).head3(), Vector3d::fill(1.0));
+
+        let image = RgbaImage::from_pixel(1, 1, Rgba([0, 0, 0, 51]));
+        let texture = Texture::from_image(&DynamicImage::ImageRgba8(image)).unwrap();
+        assert!((texture.texel(0, 0).w - 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn texture_sample_nearest() {
+        let mut texture = quad_texture();
+        texture.set_filter(FilterMode::Nearest);
+        // v grows upwards, so the top row of the image is at v close to 1
+        assert_color_eq(texture.sample(Vector2d::new(0.25, 0.75)), red());
+        assert_color_eq(texture.sample(Vector2d::new(0.75, 0.75)), Vector3d::new(0.0, 1.0, 0.0));
+        assert_color_eq(texture.sample(Vector2d::new(0.25, 0.25)), Vector3d::new(0.0, 0.0, 1.0));
+        assert_color_eq(texture.sample(Vector2d::new(0.99, 0.01)), Vector3d::fill(1.0));
+    }
+
+    #[test]
+    fn texture_sample_bilinear() {
+        let texture = quad_texture();
+        // Texel centers return the texel itself
+        assert_color_eq(texture.sample(Vector2d::new(0.25, 0.75)), red());
+        // Half way between red and green
+        assert_color_eq(texture.sample(Vector2d::new(0.5, 0.75)), Vector3d::new(0.5, 0.5, 0.0));
+        // The center blends all four texels equally
+        assert_color_eq(texture.sample(Vector2d::new(0.5, 0.5)), Vector3d::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn texture_wrap_modes() {
+        let mut texture = quad_texture();
+        texture.set_filter(FilterMode::Nearest);
+        let outside = Vector2d::new(1.25, 0.75);
+
+        texture.set_wrap(WrapMode::Repeat);
+        assert_color_eq(texture.sample(outside), red());
+        assert_color_eq(texture.sample(Vector2d::new(-0.75, 0.75)), red());
+
+        texture.set_wrap(WrapMode::ClampToEdge);
+        assert_color_eq(texture.sample(outside), Vector3d::new(0.0, 1.0, 0.0));
+        assert_color_eq(texture.sample(Vector2d::new(-5.0, 0.75)), red());
+
+        texture.set_wrap(WrapMode::MirroredRepeat);
+        assert_color_eq(texture.sample(outside), Vector3d::new(0.0, 1.0, 0.0));
+        assert_color_eq(texture.sample(Vector2d::new(1.75, 0.75)), red());
+        assert_color_eq(texture.sample(Vector2d::new(2.25, 0.75)), red());
+
+        // Bilinear filtering at the border blends with the wrapped neighbour
+        texture.set_filter(FilterMode::Bilinear);
+        texture.set_wrap(WrapMode::Repeat);
+        assert_color_eq(texture.sample(Vector2d::new(0.0, 0.75)), Vector3d::new(0.5, 0.5, 0.0));
+        texture.set_wrap(WrapMode::ClampToEdge);
+        assert_color_eq(texture.sample(Vector2d::new(0.0, 0.75)), red());
+    }
+
+    #[test]
+    fn texture_invalid_input() {
+        // An image without pixels cannot become a texture
+        let empty = DynamicImage::ImageRgb8(RgbImage::new(0, 0));
+        assert!(matches!(Texture::from_image(&empty).unwrap_err(), image::ImageError::Parameter(_)));
+        assert!(Texture::from_image(&DynamicImage::ImageRgb8(RgbImage::new(4, 0))).is_err());
+
+        // Non-finite coordinates give transparent black, huge ones still land on a texel
+        let mut texture = quad_texture();
+        for wrap in [WrapMode::Repeat, WrapMode::ClampToEdge, WrapMode::MirroredRepeat].iter() {
+            texture.set_wrap(*wrap);
+            for filter in [FilterMode::Nearest, FilterMode::Bilinear].iter() {
+                texture.set_filter(*filter);
+                assert_eq!(texture.sample_rgba(Vector2d::new(f32::NAN, 0.5)), Vector4d::fill(0.0));
+                assert_eq!(texture.sample_rgba(Vector2d::new(0.5, f32::INFINITY)), Vector4d::fill(0.0));
+                assert_eq!(texture.sample_rgba(Vector2d::new(1e30, -1e30)).w, 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn texture_fragment_shader() {
+        let mut texture = quad_texture();
+        texture.set_filter(FilterMode::Nearest);
+        let mut raster = Rasterizer::new(10, 10);
+        raster.set_fragment_shader(TextureFragmentShader::new(texture));
+
+        let mut t = Triangle::from_vertex([
+            Vector3d::new(0.0, 0.0, 1.0),
+            Vector3d::new(10.0, 0.0, 1.0),
+            Vector3d::new(0.0, 10.0, 1.0),
+        ]);
+        t.set_tex_coords(0, Vector2d::new(0.0, 0.0)).unwrap();
+        t.set_tex_coords(1, Vector2d::new(1.0, 0.0)).unwrap();
+        t.set_tex_coords(2, Vector2d::new(0.0, 1.0)).unwrap();
+        raster.rasterize_triangle(&t);
+
+        // The bottom left of the screen shows the bottom left texel, which is blue
+        assert_eq!(raster.frame_buf[raster.get_index(1, 1)], Vector3d::new(0.0, 0.0, 255.0));
+        assert_eq!(raster.frame_buf[raster.get_index(1, 7)], Vector3d::new(255.0, 0.0, 0.0));
+        assert_eq!(raster.frame_buf[raster.get_index(8, 0)], Vector3d::new(255.0, 255.0, 255.0));
+    }
+}
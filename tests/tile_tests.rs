@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use QRender::matrix::transform::{self, DepthRange};
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::{AntiAliasing, CullMode, Rasterizer};
+    use QRender::rasterizer::shader::{FragmentPayload, FragmentShader, NormalFragmentShader};
+
+    /// Count the fragment shader invocations
+    struct CountingShader {
+        count: Arc<AtomicUsize>,
+    }
+
+    impl FragmentShader for CountingShader {
+        fn shade(&self, fragment: &FragmentPayload) -> Vector3d<f32> {
+            self.count.fetch_add(1, Ordering::Relaxed);
+            fragment.varyings.color
+        }
+    }
+
+    /// A deterministic cloud of overlapping triangles at different depths, some crossing the screen border and the near plane
+    fn triangles() -> (Vec<Vector3d<f32>>, Vec<Vector3d<i32>>) {
+        let mut seed = 12345_u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 8) as f32 / (1 << 24) as f32
+        };
+        let mut positions = Vec::new();
+        let mut indices = Vec::new();
+        for i in 0..60 {
+            let center = Vector3d::new(random() * 8.0 - 4.0, random() * 8.0 - 4.0, -random() * 10.0);
+            for _ in 0..3 {
+                positions.push(center.add(Vector3d::new(random() * 4.0 - 2.0, random() * 4.0 - 2.0, random() * 2.0 - 1.0)));
+            }
+            indices.push(Vector3d::new(3 * i, 3 * i + 1, 3 * i + 2));
+        }
+        (positions, indices)
+    }
+
+    fn render(threads: usize, tile_size: usize, setup: impl Fn(&mut Rasterizer)) -> Rasterizer {
+        let mut raster = Rasterizer::new(100, 70);
+        raster.set_threads(threads, tile_size);
+        setup(&mut raster);
+        let (positions, indices) = triangles();
+        let pos_id = raster.load_positions(positions);
+        let ind_id = raster.load_indices(indices);
+        raster.set_model(transform::rotate_y(20.0));
+        raster.set_view(transform::look_at(Vector3d::new(0.0, 0.0, 3.0), Vector3d::fill(0.0), Vector3d::new(0.0, 1.0, 0.0)));
+        raster.set_projection(transform::perspective(60.0, 100.0 / 70.0, 0.1, 50.0, DepthRange::NegativeOneToOne));
+        raster.draw_triangle(pos_id, ind_id, Vector3d::new(255.0, 128.0, 64.0)).unwrap();
+        raster
+    }
+
+    fn assert_same(a: &Rasterizer, b: &Rasterizer) {
+        assert!(a.frame_buf == b.frame_buf, "frame buffers differ");
+        assert!(a.depth_buf.iter().zip(b.depth_buf.iter()).all(|(x, y)| x.to_bits() == y.to_bits()), "depth buffers differ");
+    }
+
+    #[test]
+    fn tile_matches_single_thread() {
+        let reference = render(1, 64, |_| {});
+        let covered = reference.depth_buf.iter().filter(|d| d.is_finite()).count();
+        assert!(covered > 100 * 70 / 4, "{}", covered);
+        for (threads, tile_size) in [(2, 64), (4, 16), (8, 7), (3, 1), (16, 200)].iter() {
+            assert_same(&reference, &render(*threads, *tile_size, |_| {}));
+        }
+    }
+
+    #[test]
+    fn tile_matches_with_state() {
+        let setups: [fn(&mut Rasterizer); 4] = [
+            |r| r.set_anti_aliasing(AntiAliasing::Msaa4),
+            |r| r.set_anti_aliasing(AntiAliasing::Ssaa2),
+            |r| r.set_cull_mode(CullMode::Back),
+            |r| r.set_depth_only(true),
+        ];
+        for setup in setups.iter() {
+            assert_same(&render(1, 32, setup), &render(4, 32, setup));
+        }
+        let normals = |r: &mut Rasterizer| r.set_fragment_shader(NormalFragmentShader);
+        assert_same(&render(1, 32, normals), &render(4, 13, normals));
+    }
+
+    #[test]
+    fn tile_keeps_previous_content() {
+        // A second draw call in tiled mode is depth tested against the first one
+        let mut single = render(1, 32, |_| {});
+        let mut tiled = render(6, 32, |_| {});
+        for raster in [&mut single, &mut tiled] {
+            let pos_id = raster.load_positions(vec![Vector3d::new(-3.0, -3.0, -4.0), Vector3d::new(3.0, -3.0, -4.0), Vector3d::new(0.0, 3.0, -4.0)]);
+            let ind_id = raster.load_indices(vec![Vector3d::new(0, 1, 2)]);
+            raster.draw_triangle(pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+        }
+        assert_same(&single, &tiled);
+    }
+
+    #[test]
+    fn tile_shades_every_fragment_once() {
+        let invocations = |threads: usize| {
+            let count = Arc::new(AtomicUsize::new(0));
+            let raster = render(threads, 16, |r| r.set_fragment_shader(CountingShader { count: count.clone() }));
+            (count.load(Ordering::Relaxed), raster)
+        };
+        let (single, a) = invocations(1);
+        let (tiled, b) = invocations(8);
+        assert_eq!(single, tiled);
+        assert_same(&a, &b);
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use QRender::matrix::matrix4d::Matrix4d;
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::rasterizer::rasterizer::{CullMode, PositionBufferId, Primitive, PrimitiveTopology, Rasterizer, PRIMITIVE_RESTART};
+
+    fn ndc_rasterizer(size: usize) -> Rasterizer {
+        let mut raster = Rasterizer::new(size, size);
+        raster.set_model(Matrix4d::identity(1.0));
+        raster.set_view(Matrix4d::identity(1.0));
+        raster.set_projection(Matrix4d::identity(1.0));
+        raster
+    }
+
+    fn lit(raster: &Rasterizer) -> usize {
+        raster.frame_buf.iter().filter(|c| **c != Vector3d::fill(0.0)).count()
+    }
+
+    /// A grid of 4 x 2 vertices covering the screen, vertex i at column i % 4 and row i / 4
+    fn grid(raster: &mut Rasterizer) -> PositionBufferId {
+        let positions = (0..8).map(|i| Vector3d::new((i % 4) as f32 * 2.0 / 3.0 - 1.0, (i / 4) as f32 * 2.0 - 1.0, 0.0)).collect();
+        raster.load_positions(positions)
+    }
+
+    #[test]
+    fn topology_assemble() {
+        let r = PRIMITIVE_RESTART;
+        assert_eq!(PrimitiveTopology::PointList.assemble(&[3, r, 4]), vec![vec![3], vec![4]]);
+        assert_eq!(PrimitiveTopology::LineList.assemble(&[0, 1, 2, r, 3, 4, 5]), vec![vec![0, 1], vec![3, 4]]);
+        assert_eq!(PrimitiveTopology::LineStrip.assemble(&[0, 1, 2, r, 3, 4]), vec![vec![0, 1], vec![1, 2], vec![3, 4]]);
+        assert_eq!(PrimitiveTopology::TriangleList.assemble(&[0, 1, 2, 3, r, 4, 5, 6]), vec![vec![0, 1, 2], vec![4, 5, 6]]);
+        assert_eq!(
+            PrimitiveTopology::TriangleStrip.assemble(&[0, 1, 2, 3, 4]),
+            vec![vec![0, 1, 2], vec![2, 1, 3], vec![2, 3, 4]]
+        );
+        assert_eq!(
+            PrimitiveTopology::TriangleFan.assemble(&[0, 1, 2, 3, r, r, 4, 5, 6]),
+            vec![vec![0, 1, 2], vec![0, 2, 3], vec![4, 5, 6]]
+        );
+        assert!(PrimitiveTopology::TriangleFan.assemble(&[0, 1]).is_empty());
+        assert_eq!(PrimitiveTopology::LineStrip.primitive(), Primitive::Line);
+        assert_eq!(PrimitiveTopology::PointList.primitive(), Primitive::Point);
+        assert_eq!(PrimitiveTopology::TriangleFan.primitive(), Primitive::Triangle);
+    }
+
+    #[test]
+    fn topology_strip_matches_list() {
+        let render = |topology: PrimitiveTopology, indices: Vec<i32>| {
+            let mut raster = ndc_rasterizer(24);
+            // The winding of every strip triangle matches the first, so back face culling keeps all of them
+            raster.set_cull_mode(CullMode::Back);
+            let pos_id = grid(&mut raster);
+            let ind_id = raster.load_index_list(indices);
+            raster.draw(topology, pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+            raster
+        };
+        let list = render(PrimitiveTopology::TriangleList, vec![0, 1, 4, 4, 1, 5, 1, 2, 5, 5, 2, 6, 2, 3, 6, 6, 3, 7]);
+        let strip = render(PrimitiveTopology::TriangleStrip, vec![4, 0, 5, 1, 6, 2, 7, 3]);
+        let restarted = render(PrimitiveTopology::TriangleStrip, vec![4, 0, 5, 1, PRIMITIVE_RESTART, 5, 1, 6, 2, PRIMITIVE_RESTART, 6, 2, 7, 3]);
+        // The strips split the quads along the other diagonal, which only changes the rounding of the interpolation
+        let same = |a: &Rasterizer, b: &Rasterizer| {
+            a.frame_buf.iter().zip(b.frame_buf.iter()).all(|(x, y)| x.sub(*y).norm() < 1e-2)
+                && a.depth_buf.iter().zip(b.depth_buf.iter()).all(|(x, y)| (x - y).abs() < 1e-3)
+        };
+        assert_eq!(lit(&list), 24 * 24);
+        assert!(same(&list, &strip) && same(&list, &restarted));
+
+        // A fan around vertex 0 covering the lower left triangle of the screen and the upper right one
+        let fan = render(PrimitiveTopology::TriangleFan, vec![0, 1, 2, 3, 7]);
+        assert!(lit(&fan) > 24 * 24 / 2 && lit(&fan) < 24 * 24, "{}", lit(&fan));
+    }
+
+    #[test]
+    fn topology_lines() {
+        let mut raster = ndc_rasterizer(24);
+        // The corners of a square from pixel 3 to pixel 21
+        let pos_id = raster.load_positions(vec![
+            Vector3d::new(-0.75, -0.75, 0.0), Vector3d::new(0.75, -0.75, 0.0), Vector3d::new(0.75, 0.75, 0.0), Vector3d::new(-0.75, 0.75, 0.0),
+        ]);
+        // The bottom and the top edge as separate lines
+        let list_id = raster.load_index_list(vec![0, 1, 3, 2]);
+        raster.draw(PrimitiveTopology::LineList, pos_id, list_id, Vector3d::fill(255.0)).unwrap();
+        assert_eq!(lit(&raster), 2 * 19);
+        assert!(raster.depth_buf.iter().all(|d| d.is_infinite()));
+
+        // A polyline along the border
+        raster.clear_buf();
+        let strip_id = raster.load_index_list(vec![0, 1, 2, 3, 0]);
+        raster.draw(PrimitiveTopology::LineStrip, pos_id, strip_id, Vector3d::fill(255.0)).unwrap();
+        assert_eq!(lit(&raster), 4 * 18);
+
+        // The colors of the ends are interpolated along the line
+        raster.clear_buf();
+        let color_id = raster.load_colors((0..4).map(|i| if i == 0 { Vector3d::new(255.0, 0.0, 0.0) } else { Vector3d::new(0.0, 0.0, 255.0) }).collect());
+        raster.bind_attributes(pos_id, color_id).unwrap();
+        raster.draw(PrimitiveTopology::LineList, pos_id, list_id, Vector3d::fill(0.0)).unwrap();
+        let left = raster.frame_buf[raster.get_index(3, 3)];
+        let right = raster.frame_buf[raster.get_index(21, 3)];
+        assert!(left.x > 200.0 && left.z < 55.0, "{}", left);
+        assert!(right.x < 55.0 && right.z > 200.0, "{}", right);
+    }
+
+    #[test]
+    fn topology_points() {
+        let mut raster = ndc_rasterizer(24);
+        let pos_id = raster.load_positions(vec![Vector3d::new(-0.5, -0.5, 0.0), Vector3d::new(0.5, 0.5, 0.0), Vector3d::new(0.0, 0.0, 2.0)]);
+        let ind_id = raster.load_index_list(vec![0, 1, 2]);
+        raster.draw(PrimitiveTopology::PointList, pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+        // The third point lies beyond the far plane
+        assert_eq!(lit(&raster), 2);
+        assert_eq!(raster.frame_buf[raster.get_index(6, 6)], Vector3d::fill(255.0));
+        assert_eq!(raster.frame_buf[raster.get_index(18, 18)], Vector3d::fill(255.0));
+
+        raster.clear_buf();
+        raster.set_point_size(4.0);
+        raster.draw(PrimitiveTopology::PointList, pos_id, ind_id, Vector3d::fill(255.0)).unwrap();
+        assert_eq!(lit(&raster), 2 * 12);
+    }
+
+    #[test]
+    fn topology_invalid_indices() {
+        let mut raster = ndc_rasterizer(24);
+        let pos_id = grid(&mut raster);
+        // The primitives before the invalid index are drawn
+        let ind_id = raster.load_index_list(vec![0, 3, 3, 8]);
+        assert_eq!(raster.draw(PrimitiveTopology::LineStrip, pos_id, ind_id, Vector3d::fill(255.0)).unwrap_err().err_code, 5004);
+        assert_eq!(lit(&raster), 24);
+
+        // Negative indices other than PRIMITIVE_RESTART are out of range
+        let ind_id = raster.load_index_list(vec![0, 1, -2]);
+        assert_eq!(raster.draw(PrimitiveTopology::TriangleStrip, pos_id, ind_id, Vector3d::fill(255.0)).unwrap_err().err_code, 5004);
+        assert_eq!(raster.draw(PrimitiveTopology::PointList, pos_id, ind_id, Vector3d::fill(255.0)).unwrap_err().err_code, 5004);
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use QRender::matrix::quaternion::Quaternion;
+    use QRender::matrix::transform::{self, DepthRange};
+    use QRender::matrix::vector3d::Vector3d;
+    use QRender::matrix::vector4d::Vector4d;
+    use QRender::matrix::matrix4d::Matrix4d;
+
+    fn apply(m: Matrix4d<f32>, p: Vector3d<f32>) -> Vector3d<f32> {
+        let v = m.product_with_vector4d(p.to_vector4d(1.0));
+        Vector3d::new(v.x / v.w, v.y / v.w, v.z / v.w)
+    }
+
+    fn assert_vector_eq(a: Vector3d<f32>, b: Vector3d<f32>) {
+        assert!((a.x - b.x).abs() < 1e-5 && (a.y - b.y).abs() < 1e-5 && (a.z - b.z).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn transform_translate_scale() {
+        let p = Vector3d::new(1.0, 2.0, 3.0);
+        assert_vector_eq(apply(transform::translate(Vector3d::new(1.0, -2.0, 0.5)), p), Vector3d::new(2.0, 0.0, 3.5));
+        assert_vector_eq(apply(transform::scale(Vector3d::new(2.0, -1.0, 0.5)), p), Vector3d::new(2.0, -2.0, 1.5));
+
+        // Translations do not move directions
+        let d = transform::translate(Vector3d::new(1.0, 1.0, 1.0)).product_with_vector4d(Vector4d::new(1.0, 0.0, 0.0, 0.0));
+        assert_eq!(d, Vector4d::new(1.0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn transform_rotate_axes() {
+        let x = Vector3d::new(1.0, 0.0, 0.0);
+        let y = Vector3d::new(0.0, 1.0, 0.0);
+        let z = Vector3d::new(0.0, 0.0, 1.0);
+        assert_vector_eq(apply(transform::rotate_x(90.0), y), z);
+        assert_vector_eq(apply(transform::rotate_y(90.0), z), x);
+        assert_vector_eq(apply(transform::rotate_z(90.0), x), y);
+
+        let m = transform::rotate_x(90.0);
+        assert!((m.determinant() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn transform_rotate_arbitrary_axis() {
+        let axis = Vector3d::new(1.0, 2.0, 3.0);
+        let p = Vector3d::new(-2.0, 0.5, 4.0);
+        let q = Quaternion::from_axis_angle(axis, 70.0);
+        assert_vector_eq(apply(transform::rotate(axis, 70.0), p), q.rotate_vector(p));
+
+        // Rotating around a coordinate axis matches the dedicated builder, regardless of the axis length
+        assert_vector_eq(apply(transform::rotate(Vector3d::new(0.0, 0.0, 3.0), 30.0), p), apply(transform::rotate_z(30.0), p));
+    }
+
+    #[test]
+    fn transform_look_at() {
+        let eye = Vector3d::new(3.0, 4.0, 5.0);
+        let target = Vector3d::new(1.0, 0.0, -1.0);
+        let view = transform::look_at(eye, target, Vector3d::new(0.0, 1.0, 0.0));
+
+        assert_vector_eq(apply(view, eye), Vector3d::fill(0.0));
+        let distance = target.sub(eye).norm();
+        assert_vector_eq(apply(view, target), Vector3d::new(0.0, 0.0, -distance));
+
+        // A point above the target stays above it on screen
+        let above = apply(view, Vector3d::new(1.0, 1.0, -1.0));
+        assert!(above.y > 0.0 && above.x.abs() < 1e-5);
+    }
+
+    #[test]
+    fn transform_ortho() {
+        let m = transform::ortho(-2.0, 4.0, -1.0, 3.0, 1.0, 11.0, DepthRange::NegativeOneToOne);
+        assert_vector_eq(apply(m, Vector3d::new(-2.0, -1.0, -1.0)), Vector3d::new(-1.0, -1.0, -1.0));
+        assert_vector_eq(apply(m, Vector3d::new(4.0, 3.0, -11.0)), Vector3d::new(1.0, 1.0, 1.0));
+
+        let m = transform::ortho(-2.0, 4.0, -1.0, 3.0, 1.0, 11.0, DepthRange::ZeroToOne);
+        assert_vector_eq(apply(m, Vector3d::new(-2.0, -1.0, -1.0)), Vector3d::new(-1.0, -1.0, 0.0));
+        assert_vector_eq(apply(m, Vector3d::new(4.0, 3.0, -11.0)), Vector3d::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn transform_perspective() {
+        let m = transform::perspective(90.0, 2.0, 1.0, 10.0, DepthRange::NegativeOneToOne);
+        // The corners of the near and far planes
+        assert_vector_eq(apply(m, Vector3d::new(2.0, 1.0, -1.0)), Vector3d::new(1.0, 1.0, -1.0));
+        assert_vector_eq(apply(m, Vector3d::new(-20.0, -10.0, -10.0)), Vector3d::new(-1.0, -1.0, 1.0));
+
+        let m = transform::perspective(90.0, 2.0, 1.0, 10.0, DepthRange::ZeroToOne);
+        assert_vector_eq(apply(m, Vector3d::new(2.0, 1.0, -1.0)), Vector3d::new(1.0, 1.0, 0.0));
+        assert_vector_eq(apply(m, Vector3d::new(-20.0, -10.0, -10.0)), Vector3d::new(-1.0, -1.0, 1.0));
+
+        // w is the distance in front of the camera and the depth grows with it
+        let near = m.product_with_vector4d(Vector4d::new(0.0, 0.0, -2.0, 1.0));
+        let far = m.product_with_vector4d(Vector4d::new(0.0, 0.0, -5.0, 1.0));
+        assert!((near.w - 2.0).abs() < 1e-5 && (far.w - 5.0).abs() < 1e-5);
+        assert!(near.z / near.w < far.z / far.w);
+    }
+}
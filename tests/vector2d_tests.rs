@@ -92,10 +92,25 @@ mod tests {
 
     #[test]
     fn vector2d_normalized() {
-        let mut v = Vector2d::new(3.0_f32, 5.0_f32);
+        let mut v = Vector2d::new(3.0_f32, 4.0_f32);
         v.normalized();
-        assert_eq!(v.x, 0.08823529411764706);
-        assert_eq!(v.y, 0.14705882352941177);
+        assert_eq!(v.x, 0.6);
+        assert_eq!(v.y, 0.8);
+        assert!((v.norm() - 1.0).abs() < 1e-6);
+
+        let mut v = Vector2d::new(0.0_f32, 0.0_f32);
+        v.normalized();
+        assert_eq!(v, Vector2d::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn vector2d_norm() {
+        let v = Vector2d::new(3.0_f64, 4.0_f64);
+        assert_eq!(v.length_squared(), 25.0);
+        assert_eq!(v.norm(), 5.0);
+        assert_eq!(v.normalize(), Vector2d::new(0.6, 0.8));
+        assert_eq!(v, Vector2d::new(3.0, 4.0));
+        assert_eq!(Vector2d::new(3, 4).length_squared(), 25);
     }
 
     #[test]
@@ -97,9 +97,24 @@ mod tests {
     fn vector3d_normalized() {
         let mut v = Vector3d::new(3.0_f32, 5.0_f32, 2.0_f32);
         v.normalized();
-        assert_eq!(v.x, 0.07894736842105263);
-        assert_eq!(v.y, 0.13157894736842105);
-        assert_eq!(v.z, 0.05263157894736842);
+        assert!((v.x - 3.0 / 38.0_f32.sqrt()).abs() < 1e-6);
+        assert!((v.y - 5.0 / 38.0_f32.sqrt()).abs() < 1e-6);
+        assert!((v.z - 2.0 / 38.0_f32.sqrt()).abs() < 1e-6);
+        assert!((v.norm() - 1.0).abs() < 1e-6);
+
+        let mut v = Vector3d::fill(0.0_f32);
+        v.normalized();
+        assert_eq!(v, Vector3d::fill(0.0));
+    }
+
+    #[test]
+    fn vector3d_norm() {
+        let v = Vector3d::new(2.0_f64, 3.0_f64, 6.0_f64);
+        assert_eq!(v.length_squared(), 49.0);
+        assert_eq!(v.norm(), 7.0);
+        assert_eq!(v.normalize(), Vector3d::new(2.0 / 7.0, 3.0 / 7.0, 6.0 / 7.0));
+        assert_eq!(v, Vector3d::new(2.0, 3.0, 6.0));
+        assert_eq!(Vector3d::new(2, 3, 6).length_squared(), 49);
     }
 
     #[test]
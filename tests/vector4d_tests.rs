@@ -93,12 +93,26 @@ mod tests {
 
     #[test]
     fn vector4d_normalized() {
+        let mut v = Vector4d::new(1.0_f32, 1.0_f32, 1.0_f32, 1.0_f32);
+        v.normalized();
+        assert_eq!(v.x, 0.5);
+        assert_eq!(v.y, 0.5);
+        assert_eq!(v.z, 0.5);
+        assert_eq!(v.w, 0.5);
+
         let mut v = Vector4d::new(3.0_f32, 5.0_f32, 2.0_f32, 6.0_f32);
         v.normalized();
-        assert_eq!(v.x, 0.04054054054054054);
-        assert_eq!(v.y, 0.06756756756756757);
-        assert_eq!(v.z, 0.02702702702702703);
-        assert_eq!(v.w, 0.08108108108108109);
+        assert!((v.norm() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn vector4d_norm() {
+        let v = Vector4d::new(1.0_f64, 2.0_f64, 2.0_f64, 4.0_f64);
+        assert_eq!(v.length_squared(), 25.0);
+        assert_eq!(v.norm(), 5.0);
+        assert_eq!(v.normalize(), Vector4d::new(0.2, 0.4, 0.4, 0.8));
+        assert_eq!(Vector4d::fill(0.0_f64).normalize(), Vector4d::fill(0.0));
+        assert_eq!(Vector4d::new(1, 2, 2, 4).length_squared(), 25);
     }
 
     #[test]